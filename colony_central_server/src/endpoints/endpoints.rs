@@ -8,35 +8,112 @@ use std::path::PathBuf;
 
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use actix_web::{web, HttpResponse};
+use actix_web::{web, http::header, HttpRequest, HttpResponse};
 
-use plugin_interface_elements::{elements_v1, TargetSystem, VersionedRequest, VersionedResponse};
+use plugin_interface_elements::{elements_v1, TargetSystem, VersionedRequest, VersionedResponse,
+                                WireEncoding, GZIP_THRESHOLD_BYTES, accepts_gzip, gzip_compress};
 
+use crate::server::{LocalJobQueue, QueuedJob, CancellationRegistry, CancellationToken, process_local_job_queue, exe_dir,
+                    list_directory_cancellable, copy_file_cancellable, move_file_cancellable, download_cancellable};
 
 
 
 
+
+//################################################################################
+//## Content negotiation
+//################################################################################
+
+fn header_str<'a>(http_request: &'a HttpRequest, name: header::HeaderName) -> Option<&'a str> {
+     return http_request.headers().get(name).and_then(|value| value.to_str().ok());
+}
+
+// encodes the response as requested via Accept, and compresses it if it is large and Accept-Encoding allows gzip
+fn encoded_response(http_request: &HttpRequest, response: &VersionedResponse) -> HttpResponse {
+     let encoding = header_str(http_request, header::ACCEPT)
+                         .map(WireEncoding::from_accept_header)
+                         .unwrap_or_default();
+
+     let body = match encoding.encode(response) {
+          Ok(body) => body,
+          Err(e) => return HttpResponse::InternalServerError().body(format!("{:?}", &e)),
+     };
+
+     let client_accepts_gzip = header_str(http_request, header::ACCEPT_ENCODING).is_some_and(accepts_gzip);
+     if client_accepts_gzip && body.len() > GZIP_THRESHOLD_BYTES {
+          if let Ok(compressed) = gzip_compress(&body) {
+               return HttpResponse::Ok()
+                         .content_type(encoding.content_type())
+                         .insert_header((header::CONTENT_ENCODING, "gzip"))
+                         .body(compressed);
+          }
+     }
+     return HttpResponse::Ok().content_type(encoding.content_type()).body(body);
+}
+
+
+
+//################################################################################
+//## API
+//################################################################################
+
 #[actix_web::get("/api")]
-pub async fn api_endpoint(bodydata: web::Json<VersionedRequest>,
+pub async fn api_endpoint(http_request: HttpRequest,
+                          bodydata: web::Bytes,
                           //local_message_db: web::Data<HardTypedDBAccess>,
                           local_job_queue: web::Data<LocalJobQueue>,
-                          remote_process_store: web::Data<RemoteProcessStore>)  -> Result<HttpResponse, actix_web::Error> {
-     let binding = &(*bodydata);
+                          cancellation_registry: web::Data<CancellationRegistry>)  -> Result<HttpResponse, actix_web::Error> {
+     // requests without a Content-Type are treated as JSON, as they were before other encodings were supported
+     let request_encoding = match header_str(&http_request, header::CONTENT_TYPE) {
+          Some(content_type) => match WireEncoding::from_content_type(content_type) {
+               Some(encoding) => encoding,
+               None => return Ok(HttpResponse::UnsupportedMediaType().finish()),
+          },
+          None => WireEncoding::Json,
+     };
+
+     // actix has already undone any Content-Encoding of the request body
+     let binding: VersionedRequest = match request_encoding.decode(&bodydata) {
+          Ok(request) => request,
+          Err(e) => return Ok(HttpResponse::BadRequest().body(format!("{:?}", &e))),
+     };
+
      match binding {
-          &VersionedRequest::ApiV1(TargetSystem::LocalMachine,ref request) => {
+          VersionedRequest::ApiV1(TargetSystem::LocalMachine, request_id, elements_v1::TaskRequest::PluginTaskRequest(ref request)) => {
 
                // TODO: log raw request
                // local_message_db.log_request()
-               let response_body = api_endpoint_localmachine_logic_v1(request);
-               return HttpResponse::Ok().json(response_body);
+               let response_body = api_endpoint_localmachine_logic_v1(&request_id, request, &local_job_queue, &cancellation_registry).await;
+               let response = VersionedResponse::ApiV1(TargetSystem::LocalMachine, request_id, response_body);
+               return Ok(encoded_response(&http_request, &response));
           },
-          &VersionedRequest::ApiV1(TargetSystem::RemoteMachine(_), _) => {
-               return HttpResponse::UnprocessableEntity().finish();
+          VersionedRequest::ApiV1(_, _, _) => {
+               return Ok(HttpResponse::UnprocessableEntity().finish());
           },
      }
 }
 
-pub fn api_endpoint_localmachine_logic_v1(bodydata: &elements_v1::PluginTaskRequest)  -> elements_v1::PluginTaskResponse {
+// file system operations block, so they run on actix's thread pool for blocking work instead of a worker thread;
+// the request is marked finished there as well, even if the client has disconnected in the meantime
+async fn run_cancellable<T: Send + 'static>(request_id: elements_v1::RequestId,
+                                            cancellation_registry: &web::Data<CancellationRegistry>,
+                                            operation: impl FnOnce(&CancellationToken) -> Result<T, elements_v1::RemoteOperationError> + Send + 'static)
+                                            -> Result<T, elements_v1::RemoteOperationError> {
+     let token = cancellation_registry.register_in_flight(request_id);
+     let registry = cancellation_registry.clone();
+     let result = web::block(move || {
+          let result = operation(&token);
+          registry.mark_finished(&request_id);
+          result
+     }).await;
+     return result.map_err(|e| elements_v1::RemoteOperationError::InternalFailure(format!("{:?}", &e)))
+                  .and_then(|result| result);
+}
+
+pub async fn api_endpoint_localmachine_logic_v1(request_id: &elements_v1::RequestId,
+                                          bodydata: &elements_v1::PluginTaskRequest,
+                                          local_job_queue: &web::Data<LocalJobQueue>,
+                                          cancellation_registry: &web::Data<CancellationRegistry>)  -> elements_v1::PluginTaskResponse {
      match bodydata {
           elements_v1::PluginTaskRequest::AddServerAccess(addserveraccess_data) => {
                let response_data = elements_v1::AddServerAccessResponse {
@@ -87,18 +164,8 @@ pub fn api_endpoint_localmachine_logic_v1(bodydata: &elements_v1::PluginTaskRequ
                return response_body;
           },
           elements_v1::PluginTaskRequest::ListDirectory(listdirectory_data) => {
-               let read_result = std::fs::read_dir(&listdirectory_data.directory);
-
-               let content = match read_result {
-                    Ok(entries) => {
-                         entries.into_iter().filter_map(|res| res.map(|entry| {
-                              elements_v1::FsElement::try_from(entry.path()).ok()
-                         } ))
-                    },
-                    Err(e) => {
-                         Err(RemoteOperationError::InternalFailure(format!("{:?}", &e)))
-                    }
-               };
+               let directory = listdirectory_data.directory.clone();
+               let content = run_cancellable(*request_id, cancellation_registry, move |token| list_directory_cancellable(&directory, token)).await;
 
                let response_data = elements_v1::ListDirectoryResponse { content };
                let response_body = elements_v1::PluginTaskResponse::ListDirectory(response_data);
@@ -109,37 +176,166 @@ pub fn api_endpoint_localmachine_logic_v1(bodydata: &elements_v1::PluginTaskRequ
           elements_v1::PluginTaskRequest::MoveFile(movefile_data) => {
                // TODO: log Job creation
 
-               let elements_v1::MoveFile { source, target } = *movefile_data;
-               match std::fs::rename(source, target) {
-                    Ok(()) => {
-                         // TODO: log job completion
-                         let response_data = elements_v1::MoveFileResponse { destination: Ok(target) };
-                         let response_body = elements_v1::PluginTaskResponse::MoveFile(response_data);
-                         return response_body;
+               let elements_v1::MoveFile { source, target } = movefile_data.clone();
+               let destination = run_cancellable(*request_id, cancellation_registry, move |token| move_file_cancellable(&source, &target, token)).await;
+
+               // TODO: log job completion
+               let response_data = elements_v1::MoveFileResponse { destination };
+               let response_body = elements_v1::PluginTaskResponse::MoveFile(response_data);
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::CopyFile(copyfile_data) => {
+               let elements_v1::CopyFile { source, target } = copyfile_data.clone();
+               let destination = run_cancellable(*request_id, cancellation_registry, move |token| copy_file_cancellable(&source, &target, token)).await;
+
+               let response_data = elements_v1::CopyFileResponse { destination };
+               let response_body = elements_v1::PluginTaskResponse::CopyFile(response_data);
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::DeleteFile(deletefile_data) => {
+               let response_data = elements_v1::DeleteFileResponse {
+                    success: Err(elements_v1::RemoteOperationError::NotSupported)
+               };
+               let response_body = elements_v1::PluginTaskResponse::DeleteFile(response_data);
+
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::ShowFileMetadata(showfilemetadata_data) => {
+               let response_data = elements_v1::ShowFileMetadataResponse {
+                    meta: Err(elements_v1::RemoteOperationError::NotSupported)
+               };
+               let response_body = elements_v1::PluginTaskResponse::ShowFileMetadata(response_data);
+
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::DownloadData(downloaddata_data) => {
+               let download_id = elements_v1::DownloadId { inner: Uuid::new_v4() };
+               let id = match exe_dir() {
+                    Some(dir) => {
+                         let download_dir = dir.join("downloads");
+                         match std::fs::create_dir_all(&download_dir) {
+                              Ok(()) => {
+                                   let destination = download_dir.join(download_id.inner.to_string());
+                                   let token = cancellation_registry.register_in_flight(*request_id);
+                                   let registry = cancellation_registry.clone();
+                                   let request = downloaddata_data.clone();
+                                   let request_id = *request_id;
+
+                                   // downloads outlive the request, the client cancels them by RequestId
+                                   actix_web::rt::spawn(async move {
+                                        let result = download_cancellable(&request, &destination, &token).await;
+                                        println!("Download {:?} finished: {:?}", &download_id, &result);
+                                        registry.mark_finished(&request_id);
+                                   });
+                                   Ok(download_id)
+                              },
+                              Err(e) => Err(elements_v1::RemoteOperationError::InternalFailure(format!("{:?}", &e))),
+                         }
                     },
-                    // TODO handle errors that will make copy fail as well here already? Or wait for copy to fail?
-                    Err(_e) => {
-                         todo!()
-                         // Step2 create entry in database
+                    None => Err(elements_v1::RemoteOperationError::InternalFailure("No directory available for downloads".to_string())),
+               };
 
-                         // Step3 schedule subprocess
-                         // step3b  have subprocess enter job completion and add completion message to queue
-                    }
+               let response_data = elements_v1::DownloadDataResponse { id };
+               let response_body = elements_v1::PluginTaskResponse::DownloadData(response_data);
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::RunSingularityJob(runsingularityjob_data) => {
+               let job_id = elements_v1::JobId::new();
+               let cancellation = cancellation_registry.register_queued(*request_id);
+               local_job_queue.enqueue(QueuedJob {
+                    job_id,
+                    request_id: *request_id,
+                    specification: runsingularityjob_data.specification.clone(),
+                    upstream: Vec::new(),
+                    cancellation,
+               });
+
+               if local_job_queue.claim_worker() {
+                    let queue = local_job_queue.clone().into_inner();
+                    let registry = cancellation_registry.clone().into_inner();
+                    std::thread::spawn(move || process_local_job_queue(&queue, &registry));
                }
+
+               let response_data = elements_v1::RunSingularityJobResponse { success: Ok(job_id) };
+               let response_body = elements_v1::PluginTaskResponse::RunSingularityJob(response_data);
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::ShowSingularityJobLogs(showsingularityjoblogs_data) => {
+               let response_data = elements_v1::ShowSingularityJobLogsResponse {
+                    logs: Err(elements_v1::RemoteOperationError::NotSupported)
+               };
+               let response_body = elements_v1::PluginTaskResponse::ShowSingularityJobLogs(response_data);
+
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::ShowSingularityJobsRunning(showsingularityjobsrunning_data) => {
+               let response_data = elements_v1::ShowSingularityJobsRunningResponse {
+                    running_jobs: Err(elements_v1::RemoteOperationError::NotSupported)
+               };
+               let response_body = elements_v1::PluginTaskResponse::ShowSingularityJobsRunning(response_data);
+
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::EnqueueMultipleJobs(enqueuemultiplejobs_data) => {
+               let job_ids = enqueuemultiplejobs_data.execution_order().map(|order| {
+                    let job_ids: Vec<elements_v1::JobId> = enqueuemultiplejobs_data.jobs.iter().map(|_| elements_v1::JobId::new()).collect();
+                    // all jobs of the pipeline share one token, cancelling the request cancels every remaining job
+                    let cancellation = cancellation_registry.register_queued(*request_id);
+
+                    for idx in order {
+                         local_job_queue.enqueue(QueuedJob {
+                              job_id: job_ids[idx],
+                              request_id: *request_id,
+                              specification: enqueuemultiplejobs_data.jobs[idx].clone(),
+                              upstream: enqueuemultiplejobs_data.upstreams_of(idx).into_iter().map(|upstream| job_ids[upstream]).collect(),
+                              cancellation: cancellation.clone(),
+                         });
+                    }
+
+                    if local_job_queue.claim_worker() {
+                         let queue = local_job_queue.clone().into_inner();
+                         let registry = cancellation_registry.clone().into_inner();
+                         std::thread::spawn(move || process_local_job_queue(&queue, &registry));
+                    }
+                    job_ids
+               });
+
+               let response_data = elements_v1::EnqueueMultipleJobsResponse { job_ids };
+               let response_body = elements_v1::PluginTaskResponse::EnqueueMultipleJobs(response_data);
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::StopRunningJobs(stoprunningjobs_data) => {
+               let response_data = elements_v1::StopRunningJobsResponse {
+                    success: Err(elements_v1::RemoteOperationError::NotSupported)
+               };
+               let response_body = elements_v1::PluginTaskResponse::StopRunningJobs(response_data);
+
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::SendMessages(sendmessages_data) => {
+               // nothing is queued for plugins yet
+               let response_data = elements_v1::SendMessagesResponse {
+                    requests: Vec::new(),
+                    messages: Vec::new()
+               };
+               let response_body = elements_v1::PluginTaskResponse::SendMessages(response_data);
+
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::CancelRequest(cancelrequest_data) => {
+               let outcome = cancellation_registry.cancel(&cancelrequest_data.request_id);
+
+               let response_data = elements_v1::CancelRequestResponse { outcome };
+               let response_body = elements_v1::PluginTaskResponse::CancelRequest(response_data);
+               return response_body;
+          },
+          elements_v1::PluginTaskRequest::Terminate(terminate_data) => {
+               let response_data = elements_v1::TerminateResponse { };
+               let response_body = elements_v1::PluginTaskResponse::Terminate(response_data);
+
+               return response_body;
           },
-          elements_v1::PluginTaskRequest::CopyFile(copyfile_data) => { },
-          elements_v1::PluginTaskRequest::DeleteFile(deletefile_data) => { },
-          elements_v1::PluginTaskRequest::ShowFileMetadata(showfilemetadata_data) => { },
-          elements_v1::PluginTaskRequest::DownloadData(downloaddata_data) => { },
-          elements_v1::PluginTaskRequest::RunSingularityJob(runsingularityjob_data) => { },
-          elements_v1::PluginTaskRequest::ShowSingularityJobLogs(showsingularityjoblogs_data) => { },
-          elements_v1::PluginTaskRequest::ShowSingularityJobsRunning(showsingularityjobsrunning_data) => { },
-          elements_v1::PluginTaskRequest::EnqueueMultipleJobs(enqueuemultiplejobs_data) => { },
-          elements_v1::PluginTaskRequest::StopRunningJobs(stoprunningjobs_data) => { },
-          elements_v1::PluginTaskRequest::SendMessages(sendmessages_data) => { },
-          elements_v1::PluginTaskRequest::Terminate(terminate_data) => { },
      }
-     return Ok(HttpResponse::Ok().finish());
 }
 
 
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use plugin_interface_elements::elements_v1::{RequestId, CancellationOutcome, RemoteOperationError};
+
+
+
+//################################################################################
+//## Cancellation tokens
+//## (long operations poll their token between steps and stop cooperatively)
+//################################################################################
+
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    flag: Arc<AtomicBool>
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        return Self { flag: Arc::new(AtomicBool::new(false)) };
+    }
+
+    pub fn cancel(&self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        return self.flag.load(Ordering::SeqCst);
+    }
+
+    // convenience for checkpoints inside operations that report RemoteOperationErrors
+    pub fn check(&self) -> Result<(), RemoteOperationError> {
+        return match self.is_cancelled() {
+            true => Err(RemoteOperationError::Cancelled),
+            false => Ok(())
+        };
+    }
+}
+
+
+
+//################################################################################
+//## Registry of cancellable requests
+//################################################################################
+
+// finished requests are only remembered to answer late cancellations, the oldest are forgotten first
+const FINISHED_REQUESTS_REMEMBERED: usize = 256;
+
+#[derive(Clone, Debug)]
+enum RequestEntry {
+    Queued(CancellationToken),
+    InFlight(CancellationToken),
+}
+
+#[derive(Debug, Default)]
+struct RegistryState {
+    entries: HashMap<RequestId, RequestEntry>,          // requests that can still be cancelled
+    recently_finished: VecDeque<RequestId>,
+}
+
+#[derive(Debug, Default)]
+pub struct CancellationRegistry {
+    state: Mutex<RegistryState>
+}
+
+impl CancellationRegistry {
+    pub fn new() -> Self {
+        return Self { state: Mutex::new(RegistryState::default()) };
+    }
+
+    // for operations that wait in a queue before they start
+    pub fn register_queued(&self, request_id: RequestId) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Ok(mut state) = self.state.lock() {
+            state.entries.insert(request_id, RequestEntry::Queued(token.clone()));
+        }
+        return token;
+    }
+
+    // for operations that start immediately
+    pub fn register_in_flight(&self, request_id: RequestId) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Ok(mut state) = self.state.lock() {
+            state.entries.insert(request_id, RequestEntry::InFlight(token.clone()));
+        }
+        return token;
+    }
+
+    pub fn mark_started(&self, request_id: &RequestId) {
+        if let Ok(mut state) = self.state.lock() {
+            state.entries.entry(*request_id).and_modify(|entry| {
+                if let RequestEntry::Queued(token) = entry {
+                    *entry = RequestEntry::InFlight(token.clone());
+                }
+            });
+        }
+    }
+
+    // the token is dropped, only the RequestId is kept for a while
+    pub fn mark_finished(&self, request_id: &RequestId) {
+        if let Ok(mut state) = self.state.lock() {
+            if state.entries.remove(request_id).is_none() { return; }
+            state.recently_finished.push_back(*request_id);
+            if state.recently_finished.len() > FINISHED_REQUESTS_REMEMBERED {
+                state.recently_finished.pop_front();
+            }
+        }
+    }
+
+    pub fn cancel(&self, request_id: &RequestId) -> Result<CancellationOutcome, RemoteOperationError> {
+        let state = self.state.lock()
+            .map_err(|_| RemoteOperationError::InternalFailure("Cancellation registry is poisoned".to_string()))?;
+
+        let outcome = match state.entries.get(request_id) {
+            Some(RequestEntry::Queued(token)) => { token.cancel(); CancellationOutcome::Cancelled },
+            Some(RequestEntry::InFlight(token)) => { token.cancel(); CancellationOutcome::Signalled },
+            None if state.recently_finished.contains(request_id) => CancellationOutcome::AlreadyFinished,
+            None => CancellationOutcome::UnknownRequest,
+        };
+        return Ok(outcome);
+    }
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn finished_requests_leave_the_registry() {
+        let registry = CancellationRegistry::new();
+        let request_id = RequestId { inner: Uuid::from_u128(1) };
+
+        let token = registry.register_queued(request_id);
+        registry.mark_started(&request_id);
+        assert_eq!(registry.cancel(&request_id), Ok(CancellationOutcome::Signalled));
+        assert!(token.is_cancelled());
+
+        registry.mark_finished(&request_id);
+        assert!(registry.state.lock().unwrap().entries.is_empty());
+        assert_eq!(registry.cancel(&request_id), Ok(CancellationOutcome::AlreadyFinished));
+        assert_eq!(registry.cancel(&RequestId { inner: Uuid::from_u128(2) }), Ok(CancellationOutcome::UnknownRequest));
+
+        for n in 0..FINISHED_REQUESTS_REMEMBERED as u128 {
+            let other = RequestId { inner: Uuid::from_u128(100 + n) };
+            registry.register_in_flight(other);
+            registry.mark_finished(&other);
+        }
+        assert_eq!(registry.cancel(&request_id), Ok(CancellationOutcome::UnknownRequest));
+    }
+}
@@ -0,0 +1,207 @@
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::{Read, Write, BufReader, BufWriter};
+
+use plugin_interface_elements::elements_v1::{self, FsElement, RemoteOperationError};
+
+use super::CancellationToken;
+
+
+
+//################################################################################
+//## Cancellable file operations
+//## (every chunk / directory entry is a checkpoint for the CancellationToken)
+//################################################################################
+
+const COPY_CHUNK_SIZE: usize = 1024*1024;
+
+fn io_failure(e: std::io::Error) -> RemoteOperationError {
+    return RemoteOperationError::InternalFailure(format!("{:?}", &e));
+}
+
+pub fn list_directory_cancellable(directory: &Path, token: &CancellationToken) -> Result<Vec<FsElement>, RemoteOperationError> {
+    let entries = std::fs::read_dir(directory).map_err(io_failure)?;
+
+    let mut content = Vec::new() as Vec<FsElement>;
+    for entry in entries {
+        token.check()?;
+        if let Ok(entry) = entry {
+            if let Ok(element) = FsElement::try_from(entry.path()) {
+                content.push(element);
+            }
+        }
+    }
+    return Ok(content);
+}
+
+pub fn copy_file_cancellable(source: &Path, target: &Path, token: &CancellationToken) -> Result<PathBuf, RemoteOperationError> {
+    if source.is_dir() {
+        let mut created = Vec::new() as Vec<PathBuf>;
+        let result = copy_directory_cancellable(source, target, token, &mut created);
+
+        // like partially copied files, partially copied trees must not be mistaken for complete ones;
+        // directories that existed before are kept, only what this copy created is removed
+        if result.is_err() {
+            for path in created.iter().rev() {
+                match path.is_dir() {
+                    true => std::fs::remove_dir(path).ok(),
+                    false => std::fs::remove_file(path).ok(),
+                };
+            }
+        }
+        return result.map(|_| target.to_path_buf());
+    }
+    return copy_single_file_cancellable(source, target, token);
+}
+
+// every file and directory this creates is recorded in created, parents before their content
+fn copy_directory_cancellable(source: &Path, target: &Path, token: &CancellationToken, created: &mut Vec<PathBuf>) -> Result<(), RemoteOperationError> {
+    if !target.exists() {
+        std::fs::create_dir_all(target).map_err(io_failure)?;
+        created.push(target.to_path_buf());
+    }
+    for entry in std::fs::read_dir(source).map_err(io_failure)? {
+        token.check()?;
+        let entry = entry.map_err(io_failure)?;
+        let entry_target = target.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_directory_cancellable(&entry.path(), &entry_target, token, created)?;
+        } else {
+            let existed = entry_target.exists();
+            copy_single_file_cancellable(&entry.path(), &entry_target, token)?;
+            if !existed { created.push(entry_target); }
+        }
+    }
+    return Ok(());
+}
+
+fn copy_single_file_cancellable(source: &Path, target: &Path, token: &CancellationToken) -> Result<PathBuf, RemoteOperationError> {
+    let mut reader = BufReader::new(File::open(source).map_err(io_failure)?);
+    let mut writer = BufWriter::new(File::create(target).map_err(io_failure)?);
+    let mut buf = vec![0 as u8; COPY_CHUNK_SIZE];
+
+    let result = loop {
+        if let Err(e) = token.check() { break Err(e); }
+        match reader.read(&mut buf[..]) {
+            Ok(0) => break writer.flush().map_err(io_failure),
+            Ok(n) => {
+                if let Err(e) = writer.write_all(&buf[..n]) { break Err(io_failure(e)); }
+            },
+            Err(e) => break Err(io_failure(e)),
+        }
+    };
+
+    // partially copied files are worthless and must not be mistaken for complete ones
+    if result.is_err() {
+        drop(writer);
+        std::fs::remove_file(target).ok();
+    }
+    return result.map(|_| target.to_path_buf());
+}
+
+pub fn move_file_cancellable(source: &Path, target: &Path, token: &CancellationToken) -> Result<PathBuf, RemoteOperationError> {
+    token.check()?;
+    // renaming is atomic and cannot be interrupted, but fails across file systems
+    if std::fs::rename(source, target).is_ok() {
+        return Ok(target.to_path_buf());
+    }
+
+    copy_file_cancellable(source, target, token)?;
+    let removal = match source.is_dir() {
+        true => std::fs::remove_dir_all(source),
+        false => std::fs::remove_file(source),
+    };
+    removal.map_err(io_failure)?;
+    return Ok(target.to_path_buf());
+}
+
+
+
+//################################################################################
+//## Cancellable downloads
+//################################################################################
+
+pub async fn download_cancellable(request: &elements_v1::DownloadData, destination: &Path, token: &CancellationToken) -> Result<PathBuf, RemoteOperationError> {
+    let client = reqwest::Client::new();
+    let mut request_builder = client.get(&request.url);
+    if let Some(auth) = &request.auth {
+        request_builder = request_builder.basic_auth(&auth.username, Some(&auth.password));
+    }
+
+    let mut response = request_builder.send().await
+        .map_err(|e| RemoteOperationError::InternalFailure(format!("{:?}", &e)))?;
+
+    let mut writer = BufWriter::new(File::create(destination).map_err(io_failure)?);
+    let result = loop {
+        if let Err(e) = token.check() { break Err(e); }
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                if let Err(e) = writer.write_all(&chunk) { break Err(io_failure(e)); }
+            },
+            Ok(None) => break writer.flush().map_err(io_failure),
+            Err(e) => break Err(RemoteOperationError::InternalFailure(format!("{:?}", &e))),
+        }
+    };
+
+    if result.is_err() {
+        drop(writer);
+        std::fs::remove_file(destination).ok();
+    }
+    return result.map(|_| destination.to_path_buf());
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("colony-file-operations-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(dir.join("source/nested")).unwrap();
+        std::fs::write(dir.join("source/a.txt"), "a").unwrap();
+        std::fs::write(dir.join("source/nested/b.txt"), "b").unwrap();
+        return dir;
+    }
+
+    #[test]
+    fn cancelled_copies_leave_nothing_behind() {
+        let dir = scratch_dir("copy");
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+
+        let result = copy_file_cancellable(&dir.join("source"), &dir.join("target"), &cancelled);
+        assert_eq!(result, Err(RemoteOperationError::Cancelled));
+        assert!(!dir.join("target").exists());
+
+        // copying into an existing directory keeps what was there
+        std::fs::create_dir_all(dir.join("existing")).unwrap();
+        std::fs::write(dir.join("existing/kept.txt"), "kept").unwrap();
+        let result = copy_file_cancellable(&dir.join("source"), &dir.join("existing"), &cancelled);
+        assert_eq!(result, Err(RemoteOperationError::Cancelled));
+        assert!(dir.join("existing/kept.txt").exists());
+        assert!(!dir.join("existing/a.txt").exists());
+
+        let result = copy_file_cancellable(&dir.join("source"), &dir.join("target"), &CancellationToken::new());
+        assert_eq!(result, Ok(dir.join("target")));
+        assert_eq!(std::fs::read_to_string(dir.join("target/nested/b.txt")).unwrap(), "b");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn cancelled_listings_report_cancellation() {
+        let dir = scratch_dir("list");
+        let cancelled = CancellationToken::new();
+        cancelled.cancel();
+
+        assert_eq!(list_directory_cancellable(&dir.join("source"), &cancelled), Err(RemoteOperationError::Cancelled));
+        let content = list_directory_cancellable(&dir.join("source"), &CancellationToken::new()).unwrap();
+        assert_eq!(content.len(), 2);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
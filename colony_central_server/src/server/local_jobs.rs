@@ -1,47 +1,17 @@
 
 
 use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
-use std::process::Child;
-use std::time::SystemTime;
-use uuid::Uuid;
-use serde::{Serialize, Deserialize};
-
-use super::HardTypedDBAccess;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct JobId {
-    pub order_time: UnixTime,
-    pub uuidv4: Uuid,
-}
-
-impl JobId {
-    pub fn new() -> Self {
-        return Self { order_time: UnixTime::now(), uuidv4: Uuid::new_v4() };
-    }
-}
-
-impl Default for JobId {
-    fn default() -> Self { return Self::new(); }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct UnixTime {
-    pub seconds: u64
-}
-
-impl UnixTime {
-    pub fn from(t: SystemTime) -> UnixTime {
-        return UnixTime { seconds: t.duration_since(SystemTime::UNIX_EPOCH).expect("System Time appears to be before 1970").as_secs() };
-    }
-
-    pub fn now() -> UnixTime {
-        return UnixTime::from(SystemTime::now());
-    }
-}
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
+use plugin_interface_elements::elements_v1::{JobId, JobState, RequestId, RemoteSingularityJob, RemoteOperationError};
 
+use super::{HardTypedDBAccess, CancellationToken, CancellationRegistry};
 
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(200);
 
 //pub enum Process {
 //    RunningProcess(Child, Option<String>), // process, Configuration-String if it is a colony container job
@@ -90,49 +60,218 @@ impl Drop for ProcessStore {
 
 
 //################################################################################
-//## Running jobs
+//## Queued jobs
 //################################################################################
 
-pub fn run_singularity_job() {
+#[derive(Clone, Debug)]
+pub struct QueuedJob {
+    pub job_id: JobId,
+    pub request_id: RequestId,
+    pub specification: RemoteSingularityJob,
+    pub upstream: Vec<JobId>,           // jobs that have to succeed before this one may start
+    pub cancellation: CancellationToken,
+}
+
+#[derive(Debug, Default)]
+pub struct LocalJobQueue {
+    queue: Mutex<VecDeque<QueuedJob>>,
+    states: Mutex<HashMap<JobId, JobState>>,
+    worker_active: AtomicBool,      // only one worker drains the queue at a time
+}
+
+impl LocalJobQueue {
+    pub fn new() -> Self {
+        return Self { queue: Mutex::new(VecDeque::new()), states: Mutex::new(HashMap::new()), worker_active: AtomicBool::new(false) };
+    }
+
+    pub fn job_state(&self, job_id: &JobId) -> Option<JobState> {
+        return self.states.lock().ok()?.get(job_id).copied();
+    }
+
+    pub fn set_job_state(&self, job_id: JobId, state: JobState) {
+        if let Ok(mut states) = self.states.lock() {
+            states.insert(job_id, state);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.queue.lock().map(|queue| queue.is_empty()).unwrap_or(true);
+    }
+
+    // returns true if the caller is now responsible for running process_local_job_queue
+    pub fn claim_worker(&self) -> bool {
+        return self.worker_active.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok();
+    }
+
+    pub fn enqueue(&self, job: QueuedJob) {
+        self.set_job_state(job.job_id, JobState::Queued);
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.push_back(job);
+        }
+    }
+
+    // several jobs may share a RequestId if they were enqueued together
+    pub fn has_pending(&self, request_id: &RequestId) -> bool {
+        return self.queue.lock().map(|queue| queue.iter().any(|job| &job.request_id == request_id)).unwrap_or(false);
+    }
+
+    // None while any upstream job has not finished yet, Some(false) if one of them did not succeed
+    fn upstream_succeeded(&self, job: &QueuedJob) -> Option<bool> {
+        let mut all_succeeded = true;
+        for upstream_id in job.upstream.iter() {
+            match self.job_state(upstream_id) {
+                Some(JobState::Succeeded) => {},
+                Some(state) if !state.is_finished() => return None,
+                _ => { all_succeeded = false; },
+            }
+        }
+        return Some(all_succeeded);
+    }
 
-    let child_result = singularity_run_in_dir(&workdir, &container_path, container_args);
+    // cancelled jobs and jobs whose upstream jobs have all finished can be taken off the queue
+    fn is_decided(&self, job: &QueuedJob) -> bool {
+        return job.cancellation.is_cancelled() || self.upstream_succeeded(job).is_some();
+    }
 
-    match child_result {
-        Ok(mut child) => {
-            //let jid = JobId::new(format!("Running: {}", &container_path));
-            let job_id = JobId::new();
-            let output_collector = process_outputs
-                                    .entry(job_id.clone())
-                                    .or_insert_with(|| Arc::new(Mutex::new(Vec::new())));
-            let mut output_collector = Arc::clone(&output_collector);
-            println!("Command: {:?}", &child);
+    // false if every queued job still waits for an upstream job
+    pub fn has_decided_job(&self) -> bool {
+        return self.queue.lock().map(|queue| queue.iter().any(|job| self.is_decided(job))).unwrap_or(false);
+    }
 
-            let job_id_coll = container_infos.entry(PathBuf::from(container_path))
-                                .or_insert(Arc::new(Mutex::new(Vec::new() as Vec<JobId>)));
-            job_id_coll.lock().map(|mut vec_jid| vec_jid.push(job_id.clone())).ok();
+    // jobs that were cancelled while waiting, or whose upstream jobs failed, are dropped here and reported as finished
+    pub fn next_job(&self, registry: &CancellationRegistry) -> Option<QueuedJob> {
+        loop {
+            let job = {
+                let mut queue = self.queue.lock().ok()?;
+                let idx = queue.iter().position(|job| self.is_decided(job))?;
+                queue.remove(idx)?
+            };
+
+            if job.cancellation.is_cancelled() {
+                println!("Dropping cancelled job {:?}", &job.job_id);
+                self.set_job_state(job.job_id, JobState::Cancelled);
+            } else if self.upstream_succeeded(&job) == Some(false) {
+                println!("Skipping job {:?}, because an upstream job did not succeed", &job.job_id);
+                self.set_job_state(job.job_id, JobState::Skipped);
+            } else {
+                return Some(job);
+            }
 
-            let mut child_stdout = child.stdout.take().unwrap();
-            tokio::spawn(async move {
-                backend::bufread_stdout_bytes_into_messages(&mut output_collector, &mut child_stdout);
-            });
+            if !self.has_pending(&job.request_id) {
+                registry.mark_finished(&job.request_id);
+            }
+        }
+    }
+}
 
-            process_store.insert(job_id.clone(), Arc::new(Mutex::new(child)));
 
-            todo!("run_singularity_job may need to report its completion to the runtime")
 
-            //comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Running)).ok();
-        },
-        Err(e) => {
-            println!("Error starting container: {:?}", &e);
+//################################################################################
+//## Running jobs
+//################################################################################
+
+pub fn run_singularity_job(job: &QueuedJob) -> Result<JobState, RemoteOperationError> {
+    let RemoteSingularityJob { singularity_container, configuration, working_directory } = &job.specification;
+
+    let mut child = Command::new("singularity")
+        .current_dir(working_directory)
+        .arg("run")
+        .arg(singularity_container)
+        .arg(configuration)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| RemoteOperationError::InternalFailure(format!("{:?}", &e)))?;
+
+    loop {
+        if job.cancellation.is_cancelled() {
+            println!("Killing cancelled job {:?}", &job.job_id);
+            child.kill().ok();
+            child.wait().ok();
+            return Ok(JobState::Cancelled);
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(JobState::from_exit_status(status)),
+            Ok(None) => std::thread::sleep(JOB_POLL_INTERVAL),
+            Err(e) => {
+                println!("Lost track of job {:?}: {:?}", &job.job_id, &e);
+                return Ok(JobState::Orphaned);
+            },
         }
     }
 }
 
+// runs queued jobs one after another until none is left that could start, must only be called after claim_worker
+pub fn process_local_job_queue(queue: &LocalJobQueue, registry: &CancellationRegistry) {
+    loop {
+        while let Some(job) = queue.next_job(registry) {
+            registry.mark_started(&job.request_id);
+            queue.set_job_state(job.job_id, JobState::Running);
+            let final_state = match run_singularity_job(&job) {
+                Ok(state) => state,
+                Err(e) => {
+                    println!("Job {:?} could not be started: {:?}", &job.job_id, &e);
+                    JobState::Failed { exit_code: None, signal: None }
+                },
+            };
+            println!("Job {:?} ended as {:?}", &job.job_id, &final_state);
+            queue.set_job_state(job.job_id, final_state);
+            if !queue.has_pending(&job.request_id) {
+                registry.mark_finished(&job.request_id);
+            }
+        }
+        queue.worker_active.store(false, Ordering::SeqCst);
+
+        // a job may have been enqueued between the last check and releasing the worker flag;
+        // jobs that only wait for upstream jobs are left to the worker that finishes those, instead of polling for them
+        if !queue.has_decided_job() || !queue.claim_worker() { return; }
+    }
+}
+
 //################################################################################
 //## Background runtime
 //################################################################################
 
 
 
+//################################################################################
+//## Tests
+//################################################################################
 
-
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn worker_stops_when_only_waiting_jobs_are_left() {
+        let queue = LocalJobQueue::new();
+        let registry = CancellationRegistry::new();
+        let request_id = RequestId { inner: Uuid::from_u128(1) };
+
+        // the upstream job is run elsewhere and has not finished yet
+        let upstream = JobId::new();
+        queue.set_job_state(upstream, JobState::Running);
+        let waiting = QueuedJob {
+            job_id: JobId::new(),
+            request_id,
+            specification: RemoteSingularityJob { singularity_container: PathBuf::from("a.sif"), configuration: PathBuf::from("a.json"), working_directory: PathBuf::from(".") },
+            upstream: vec![upstream],
+            cancellation: registry.register_queued(request_id),
+        };
+        queue.enqueue(waiting.clone());
+
+        assert!(queue.claim_worker());
+        process_local_job_queue(&queue, &registry);
+        assert_eq!(queue.job_state(&waiting.job_id), Some(JobState::Queued));
+        assert!(!queue.worker_active.load(Ordering::SeqCst));
+
+        // once the upstream job failed, the next worker skips the waiting job
+        queue.set_job_state(upstream, JobState::Failed { exit_code: Some(1), signal: None });
+        assert!(queue.claim_worker());
+        process_local_job_queue(&queue, &registry);
+        assert_eq!(queue.job_state(&waiting.job_id), Some(JobState::Skipped));
+        assert!(queue.is_empty());
+    }
+}
@@ -1,6 +1,12 @@
 
 
 
+mod cancellation;
+pub use cancellation::*;
+
+mod file_operations;
+pub use file_operations::*;
+
 mod local_jobs;
 pub use local_jobs::*;
 
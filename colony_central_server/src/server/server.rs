@@ -10,6 +10,9 @@ use actix_cors::Cors;
 use actix_web::{get, post, web, web::Data, App, HttpServer, Responder};
 use actix_web::web::PayloadConfig;
 
+use crate::endpoints::api_endpoint;
+use super::{LocalJobQueue, CancellationRegistry};
+
 
 
 //################################################################################
@@ -22,6 +25,9 @@ pub async fn start_actix_server(address: String, port: u16,
     //TODO: implement timeout with a tokio::select! statement or similar
     tokio::spawn( async move {
         let state_data = Data::new(Mutex::new(server_state));
+        // created once and shared by all workers, so that every request sees the same jobs and cancellations
+        let local_job_queue = Data::new(LocalJobQueue::new());
+        let cancellation_registry = Data::new(CancellationRegistry::new());
 
         println!("Listening on {}:{}...", &address, &port);
         let server_result = HttpServer::new(move || {
@@ -38,7 +44,10 @@ pub async fn start_actix_server(address: String, port: u16,
             .wrap(cors)
             .app_data(payload_config)
             .app_data(Data::clone(&state_data))
+            .app_data(Data::clone(&local_job_queue))
+            .app_data(Data::clone(&cancellation_registry))
             .service(health_check)
+            .service(api_endpoint)
         })
         .bind((address,port));
 
@@ -471,6 +480,9 @@ pub async fn start_actix_server(address: String, port: u16,
     //TODO: implement timeout with a tokio::select! statement or similar
     tokio::spawn( async move {
         let state_data = Data::new(Mutex::new(server_state));
+        // created once and shared by all workers, so that every request sees the same jobs and cancellations
+        let local_job_queue = Data::new(LocalJobQueue::new());
+        let cancellation_registry = Data::new(CancellationRegistry::new());
 
         println!("Listening on {}:{}...", &address, &port);
         let server_result = HttpServer::new(move || {
@@ -487,7 +499,10 @@ pub async fn start_actix_server(address: String, port: u16,
             .wrap(cors)
             .app_data(payload_config)
             .app_data(Data::clone(&state_data))
+            .app_data(Data::clone(&local_job_queue))
+            .app_data(Data::clone(&cancellation_registry))
             .service(health_check)
+            .service(api_endpoint)
         })
         .bind((address,port));
 
@@ -2,6 +2,7 @@
 
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::sync::OnceLock;
+use std::path::PathBuf;
 
 use serde::{Serialize, Deserialize};
 
@@ -1,24 +1,139 @@
 
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
+use std::path::PathBuf;
+
+use uuid::Uuid;
+use chrono::{TimeZone, Utc};
+
+use plugin_interface_elements::{elements_v1, TargetSystem, VersionedRequest, VersionedResponse, WireEncoding,
+                                gzip_compress, gzip_decompress};
+
+
+//################################################################################
+//## Sample messages
+//################################################################################
+
+fn request_id() -> elements_v1::RequestId {
+    return elements_v1::RequestId { inner: Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef) };
+}
+
+fn job_id() -> elements_v1::JobId {
+    return elements_v1::JobId { id: Uuid::from_u128(42), generation_time: Utc.with_ymd_and_hms(2025, 3, 14, 15, 9, 26).unwrap() };
+}
+
+fn sample_job() -> elements_v1::RemoteSingularityJob {
+    return elements_v1::RemoteSingularityJob {
+        singularity_container: PathBuf::from("/data/containers/alignment.sif"),
+        configuration: PathBuf::from("/data/runs/äöü config.json"),
+        working_directory: PathBuf::from("/data/runs"),
+    };
+}
+
+fn sample_requests() -> Vec<VersionedRequest> {
+    let plugin_requests = vec![
+        elements_v1::PluginTaskRequest::ListDirectory(elements_v1::ListDirectory { directory: PathBuf::from("/data") }),
+        elements_v1::PluginTaskRequest::DownloadData(elements_v1::DownloadData {
+            url: "https://example.org/reference.fa.gz".to_string(),
+            auth: Some(elements_v1::DownloadAuth { username: "user".to_string(), password: "".to_string() }),
+        }),
+        elements_v1::PluginTaskRequest::ShowSingularityJobLogs(elements_v1::ShowSingularityJobLogs { job: job_id() }),
+        elements_v1::PluginTaskRequest::EnqueueMultipleJobs(elements_v1::EnqueueMultipleJobs {
+            jobs: vec![sample_job(), sample_job(), sample_job()],
+            dependencies: vec![
+                elements_v1::JobDependency { upstream: 0, downstream: 1 },
+                elements_v1::JobDependency { upstream: 1, downstream: 2 },
+            ],
+        }),
+        elements_v1::PluginTaskRequest::CancelRequest(elements_v1::CancelRequest { request_id: request_id() }),
+    ];
+
+    let targets = vec![TargetSystem::LocalMachine, TargetSystem::RemoteMachine("hpc-login-01".to_string())];
+
+    return targets.iter().flat_map(|target| {
+        plugin_requests.iter().map(|request| {
+            VersionedRequest::ApiV1(target.clone(), request_id(), elements_v1::TaskRequest::PluginTaskRequest(request.clone()))
+        })
+    }).collect();
+}
+
+fn sample_responses() -> Vec<VersionedResponse> {
+    let listing = (0..2000).map(|idx| elements_v1::FsElement::File(PathBuf::from(format!("/data/sample_{idx:04}.fastq.gz")))).collect();
+
+    let plugin_responses = vec![
+        elements_v1::PluginTaskResponse::ListDirectory(elements_v1::ListDirectoryResponse { content: Ok(listing) }),
+        elements_v1::PluginTaskResponse::ListDirectory(elements_v1::ListDirectoryResponse {
+            content: Err(elements_v1::RemoteOperationError::InternalFailure("permission denied".to_string()))
+        }),
+        elements_v1::PluginTaskResponse::RunSingularityJob(elements_v1::RunSingularityJobResponse { success: Ok(job_id()) }),
+        elements_v1::PluginTaskResponse::ShowSingularityJobLogs(elements_v1::ShowSingularityJobLogsResponse {
+            logs: Ok(vec!["line 1".to_string(), String::new(), "\u{1b}[32mcolored\u{1b}[0m\r".to_string()])
+        }),
+        elements_v1::PluginTaskResponse::CancelRequest(elements_v1::CancelRequestResponse {
+            outcome: Ok(elements_v1::CancellationOutcome::Signalled)
+        }),
+        elements_v1::PluginTaskResponse::CancelRequest(elements_v1::CancelRequestResponse {
+            outcome: Err(elements_v1::RemoteOperationError::Cancelled)
+        }),
+    ];
+
+    return plugin_responses.into_iter()
+        .map(|response| VersionedResponse::ApiV1(TargetSystem::LocalMachine, request_id(), response))
+        .collect();
+}
+
+
+
+//################################################################################
+//## Equivalence of encodings
+//################################################################################
+
+#[test]
+fn requests_roundtrip_identically_in_all_encodings() {
+    for request in sample_requests() {
+        let json = WireEncoding::Json.encode(&request).unwrap();
+        let cbor = WireEncoding::Cbor.encode(&request).unwrap();
+
+        let from_json: VersionedRequest = WireEncoding::Json.decode(&json).unwrap();
+        let from_cbor: VersionedRequest = WireEncoding::Cbor.decode(&cbor).unwrap();
+
+        assert_eq!(from_json, request);
+        assert_eq!(from_cbor, request);
+        // re-encoding the CBOR result must give the exact same JSON document
+        assert_eq!(WireEncoding::Json.encode(&from_cbor).unwrap(), json);
+    }
+}
+
+#[test]
+fn responses_roundtrip_identically_in_all_encodings() {
+    for response in sample_responses() {
+        let json = WireEncoding::Json.encode(&response).unwrap();
+        let cbor = WireEncoding::Cbor.encode(&response).unwrap();
+
+        let from_json: VersionedResponse = WireEncoding::Json.decode(&json).unwrap();
+        let from_cbor: VersionedResponse = WireEncoding::Cbor.decode(&cbor).unwrap();
+
+        assert_eq!(from_json, response);
+        assert_eq!(from_cbor, response);
+        assert_eq!(WireEncoding::Json.encode(&from_cbor).unwrap(), json);
+    }
+}
+
+#[test]
+fn gzip_roundtrip_is_lossless() {
+    for response in sample_responses() {
+        for encoding in [WireEncoding::Json, WireEncoding::Cbor] {
+            let bytes = encoding.encode(&response).unwrap();
+            let decompressed = gzip_decompress(&gzip_compress(&bytes).unwrap()).unwrap();
+            assert_eq!(decompressed, bytes);
+        }
+    }
+}
+
+#[test]
+fn content_negotiation() {
+    assert_eq!(WireEncoding::from_content_type("application/cbor"), Some(WireEncoding::Cbor));
+    assert_eq!(WireEncoding::from_content_type("application/json; charset=utf-8"), Some(WireEncoding::Json));
+    assert_eq!(WireEncoding::from_content_type("text/plain"), None);
+
+    assert_eq!(WireEncoding::from_accept_header("text/html, application/cbor, application/json"), WireEncoding::Cbor);
+    assert_eq!(WireEncoding::from_accept_header("*/*"), WireEncoding::Json);
+}
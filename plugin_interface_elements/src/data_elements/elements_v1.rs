@@ -27,7 +27,8 @@ pub enum RemoteOperationError {
     ParsingError(String),
     IncorrectParameters(String),
     ClientServerInconsistency(String),
-    InternalFailure(String)
+    InternalFailure(String),
+    Cancelled
 }
 
 
@@ -40,33 +41,80 @@ pub struct PluginId {
     pub clear_name: String
 }
 
-#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct RequestId { 
     pub inner: Uuid
 }
 
-#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct ChannelId { 
     pub inner: Uuid
 }
 
-#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct JobId { 
     pub id: Uuid, 
     pub generation_time: DateTime<Utc>
 }
 
+impl JobId {
+    pub fn new() -> Self {
+        return Self { id: Uuid::new_v4(), generation_time: Utc::now() };
+    }
+}
+
+impl Default for JobId {
+    fn default() -> Self { return Self::new(); }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub struct DownloadId { 
     pub inner: Uuid
 }
 
-#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct MessageId { 
     pub inner: Uuid
 }
 
 
+//################################################################################
+//## Job states
+//################################################################################
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum JobState {
+    Queued,                     // accepted, but not yet started
+    Running,
+    Succeeded,
+    Failed {
+        exit_code: Option<i32>, // None if the process was terminated by a signal
+        signal: Option<i32>     // always None on Windows
+    },
+    Cancelled,                  // stopped on request of a user
+    Skipped,                    // never started, because an upstream job did not succeed
+    TimedOut,
+    Orphaned,                   // the process can no longer be observed
+}
+
+impl JobState {
+    pub fn from_exit_status(status: std::process::ExitStatus) -> Self {
+        if status.success() { return JobState::Succeeded; }
+
+        #[cfg(unix)]
+        let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+        #[cfg(not(unix))]
+        let signal = None;
+
+        return JobState::Failed { exit_code: status.code(), signal };
+    }
+
+    pub fn is_finished(&self) -> bool {
+        return !matches!(self, JobState::Queued | JobState::Running);
+    }
+}
+
+
 //################################################################################
 //## Request data types
 //################################################################################
@@ -105,6 +153,7 @@ pub enum PluginTaskRequest {
     EnqueueMultipleJobs(EnqueueMultipleJobs),
     StopRunningJobs(StopRunningJobs),
     SendMessages(SendMessages),
+    CancelRequest(CancelRequest),
     Terminate(Terminate),
 }
 
@@ -115,6 +164,8 @@ pub enum FrontendTaskRequest {
     HaveConfigurationStored(HaveConfigurationStored),
     OpenChatChannel(OpenChatChannel),
     CloseChatChannel(CloseChatChannel),
+    SendChatMessage(SendChatMessage),
+    ReceiveChatMessages(ReceiveChatMessages),
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -206,7 +257,55 @@ pub struct ShowSingularityJobLogs {
 pub struct ShowSingularityJobsRunning { }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
-pub struct EnqueueMultipleJobs { }
+pub struct EnqueueMultipleJobs { 
+    pub jobs: Vec<RemoteSingularityJob>, 
+    pub dependencies: Vec<JobDependency>
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct JobDependency {              // indices into EnqueueMultipleJobs::jobs
+    pub upstream: usize,                // must succeed before the downstream job may start
+    pub downstream: usize
+}
+
+impl EnqueueMultipleJobs {
+    pub fn upstreams_of(&self, job_index: usize) -> Vec<usize> {
+        return self.dependencies.iter()
+            .filter(|dependency| dependency.downstream == job_index)
+            .map(|dependency| dependency.upstream)
+            .collect();
+    }
+
+    // job indices ordered such that every job comes after all of its upstream jobs
+    pub fn execution_order(&self) -> Result<Vec<usize>, RemoteOperationError> {
+        let job_count = self.jobs.len();
+        let mut missing_upstreams = vec![0 as usize; job_count];
+
+        for dependency in self.dependencies.iter() {
+            if dependency.upstream >= job_count || dependency.downstream >= job_count {
+                return Err(RemoteOperationError::IncorrectParameters(format!("Dependency {:?} refers to a job that does not exist", dependency)));
+            }
+            missing_upstreams[dependency.downstream] += 1;
+        }
+
+        let mut ready: Vec<usize> = (0..job_count).filter(|&idx| missing_upstreams[idx] == 0).rev().collect();
+        let mut order = Vec::with_capacity(job_count);
+        while let Some(idx) = ready.pop() {
+            order.push(idx);
+            for dependency in self.dependencies.iter().filter(|dependency| dependency.upstream == idx) {
+                missing_upstreams[dependency.downstream] -= 1;
+                if missing_upstreams[dependency.downstream] == 0 {
+                    ready.push(dependency.downstream);
+                }
+            }
+        }
+
+        if order.len() < job_count {
+            return Err(RemoteOperationError::IncorrectParameters("Job dependencies contain a cycle".to_string()));
+        }
+        return Ok(order);
+    }
+}
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct StopRunningJobs { }
@@ -217,6 +316,12 @@ pub struct StopRunningJobs { }
 pub struct SendMessages { }
 
 
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CancelRequest {              // Cancellation is cooperative: operations stop at their next checkpoint
+    pub request_id: RequestId
+}
+
+
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Terminate { }
@@ -226,16 +331,43 @@ pub struct Terminate { }
 
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
-pub struct RequestConfiguration { }
+pub struct RequestConfiguration {       // configurations are keyed by container and app
+    pub container: String,              // e.g. the container's file name
+    pub app: Option<String>,            // None for the container's main runscript
+    pub name: Option<String>            // None requests the latest version
+}
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
-pub struct HaveConfigurationStored { }
+pub struct HaveConfigurationStored { 
+    pub container: String, 
+    pub app: Option<String>, 
+    pub name: Option<String>,           // optional label to fetch this version by later
+    pub author: String, 
+    pub configuration: String
+}
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
-pub struct OpenChatChannel { }
+pub struct OpenChatChannel {            // opened by a plugin or a running container, answered by the user in the launcher
+    pub title: String, 
+    pub job: Option<JobId>              // set if the channel belongs to a running job
+}
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
-pub struct CloseChatChannel { }
+pub struct CloseChatChannel { 
+    pub channel_id: ChannelId
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SendChatMessage { 
+    pub channel_id: ChannelId, 
+    pub text: String
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ReceiveChatMessages {        // polls the channel history, e.g. for the answer of the user
+    pub channel_id: ChannelId, 
+    pub since: usize                    // number of messages the caller has already received
+}
 
 
 
@@ -264,6 +396,7 @@ pub enum PluginTaskResponse {
     EnqueueMultipleJobs(EnqueueMultipleJobsResponse),
     StopRunningJobs(StopRunningJobsResponse),
     SendMessages(SendMessagesResponse),
+    CancelRequest(CancelRequestResponse),
     Terminate(TerminateResponse),
 }
 
@@ -273,6 +406,8 @@ pub enum FrontendTaskResponse {
     HaveConfigurationStored(HaveConfigurationStoredResponse),
     OpenChatChannel(OpenChatChannelResponse),
     CloseChatChannel(CloseChatChannelResponse),
+    SendChatMessage(SendChatMessageResponse),
+    ReceiveChatMessages(ReceiveChatMessagesResponse),
 }
 
 
@@ -311,12 +446,21 @@ pub struct DisconnectFromAllServersResponse {
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct RequestConfigurationResponse { 
-    pub configuration: Option<String>
+    pub configuration: Result<Option<String>, RemoteOperationError>,   // Ok(None) if nothing is stored for the container and app
+    pub version: Option<ConfigurationVersion>
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct HaveConfigurationStoredResponse { 
-    pub success: Result<(), RemoteOperationError>
+    pub version: Result<ConfigurationVersion, RemoteOperationError>
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ConfigurationVersion { 
+    pub version: u32,                   // counts up per container and app, starting at 1
+    pub name: Option<String>, 
+    pub author: String, 
+    pub creation_time: DateTime<Utc>
 }
 
 
@@ -330,6 +474,31 @@ pub struct CloseChatChannelResponse {
     pub channel_id: Result<ChannelId, RemoteOperationError>
 }
 
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SendChatMessageResponse { 
+    pub message_id: Result<MessageId, RemoteOperationError>
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ReceiveChatMessagesResponse { 
+    pub messages: Result<Vec<ChatMessage>, RemoteOperationError>, 
+    pub channel_open: bool              // false once the user or the plugin closed the channel
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ChatMessage { 
+    pub id: MessageId, 
+    pub author: ChatAuthor, 
+    pub creation_time: DateTime<Utc>, 
+    pub text: String
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum ChatAuthor {
+    User,
+    Plugin,
+}
+
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct ListDirectoryResponse { 
@@ -401,7 +570,7 @@ pub struct ShowSingularityJobsRunningResponse {
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct EnqueueMultipleJobsResponse { 
-    pub success: Result<(), RemoteOperationError>
+    pub job_ids: Result<Vec<JobId>, RemoteOperationError>   // same order as EnqueueMultipleJobs::jobs
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -419,6 +588,21 @@ pub struct SendMessagesResponse {
 
 
 
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct CancelRequestResponse { 
+    pub outcome: Result<CancellationOutcome, RemoteOperationError>
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum CancellationOutcome {
+    Cancelled,          // the operation was still queued and will never start
+    Signalled,          // the operation is in flight and stops at its next checkpoint
+    AlreadyFinished,    // the operation had completed before the cancellation arrived
+    UnknownRequest,     // no cancellable operation is registered under this RequestId
+}
+
+
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct TerminateResponse { }
 
@@ -432,3 +616,44 @@ pub struct PluginMessage {
 }
 
 
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(job_count: usize, dependencies: &[(usize, usize)]) -> EnqueueMultipleJobs {
+        let job = RemoteSingularityJob { singularity_container: PathBuf::from("a.sif"), configuration: PathBuf::from("a.json"), working_directory: PathBuf::from(".") };
+        return EnqueueMultipleJobs {
+            jobs: vec![job; job_count],
+            dependencies: dependencies.iter().map(|&(upstream, downstream)| JobDependency { upstream, downstream }).collect(),
+        };
+    }
+
+    #[test]
+    fn diamonds_run_every_job_after_its_upstreams() {
+        // 0 -> 1 -> 3 and 0 -> 2 -> 3
+        let diamond = pipeline(4, &[(0, 1), (0, 2), (1, 3), (2, 3)]);
+        let order = diamond.execution_order().unwrap();
+
+        assert_eq!(order.len(), 4);
+        let position = |job: usize| order.iter().position(|&idx| idx == job).unwrap();
+        for dependency in diamond.dependencies.iter() {
+            assert!(position(dependency.upstream) < position(dependency.downstream));
+        }
+        assert_eq!(diamond.upstreams_of(3), vec![1, 2]);
+        assert_eq!(diamond.upstreams_of(0), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn cycles_and_missing_upstreams_are_rejected() {
+        let cycle = pipeline(3, &[(0, 1), (1, 2), (2, 1)]);
+        assert!(matches!(cycle.execution_order(), Err(RemoteOperationError::IncorrectParameters(_))));
+
+        let missing_upstream = pipeline(2, &[(5, 1)]);
+        assert!(matches!(missing_upstream.execution_order(), Err(RemoteOperationError::IncorrectParameters(_))));
+    }
+}
@@ -0,0 +1,102 @@
+
+use std::io::{Read, Write};
+
+use serde::{Serialize, de::DeserializeOwned};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::data_elements::elements_v1::RemoteOperationError;
+
+
+//################################################################################
+//## Wire encodings
+//## (JSON stays the default, CBOR is selected via Content-Type / Accept headers)
+//################################################################################
+
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+// responses above this size are compressed, if the client accepts gzip
+pub const GZIP_THRESHOLD_BYTES: usize = 16 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum WireEncoding {
+    #[default]
+    Json,
+    Cbor,
+}
+
+impl WireEncoding {
+    pub fn content_type(&self) -> &'static str {
+        return match self {
+            WireEncoding::Json => JSON_CONTENT_TYPE,
+            WireEncoding::Cbor => CBOR_CONTENT_TYPE,
+        };
+    }
+
+    // parameters like "; charset=utf-8" are ignored
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+        return match mime.as_str() {
+            JSON_CONTENT_TYPE => Some(WireEncoding::Json),
+            CBOR_CONTENT_TYPE => Some(WireEncoding::Cbor),
+            _ => None,
+        };
+    }
+
+    // picks the first supported type in the order the client listed them, quality values are not weighed
+    pub fn from_accept_header(accept: &str) -> Self {
+        return accept.split(',')
+            .find_map(|media_range| WireEncoding::from_content_type(media_range))
+            .unwrap_or_default();
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>, RemoteOperationError> {
+        return match self {
+            WireEncoding::Json => {
+                serde_json::to_vec(value).map_err(|e| RemoteOperationError::InternalFailure(format!("{:?}", &e)))
+            },
+            WireEncoding::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map(|_| bytes)
+                    .map_err(|e| RemoteOperationError::InternalFailure(format!("{:?}", &e)))
+            },
+        };
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, RemoteOperationError> {
+        return match self {
+            WireEncoding::Json => {
+                serde_json::from_slice(bytes).map_err(|e| RemoteOperationError::ParsingError(format!("{:?}", &e)))
+            },
+            WireEncoding::Cbor => {
+                ciborium::from_reader(bytes).map_err(|e| RemoteOperationError::ParsingError(format!("{:?}", &e)))
+            },
+        };
+    }
+}
+
+
+
+//################################################################################
+//## Compression
+//################################################################################
+
+pub fn accepts_gzip(accept_encoding: &str) -> bool {
+    return accept_encoding.split(',')
+        .any(|coding| coding.split(';').next().unwrap_or("").trim().eq_ignore_ascii_case("gzip"));
+}
+
+pub fn gzip_compress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    return encoder.finish();
+}
+
+pub fn gzip_decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    return Ok(decompressed);
+}
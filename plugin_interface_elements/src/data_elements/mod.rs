@@ -7,5 +7,8 @@ pub mod elements_v1;
 mod versioned_api;
 pub use versioned_api::*;
 
+mod encoding;
+pub use encoding::*;
+
 
 
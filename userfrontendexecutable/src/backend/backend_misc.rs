@@ -4,7 +4,6 @@
 
 
 use std::error::Error;
-use std::os::windows::process::CommandExt;
 use std::path::PathBuf;
 use std::io::Read;
 use std::process::{Child, Command, ChildStdout};
@@ -28,6 +27,7 @@ extern crate fs_extra;
 //## Constants
 //################################################################################
 
+#[cfg(windows)]
 pub const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 
@@ -36,6 +36,18 @@ pub const CREATE_NO_WINDOW: u32 = 0x08000000;
 //## Utilities
 //################################################################################
 
+// keeps spawned console programs from opening a window of their own, nothing to do elsewhere
+#[cfg(windows)]
+pub fn hide_console_window(command: &mut Command) -> &mut Command {
+    use std::os::windows::process::CommandExt; // Command.creation_flags
+    return command.creation_flags(CREATE_NO_WINDOW);
+}
+
+#[cfg(not(windows))]
+pub fn hide_console_window(command: &mut Command) -> &mut Command {
+    return command;
+}
+
 pub fn wslify_windows_path(windows_path: &str) -> String {
     let windows_path_regex = Regex::new(r#"(?<hd>\w):\\(.*)"#).unwrap();
     let result = windows_path_regex.replace(windows_path, |cpt: &Captures| {
@@ -155,24 +167,3 @@ pub async fn choose_directories(starting_dir: &Option<PathBuf>) -> Option<Vec<Pa
 
     return res;
 }
-
-
-//################################################################################
-//## General WSL command
-//################################################################################
-
-pub fn run_wsl_command(command: &str) -> Result<Child,std::io::Error> {
-
-    println!("Command: /C wsl -d ColonyWSL -e {}", &command);
-    //return Command::new("cmd")
-    //                .args(["/C", "wsl", "-e", &command])
-    //                .args(command_args)
-    //                .spawn();
-    return Command::new("cmd")
-    .creation_flags(CREATE_NO_WINDOW) // create no window
-    .arg(format!("/C wsl -d ColonyWSL -e {}", &command))
-    .env("WSL_UTF8", "1")
-    .stdin(std::process::Stdio::piped())
-    .stdout(std::process::Stdio::piped())
-    .spawn();
-}
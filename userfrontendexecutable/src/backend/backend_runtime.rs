@@ -1,14 +1,10 @@
 
-use std::collections::HashMap;
-use std::os::windows::fs::FileTypeExt;
-use std::os::windows::process::{CommandExt, ExitStatusExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
 use std::sync::mpsc::{Sender, Receiver};
 use std::io::{BufWriter, Write};
-use std::process::Child;
-use std::process::ExitStatus;
 
 use dioxus::signals::SyncSignal;
 use itertools::Itertools;
@@ -17,15 +13,29 @@ use futures_util::StreamExt;
 use regex::Regex;
 
 use crate::pages::AppState;
-use crate::{backend, singularity_run};
+use crate::backend;
 use crate::backend::utils::*;
+use crate::backend::command_runner::*;
+use crate::backend::container_runtime::{ContainerEngine, RuntimePreference, RuntimeVersion, spawn_runtime_detection};
+use crate::backend::container_inspection::ContainerInspection;
+use crate::backend::container_identity::{ContainerIdentity, start_container_identification};
+use crate::backend::run_manifest::RunManifest;
+use crate::backend::run_profile::RunProfile;
 use crate::backend::jobs::*;
+use crate::backend::job_process::JobProcess;
+use crate::backend::process_tree::{terminate_job_processes, TERMINATION_GRACE_PERIOD};
+use crate::backend::{SelfConfiguratorPorts, SessionToken, COLONY_INTEROP_ARGUMENT, SESSION_TOKEN_VARIABLE, FRONTEND_TASK_URL_VARIABLE, frontend_task_url};
+use crate::backend::chat_channels::*;
+use crate::backend::configuration_store::*;
+use crate::backend::configuration_schema::{ConfigurationSchema, SchemaViolation};
 use crate::persistent_state::exe_dir;
 use crate::components::FilesystemData;
 
-use crate::backend::persistent_state::{config_path, PersistentState, PersistentStateUpdate};
+use plugin_interface_elements::elements_v1::{ChannelId, ChatAuthor};
 
-use super::{persistent_state, CREATE_NO_WINDOW};
+use crate::backend::persistent_state::{config_path, IdentificationOutcome, PersistentState, PersistentStateUpdate};
+
+use super::persistent_state;
 
 #[derive(Debug)]
 pub struct CommChannel<T1,T2> {
@@ -92,14 +102,21 @@ pub enum BackendRequest {
     ReadPersistentState,
     UpdatePersistentState(backend::persistent_state::PersistentStateUpdate),
         // backend decides when to save state
+    InformContainerIdentified(Uuid, Result<ContainerIdentity, String>), // container id, content digest computed in the background
+    InformRuntimeDetected(RuntimePreference, Option<RuntimeVersion>), // preference the detection was run for, what was found
     //interacting with singularity containers locally
     QuerySingularity(PathBuf, SingularityQuery), // container path, query object
-    RunSingularity(PathBuf, PathBuf, Vec<String>), // workdir, container path, container_args
-    RunSingularityApp(PathBuf, PathBuf, String, Vec<String>), // workdir, containerpath, app name, app arguments
+    RunSingularity(PathBuf, PathBuf, Vec<String>, Option<RunProfile>), // workdir, container path, container_args, profile if not the container's current one
+    RunSingularityApp(PathBuf, PathBuf, String, Vec<String>, Option<RunProfile>), // workdir, containerpath, app name, app arguments, profile if not the app's current one
+    OpenContainerShell(JobId, PathBuf, PathBuf), // picked by the page, so it can close the shell before it has opened; workdir, container path; runs in a terminal with the container's run profile
+    CloseContainerShell(JobId), // stopped if it still runs, then forgotten together with its output
     StartLocalWebServer(AppState, PathBuf), // page to return to after completion and PathBuf is communication partner container identified by path
-    InformLocalWebserverStarted(Result<u16, Arc<Box<std::io::Error>>>),
+    InformLocalWebserverStarted(Result<SelfConfiguratorPorts, Arc<Box<std::io::Error>>>),
     InformContainerWebserverStarted(Result<u16, Arc<Box<std::io::Error>>>),
+    InformFrontendTaskServerStarted(Result<u16, Arc<Box<std::io::Error>>>),
     AcceptConfiguration(PathBuf, String),
+    InformConfigurationRejected(PathBuf, Vec<SchemaViolation>), // container, why its configuration was not saved
+    StoreConfiguration(PathBuf, String), // container, configuration from the native form; the user picks the file
     SendConfiguration(),
     ExportAnalysisIntoRepository(JobId, PathBuf, PathBuf, PathBuf), // workdir == export target, container path, configuration file (also contains content to
     // Starting and stopping jobs
@@ -107,8 +124,13 @@ pub enum BackendRequest {
     StartJob(PathBuf, String),
     SendJobInfo(JobId),
     SendJobOutput(JobId, usize),
+    SendJobInput(JobId, JobInput), // echoed into the job's output
+    InformJobOutputClosed(JobId), // both output streams of the job have ended
+    ReadJobHistory,
     StopProcess(JobId),
     StopAllProcesses,
+    InformJobTerminated(JobId, Result<(), String>), // no process of the job is left, or why some could not be stopped
+    InformAllJobsTerminated(Vec<(JobId, Result<(), String>)>),
     StopProgram,
 
     // file interactions and remote file interactions
@@ -116,6 +138,12 @@ pub enum BackendRequest {
     ListDirectory(FilesystemData, PathBuf),
     MoveContent(PathBuf, PathBuf),
     DownloadContent(String, PathBuf),
+
+    // chat channels between plugins / running containers and the user
+    InformChatChannelUpdated(ChannelId),
+    ReadChatChannels,
+    AnswerChatChannel(ChannelId, String),
+    CloseChatChannel(ChannelId),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -127,24 +155,34 @@ pub enum BackendResponse {
     //Reading, Updating, Writing persistent state
     PersistentState(PersistentState), // Updates either all return the newly updated state, or nothing at all
     AddedNewContainer(persistent_state::BackendContainerDescription),
+    ContainerIdentified(persistent_state::BackendContainerDescription),
+    ContainerMoved(Uuid, persistent_state::BackendContainerDescription), // id registered for the new path, container that took it over
     //interacting with singularity containers locally
     SingularityInfo(PathBuf, Option<SingularityResponse>),
-    LocalWebServerStarted(Option<u16>), // port number
+    LocalWebServerStarted(Result<SelfConfiguratorPorts, String>), // ports of the handshake, or why the server could not start
     ContainerWebServerStarted(Option<u16>), // port number
     Configuration(PathBuf, String), // container identified by path, and the corresponding configuration
+    ConfigurationRejected(PathBuf, Vec<SchemaViolation>), // the configuration does not match the container's schema
     #[allow(dead_code)]
     ExportedAnalysisIntoRepository(Option<PathBuf>), // workdir == export target, if successfull
     // Starting and stopping jobs
+    JobStarted(JobId, PathBuf, Option<String>, PathBuf), // container path, app (None for the main app), working directory
     JobInfo(JobId, JobState),
-    JobOutput(JobId, Vec<String>),
+    JobNotListed(JobId),
+    JobOutput(JobId, usize, Vec<JobOutputLine>), // index of the first line sent, lines from there on
     StoppedProcess(JobId),
     StoppedAllProcesses,
+    JobStopFailed(JobId, String), // processes of the job are still running
+    JobHistory(Vec<JobRecord>), // newest first
+    ContainerShellOpened(JobId, Result<(), String>),
     // TODO: declare Error types
     // file interactions and remote file interactions
     FileCreated(Option<PathBuf>),
     #[allow(unused)]
     FileList(Vec<String>),
     ListDirectory(Result<DirectoryContents, Result<DirectoryContents, ()>>),
+    // chat channels
+    ChatChannels(Vec<ChatChannel>),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -184,12 +222,12 @@ pub enum SingularityQuery { // container identifier is in the Query enum
 pub enum SingularityResponse {
     AppList(Vec<String>),
     AppRequirements(String),
-    AppConfigurationOptions(String),
-    Inspection(serde_json::Value),
-    RunHelp(String),
-    AppHelp(String),
-    RunLabels(String),
-    AppLabels(String),
+    AppConfigurationOptions(Option<String>),        // None if the container publishes no schema
+    Inspection(ContainerInspection),
+    RunHelp(Option<String>),                        // None if the container has no help text, so it is not asked again
+    AppHelp(String, Option<String>),                // app name, help text
+    RunLabels(BTreeMap<String, String>),            // empty if there are none or they could not be read
+    AppLabels(String, BTreeMap<String, String>),    // app name, labels
 }
 
 #[allow(unused)]
@@ -204,21 +242,10 @@ pub enum JobQuery {
 pub enum JobResponse {
     JobScheduled(JobId),
     JobState(JobId, JobState),
-    JobOutput(JobId, String, String) // StdOut, StdErr
+    JobOutput(JobId, Vec<JobOutputLine>) // both streams, in the order they were written
 }
 
 
-#[allow(unused)]
-#[derive(Debug, Clone, PartialEq)]
-pub enum JobState {
-    Scheduled,
-    Submitted,
-    Running,
-    Completed(ExitStatus),
-    Unknown,
-    JobNotListed
-}
-
 pub type FrontendCommChannel = CommChannel<BackendResponse, BackendRequest>;
 pub type BackendCommChannel = CommChannel<BackendRequest, BackendResponse>;
 
@@ -232,11 +259,11 @@ pub type BackendCommChannel = CommChannel<BackendRequest, BackendResponse>;
 //type ProcessStore = HashMap<JobId, Arc<Mutex<Process>>>;              //TODO: are configurations stored here, too?
 
 type ContainerJobStore = HashMap<PathBuf, Arc<Mutex<Vec<JobId>>>>;
-type JobOutputStore = HashMap<JobId, Arc<Mutex<Vec<String>>>>;
+type JobOutputStore = HashMap<JobId, SharedJobOutput>;
 
 
 struct ProcessStore {
-        store: HashMap<JobId, Arc<Mutex<Child>>>
+        store: HashMap<JobId, Arc<Mutex<JobProcess>>>
 }
 
 #[allow(unused)]
@@ -245,15 +272,15 @@ impl ProcessStore {
         return ProcessStore { store: HashMap::new() };
     }
 
-    pub fn insert(&mut self, key: JobId, ch: Arc<Mutex<Child>>) {
+    pub fn insert(&mut self, key: JobId, ch: Arc<Mutex<JobProcess>>) {
         self.store.insert(key, ch);
     }
 
-    pub fn get(&mut self, jbd: &JobId) -> Option<&Arc<Mutex<Child>>> {
+    pub fn get(&mut self, jbd: &JobId) -> Option<&Arc<Mutex<JobProcess>>> {
         return self.store.get(jbd);
     }
 
-    pub fn remove(&mut self, jbd: &JobId) -> Option<Arc<Mutex<Child>>>{
+    pub fn remove(&mut self, jbd: &JobId) -> Option<Arc<Mutex<JobProcess>>>{
         return self.store.remove(&jbd);
     }
 }
@@ -287,6 +314,77 @@ impl Drop for ProcessStore {
 //    }
 //}
 
+// runs reach the frontend task server through their environment, like the session token it is kept out of the manifest
+fn with_task_server(profile: &RunProfile, task_server_url: &Option<String>) -> RunProfile {
+    let mut run_profile = profile.clone();
+    if let Some(url) = task_server_url {
+        run_profile.environment.push((FRONTEND_TASK_URL_VARIABLE.to_string(), url.clone()));
+    }
+    return run_profile;
+}
+
+// every run leaves a record of what exactly was run next to its results
+fn write_run_manifest(persistent_state: &PersistentState, container_engine: &ContainerEngine, mut manifest: RunManifest) -> RunManifest {
+    manifest.container_digest = persistent_state.containers.iter()
+                                    .find(|bcd| bcd.path == manifest.container_path)
+                                    .and_then(|bcd| bcd.digest().cloned());
+    if manifest.container_digest.is_none() {
+        println!("Container {:?} has not been hashed yet, run manifest will not contain its digest", &manifest.container_path);
+    }
+    manifest.runtime = container_engine.version().map(|version| version.to_string());
+
+    match manifest.write_to_dir(&manifest.working_directory) {
+        Ok(path) => { println!("Run manifest written to {:?}", &path); },
+        Err(e) => { println!("{}", &e); }
+    }
+    return manifest;
+}
+
+fn save_job_history(job_history: &JobHistory) {
+    if let Err(e) = job_history.write_to_file() {
+        println!("{}", &e);
+    }
+}
+
+// the backend is told once the job's output is complete, to write its log
+fn watch_job_output(job_id: JobId, readers: Vec<std::thread::JoinHandle<()>>, comm_with_backend: Sender<BackendRequest>) {
+    std::thread::spawn(move || {
+        for reader in readers { reader.join().ok(); }
+        comm_with_backend.send(BackendRequest::InformJobOutputClosed(job_id)).ok();
+    });
+}
+
+// killing the process the launcher started only ends a wrapper like wsl.exe, so every process of the job is terminated
+fn terminate_job(runner: &dyn CommandRunner, job_id: &JobId, process: Option<Arc<Mutex<JobProcess>>>) -> Result<(), String> {
+    let result = terminate_job_processes(runner, job_id, TERMINATION_GRACE_PERIOD);
+    if let Some(process) = process {
+        if let Ok(mut process) = process.lock() {
+            if !matches!(process.try_wait(), Ok(Some(_))) { process.kill().ok(); }
+        }
+    }
+    return result;
+}
+
+// takes up to the grace period, so jobs are terminated side by side, off the backend loop
+fn spawn_job_termination(runner: Arc<dyn CommandRunner>, jobs: Vec<(JobId, Option<Arc<Mutex<JobProcess>>>)>, stop_all: bool, comm_with_backend: Sender<BackendRequest>) {
+    std::thread::spawn(move || {
+        let results = std::thread::scope(|scope| {
+            let runner = runner.as_ref();
+            let terminations = jobs.into_iter()
+                                   .map(|(job_id, process)| scope.spawn(move || (job_id, terminate_job(runner, &job_id, process))))
+                                   .collect_vec();
+            return terminations.into_iter().filter_map(|termination| termination.join().ok()).collect_vec();
+        });
+        if stop_all {
+            comm_with_backend.send(BackendRequest::InformAllJobsTerminated(results)).ok();
+        } else {
+            for (job_id, result) in results {
+                comm_with_backend.send(BackendRequest::InformJobTerminated(job_id, result)).ok();
+            }
+        }
+    });
+}
+
 //################################################################################
 //## running the background thread content
 //################################################################################
@@ -296,19 +394,64 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
     let mut container_infos = ContainerJobStore::new();
     let mut process_store = ProcessStore::new();
     let mut process_outputs = JobOutputStore::new();
+    let mut job_history = JobHistory::from_file(&job_history_path());
+    let mut cancelled_jobs = HashSet::<JobId>::new(); // stopped by the user, whatever their exit status says
+    let mut stopping_jobs = HashSet::<JobId>::new(); // running when the user stopped them, cancelled once termination is confirmed
+    let mut session_token = None as Option<SessionToken>; // of the frontend-local server, once it has been started
+    let task_server_token = SessionToken::new(); // of the frontend task server, for as long as the launcher runs
+    let mut task_server_url = None as Option<String>; // handed to every run, once the frontend task server listens
 
     let mut persistent_state = PersistentState::from_file(config_path());
 
+    // everything the backend executes goes through these, so it can be replaced for tests
+    let host_runner = NativeCommandRunner::default();
+    let mut container_engine = ContainerEngine::assume(default_container_runner(), &persistent_state.container_runtime);
+    spawn_runtime_detection(Arc::clone(&container_engine.runner), persistent_state.container_runtime, comm_with_frontend.backsender.clone());
+
+    let identification_queue = start_container_identification(comm_with_frontend.backsender.clone());
+    for bcd in persistent_state.containers.iter().filter(|bcd| bcd.needs_identification()) {
+        identification_queue.send((bcd.id, bcd.path.clone())).ok();
+    }
+
+    let chat_store: SharedChatStore = Arc::new(Mutex::new(ChatStore::from_file(&chat_history_path())));
+    let configuration_store: SharedConfigurationStore = Arc::new(Mutex::new(ConfigurationStore::from_file(&configuration_store_path())));
+    backend::start_frontend_task_server(Arc::clone(&chat_store), Arc::clone(&configuration_store), task_server_token.clone(), comm_with_frontend.backsender.clone()).await;
+
     loop {
         let message = comm_with_frontend.receiver.recv();
         //println!("Backend received message: {:?}", message);
         match message {
             Ok(task) => match task {
+                // there is no WSL to set up outside of windows, the container runtime has to be installed natively
+                BackendRequest::CheckWSL(mut output_collection, _child_output_count)
+                | BackendRequest::CheckWslV2(mut output_collection, _child_output_count) if cfg!(not(windows)) => {
+                    let msg = "Backend tasked with: Checking native container runtime installation";
+                    println!("{}", &msg);
+                    output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
+                    comm_with_frontend.send(BackendResponse::InstallStepCompleted(InstallationState::InstallationStarted)).ok();
+
+                    container_engine.redetect(&persistent_state.container_runtime);
+                    match container_engine.version() {
+                        Some(version) => {
+                            let msg = format!("{} installed: {}", version.runtime.display_name(), &version);
+                            println!("{}", &msg);
+                            output_collection.lock().as_mut().map(|outp| outp.push(msg)).ok();
+                            comm_with_frontend.send(BackendResponse::InstallStepCompleted(InstallationState::InstallingSingularity)).ok();
+                            comm_with_frontend.send(BackendResponse::InstallStepCompleted(InstallationState::InstallationEnded)).ok();
+                        },
+                        None => {
+                            let msg = "Neither Singularity nor Apptainer could be found. Please install one of them and make sure it is on the PATH.";
+                            println!("{}", &msg);
+                            output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
+                            comm_with_frontend.send(BackendResponse::InstallStepCompleted(InstallationState::InstallationFailed)).ok();
+                        }
+                    }
+                },
                 BackendRequest::CheckWSL(mut output_collection, _child_output_count) => {
                     let msg = "Backend tasked with: Checking WSL";
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
                     println!("{}", &msg);
-                    match backend::check_wsl() {
+                    match backend::check_wsl(&host_runner) {
                         Ok(out) => {
                             let msg = String::from_utf8(out.stdout.clone())
                                         .unwrap_or_else(|_| {
@@ -339,8 +482,8 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Installing WSL...";
                     println!("{}", &msg);
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
-                    //match backend::install_wsl_only_child() {
-                    match backend::install_wsl_only_child() {
+                    //match backend::install_wsl_only_child(&host_runner) {
+                    match backend::install_wsl_only_child(&host_runner) {
                         Ok(mut child) => {
                             backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child)
                         },
@@ -353,7 +496,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Updating WSL...";
                     println!("{}", &msg);
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
-                    match backend::update_wsl_only_child() {
+                    match backend::update_wsl_only_child(&host_runner) {
                         Ok(mut child) => {
                             backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
                         },
@@ -369,13 +512,13 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Backend tasked with: Exporting and Reimporting WSL";
                     println!("{}", &msg);
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
-                    let distros = backend::find_wsl_distros();
+                    let distros = backend::find_wsl_distros(&host_runner);
                     if !distros.contains(&"Ubuntu".to_owned()) {
 
                         if distros.contains(&"DEFAULT_DISTRO_NOT_FOUND".to_string()) {
                             let msg = "Setting Default Distribution to Ubuntu...";
                             println!("{}", &msg);
-                            match backend::set_wsl_set_default_distro_child() {
+                            match backend::set_wsl_set_default_distro_child(&host_runner) {
                                 Ok(mut child) => {
                                     backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
                                 },
@@ -396,7 +539,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         //################################################################################
                         //backend::install_wsl_ubuntu();
 
-                        match backend::install_wsl_ubuntu_child() {
+                        match backend::install_wsl_ubuntu_child(&host_runner) {
                             Ok(mut child) => {
                                 backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
                             },
@@ -425,7 +568,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
                         let pathstr = path.to_string_lossy();
                         //backend::wsl_export_reimport_ubuntu(&pathstr);
-                        match backend::wsl_export_ubuntu_child(&pathstr) {
+                        match backend::wsl_export_ubuntu_child(&host_runner, &pathstr) {
                             Ok(mut child) => {
                                 //backend::bufread_child_stdout(backend_output, child);
                                 backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
@@ -437,7 +580,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                             }
                         }
 
-                        match backend::wsl_reimport_ubuntu_child(&pathstr) {
+                        match backend::wsl_reimport_ubuntu_child(&host_runner, &pathstr) {
                             Ok(mut child) => {
                                 //backend::bufread_child_stdout(backend_output, child);
                                 backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
@@ -455,22 +598,20 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Backend tasked with: Installing Singularity";
                     println!("{}", &msg);
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
-                    match backend::check_singularity_version() {
-                        Ok(response) => {
-                            let msg = format!("Singularity installed: {}", match std::str::from_utf8(&response.stdout) {
-                                Ok(s) => s,
-                                Err(_) => "Something went wrong"
-                            });
+                    container_engine.redetect(&persistent_state.container_runtime);
+                    match container_engine.version() {
+                        Some(version) => {
+                            let msg = format!("{} installed: {}", version.runtime.display_name(), &version);
                             println!("{}", &msg);
-                            output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
+                            output_collection.lock().as_mut().map(|outp| outp.push(msg)).ok();
                         },
-                        Err(_) => {
+                        None => {
                             let msg = "Installing Singularity...";
                             println!("{}", &msg);
                             output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
                             //backend::install_singularity().ok();
 
-                            match backend::install_singularity_child() {
+                            match backend::install_singularity_child(&container_engine) {
                                 Ok(mut child) => {
                                     //backend::bufread_child_stdout(backend_output, child);
                                     backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
@@ -504,7 +645,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Backend tasked with: Checking WSL";
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
                     println!("{}", &msg);
-                    match backend::check_wsl() {
+                    match backend::check_wsl(&host_runner) {
                         Ok(out) => {
                             let msg = String::from_utf8(out.stdout.clone())
                                         .unwrap_or_else(|_| {
@@ -535,8 +676,8 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Installing WSL...";
                     println!("{}", &msg);
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
-                    //match backend::install_wsl_only_child() {
-                    match backend::install_wsl_only_child() {
+                    //match backend::install_wsl_only_child(&host_runner) {
+                    match backend::install_wsl_only_child(&host_runner) {
                         Ok(mut child) => {
                             backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child)
                         },
@@ -550,7 +691,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Updating WSL...";
                     println!("{}", &msg);
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
-                    match backend::update_wsl_only_child() {
+                    match backend::update_wsl_only_child(&host_runner) {
                         Ok(mut child) => {
                             backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
                         },
@@ -566,7 +707,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                     let msg = "Backend tasked with: Importing WSL Linux Distribution";
                     println!("{}", &msg);
                     output_collection.lock().as_mut().map(|outp| outp.push(String::from(msg))).ok();
-                    let distros = backend::find_wsl_distros();
+                    let distros = backend::find_wsl_distros(&host_runner);
                     if !distros.iter().any(|distro| distro.starts_with("ColonyWSL")) {
                         let mut distro_path = exe_dir().clone().unwrap_or_else(PathBuf::new);
                         distro_path.push("assets");
@@ -574,7 +715,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         if !distro_path.is_file() {
                             comm_with_frontend.send(BackendResponse::InstallStepCompleted(InstallationState::DistributionWasNotFound)).ok();
                             std::thread::sleep(std::time::Duration::from_millis(1500));
-                            match backend::wsl_manually_import_distro() {
+                            match backend::wsl_manually_import_distro(&host_runner) {
                                 Ok(mut child) => {
                                     backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
                                 },
@@ -589,7 +730,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         else {
                             println!("Trying to import: {:?}", &distro_path);
                             let pathstr = distro_path.to_str().unwrap();
-                            match backend::wsl_import_colony_wsl_child(&pathstr) {
+                            match backend::wsl_import_colony_wsl_child(&host_runner, &pathstr) {
                                 Ok(mut child) => {
                                     //backend::bufread_child_stdout(backend_output, child);
                                     backend::bufread_child_stdout_bytes_into_messages(&mut output_collection, &mut child);
@@ -604,10 +745,11 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         }
 
                     }
-                    let distros = backend::find_wsl_distros();
+                    let distros = backend::find_wsl_distros(&host_runner);
                     if !distros.iter().any(|distro| distro.starts_with("ColonyWSL")) {
                         comm_with_frontend.send(BackendResponse::InstallStepCompleted(InstallationState::InstallationFailed)).ok();
                     } else {
+                        container_engine.redetect(&persistent_state.container_runtime);
                         comm_with_frontend.send(BackendResponse::InstallStepCompleted(InstallationState::ImportingDistribution)).ok();
                     }
 
@@ -644,6 +786,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                             persistent_state.containers.dedup_by(|elem1, elem2| elem1.path == elem2.path);
 
                             if persistent_state.containers.iter().any(|elem| elem.id == bcd.id) {
+                                identification_queue.send((bcd.id, bcd.path.clone())).ok();
                                 comm_with_frontend.send(BackendResponse::AddedNewContainer(bcd)).ok();
                             }
                         },
@@ -659,6 +802,17 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                             println!("Setting last_selected_container_dir {:?}", &ptb);
                             persistent_state.last_selected_container_dir = ptb;
                         },
+                        PersistentStateUpdate::SetContainerRuntime(preference) => {
+                            println!("Setting container runtime preference {:?}", &preference);
+                            persistent_state.container_runtime = preference;
+                            spawn_runtime_detection(Arc::clone(&container_engine.runner), preference, comm_with_frontend.backsender.clone());
+                        },
+                        PersistentStateUpdate::SetRunProfile(container_id, app, profile) => {
+                            println!("Setting run profile of {:?} (app {:?}) to {:?}", &container_id, &app, &profile);
+                            if let Some(bcd) = persistent_state.containers.iter_mut().find(|bcd| bcd.id == container_id) {
+                                bcd.set_run_profile(app, profile);
+                            }
+                        },
                         //PersistentStateUpdate::AddRecentProtocol(_ptb) => { todo!() },
 //                        PersistentStateUpdate::DeleteProtocol(_ptb) => { todo!() },
 //                        PersistentStateUpdate::ArchiveProtocol(_ptb) => { todo!() },
@@ -670,64 +824,104 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         None => {}
                     }
                 },
+                BackendRequest::InformRuntimeDetected(preference, version) => {
+                    // answers for a preference the user has changed since are outdated
+                    if preference == persistent_state.container_runtime {
+                        container_engine.use_detected(version, &preference);
+                    }
+                },
+                BackendRequest::InformContainerIdentified(container_id, identity) => {
+                    match identity {
+                        Ok(identity) => {
+                            println!("Container {:?} identified as {}", &container_id, &identity.digest);
+                            match persistent_state.apply_identity(container_id, identity) {
+                                IdentificationOutcome::Identified(bcd) => {
+                                    comm_with_frontend.send(BackendResponse::ContainerIdentified(bcd)).ok();
+                                },
+                                IdentificationOutcome::Moved { removed, kept } => {
+                                    println!("Container {:?} is {:?} at its new path", &removed, &kept.id);
+                                    comm_with_frontend.send(BackendResponse::ContainerMoved(removed, kept)).ok();
+                                },
+                                IdentificationOutcome::UnknownContainer => {} // removed while it was hashed
+                            }
+                            if let Some(cpath) = config_path() { persistent_state.write_to_file(cpath); }
+                        },
+                        Err(e) => { println!("{}", &e); }
+                    }
+                },
                 BackendRequest::QuerySingularity(container_path, query) => {
                     match query {
                         SingularityQuery::AppList => {
-                            match backend::singularity_app_list(&container_path) {
+                            match backend::sif_app_list(&container_path).or_else(|| backend::singularity_app_list(&container_engine, &container_path)) {
                                 Some(msg) => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::AppList(msg)))).ok();},
                                 None => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, None)).ok();}
                             }
                         },
                         SingularityQuery::Inspection => {
-                            match backend::singularity_inspection(&container_path) {
+                            match backend::container_inspection(&container_engine, &container_path) {
                                 Some(msg) => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::Inspection(msg)))).ok();},
                                 None => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, None)).ok();}
                             }
                         },
-                        SingularityQuery::RunHelp => {todo!()},
-                        SingularityQuery::AppHelp(_appname) => {todo!()},
-                        SingularityQuery::RunLabels => {todo!()},
-                        SingularityQuery::AppLabels(_appname) => {todo!()},
+                        // missing help and labels are answered as well, so the frontend does not ask again
+                        SingularityQuery::RunHelp => {
+                            let help = backend::singularity_help(&container_engine, &container_path, None);
+                            comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::RunHelp(help)))).ok();
+                        },
+                        SingularityQuery::AppHelp(appname) => {
+                            let help = backend::singularity_help(&container_engine, &container_path, Some(&appname));
+                            comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::AppHelp(appname, help)))).ok();
+                        },
+                        SingularityQuery::RunLabels => {
+                            let labels = backend::singularity_labels(&container_engine, &container_path, None).unwrap_or_default();
+                            comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::RunLabels(labels)))).ok();
+                        },
+                        SingularityQuery::AppLabels(appname) => {
+                            let labels = backend::singularity_labels(&container_engine, &container_path, Some(&appname)).unwrap_or_default();
+                            comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::AppLabels(appname, labels)))).ok();
+                        },
                         // Specific to our containers
                         SingularityQuery::AppRequirements => {
-                            match backend::singularity_app_requirements(&container_path) {
+                            match backend::singularity_app_requirements(&container_engine, &container_path) {
                                 Some(msg) => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::AppRequirements(msg)))).ok();},
                                 None => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, None)).ok();}
                             }
                         },
                         SingularityQuery::AppConfigurationOptions => {
-                            match backend::singularity_app_configuration_options(&container_path) {
-                                Some(msg) => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::AppConfigurationOptions(msg)))).ok();},
-                                None => {comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, None)).ok();}
-                            }
+                            let options = backend::singularity_app_configuration_options(&container_engine, &container_path);
+                            comm_with_frontend.send(BackendResponse::SingularityInfo(container_path, Some(SingularityResponse::AppConfigurationOptions(options)))).ok();
                         },
                     }
                 },
-                BackendRequest::RunSingularity(workdir, container_path, container_args) => {
+                BackendRequest::RunSingularity(workdir, container_path, container_args, profile) => {
                     println!("Backend tasked with: Starting Container {:?}", &container_path);
-                    let child_result = backend::singularity_run_in_dir(&workdir, &container_path, container_args);
+                    let manifest_args = container_args.clone();
+                    let profile = profile.unwrap_or_else(|| persistent_state.run_profile_for(&container_path, None));
+                    let run_profile = with_task_server(&profile, &task_server_url);
+                    let job_id = JobId::new();
+                    let child_result = backend::singularity_run_in_dir(&container_engine, &run_profile, &job_id, &workdir, &container_path, container_args);
 
                     match child_result {
-                        Ok(mut child) => {
-                            //let jid = JobId::new(format!("Running: {}", &container_path));
-                            let job_id = JobId::new();
+                        Ok(mut process) => {
                             let output_collector = process_outputs
                                                     .entry(job_id.clone())
                                                     .or_insert_with(|| Arc::new(Mutex::new(Vec::new())));
-                            let mut output_collector = Arc::clone(&output_collector);
-                            println!("Command: {:?}", &child);
+                            let output_collector = Arc::clone(&output_collector);
+                            println!("Command: {:?}", &process);
 
-                            let job_id_coll = container_infos.entry(PathBuf::from(container_path))
+                            let job_id_coll = container_infos.entry(container_path.clone())
                                 .or_insert(Arc::new(Mutex::new(Vec::new() as Vec<JobId>)));
                             job_id_coll.lock().map(|mut vec_jid| vec_jid.push(job_id.clone())).ok();
+                            let manifest = write_run_manifest(&persistent_state, &container_engine, RunManifest::new(job_id, &workdir, &container_path, None, manifest_args, profile));
+                            job_history.record(JobRecord::from_manifest(&manifest));
+                            save_job_history(&job_history);
 
-                            let mut child_stdout = child.stdout.take().unwrap();
-                            tokio::spawn(async move {
-                                backend::bufread_stdout_bytes_into_messages(&mut output_collector, &mut child_stdout);
-                            });
+                            let readers = process.spawn_output_readers(&output_collector);
+                            watch_job_output(job_id, readers, comm_with_frontend.backsender.clone());
 
-                            process_store.insert(job_id.clone(), Arc::new(Mutex::new(child)));
+                            process_store.insert(job_id.clone(), Arc::new(Mutex::new(process)));
 
+                            comm_with_frontend.send(BackendResponse::JobStarted(job_id, container_path, None, workdir)).ok();
                             comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Running)).ok();
                         },
                         Err(e) => {
@@ -735,34 +929,71 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         }
                     }
                 },
-                BackendRequest::RunSingularityApp(workdir, container_path, app_name, app_args) => {
+                BackendRequest::RunSingularityApp(workdir, container_path, app_name, app_args, profile) => {
                     println!("Backend tasked with: Starting App '{}' of container {:?}", &app_name, &container_path);
-                    let child_result = backend::singularity_run_app_in_dir(&workdir, &container_path, &app_name, app_args);
+                    let manifest_args = app_args.clone();
+                    let profile = profile.unwrap_or_else(|| persistent_state.run_profile_for(&container_path, Some(&app_name)));
+                    // apps that talk to the launcher get the session token, it is kept out of the manifest
+                    let mut run_profile = with_task_server(&profile, &task_server_url);
+                    if let (Some(token), true) = (&session_token, app_args.iter().any(|arg| arg == COLONY_INTEROP_ARGUMENT)) {
+                        run_profile.environment.push((SESSION_TOKEN_VARIABLE.to_string(), token.as_str().to_string()));
+                    }
+                    let job_id = JobId::new();
+                    let child_result = backend::singularity_run_app_in_dir(&container_engine, &run_profile, &job_id, &workdir, &container_path, &app_name, app_args);
                     match child_result {
-                        Ok(mut child) => {
-                            println!("Command: {:?}", &child);
-                            let job_id = JobId::new();
+                        Ok(mut process) => {
+                            println!("Command: {:?}", &process);
                             let output_collector = process_outputs
                                                     .entry(job_id.clone())
                                                     .or_insert_with(|| Arc::new(Mutex::new(Vec::new())));
-                            let mut output_collector = Arc::clone(&output_collector);
+                            let output_collector = Arc::clone(&output_collector);
 
-                            let job_id_coll = container_infos.entry(PathBuf::from(container_path))
+                            let job_id_coll = container_infos.entry(container_path.clone())
                                 .or_insert(Arc::new(Mutex::new(Vec::new() as Vec<JobId>)));
                             job_id_coll.lock().map(|mut vec_jid| vec_jid.push(job_id.clone())).ok();
+                            let manifest = write_run_manifest(&persistent_state, &container_engine, RunManifest::new(job_id, &workdir, &container_path, Some(app_name.clone()), manifest_args, profile));
+                            job_history.record(JobRecord::from_manifest(&manifest));
+                            save_job_history(&job_history);
 
-                            let mut child_stdout = child.stdout.take().unwrap();
-                            tokio::spawn(async move {
-                                backend::bufread_stdout_bytes_into_messages(&mut output_collector, &mut child_stdout);
-                            });
+                            let readers = process.spawn_output_readers(&output_collector);
+                            watch_job_output(job_id, readers, comm_with_frontend.backsender.clone());
 
-                            process_store.insert(job_id.clone(), Arc::new(Mutex::new(child)));
+                            process_store.insert(job_id.clone(), Arc::new(Mutex::new(process)));
 
+                            comm_with_frontend.send(BackendResponse::JobStarted(job_id, container_path, Some(app_name), workdir)).ok();
                             comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Running)).ok();
                         },
                         Err(_) => {}
                     }
                 },
+                BackendRequest::OpenContainerShell(job_id, workdir, container_path) => {
+                    println!("Backend tasked with: Opening a shell in container {:?}", &container_path);
+                    let profile = with_task_server(&persistent_state.run_profile_for(&container_path, None), &task_server_url);
+                    match backend::singularity_shell_in_dir(&container_engine, &profile, &job_id, &workdir, &container_path) {
+                        Ok(mut process) => {
+                            // not a run, so there is neither a manifest nor an entry in the job history
+                            let output_collector = process_outputs
+                                                    .entry(job_id.clone())
+                                                    .or_insert_with(|| Arc::new(Mutex::new(Vec::new())));
+                            process.spawn_output_readers(output_collector);
+                            process_store.insert(job_id.clone(), Arc::new(Mutex::new(process)));
+                            comm_with_frontend.send(BackendResponse::ContainerShellOpened(job_id, Ok(()))).ok();
+                        },
+                        Err(e) => {
+                            println!("Error opening container shell: {:?}", &e);
+                            comm_with_frontend.send(BackendResponse::ContainerShellOpened(job_id, Err(e.to_string()))).ok();
+                        }
+                    }
+                },
+                BackendRequest::CloseContainerShell(job_id) => {
+                    process_outputs.remove(&job_id);
+                    if let Some(process) = process_store.remove(&job_id) {
+                        let running = process.lock().map(|mut process| matches!(process.try_wait(), Ok(None))).unwrap_or(false);
+                        if running {
+                            spawn_job_termination(Arc::clone(&container_engine.runner), vec![(job_id, Some(process))], false, comm_with_frontend.backsender.clone());
+                        }
+                    }
+                },
                 BackendRequest::DownloadContent(source_url, destination_path) => {
 
                     //let file_errored = false;
@@ -810,22 +1041,14 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                 },
                 BackendRequest::MoveContent(source_path, destination_path) => {
                     let mut operation_succeeded = false;
-                    if source_path.is_file() {
-                        match std::process::Command::new("cmd /C mv -f")
-                                .args([&source_path.to_string_lossy().into_owned(), &destination_path.to_string_lossy().into_owned()])
-                                .creation_flags(CREATE_NO_WINDOW)
-                                .output() {
-                                    Ok(_) => {operation_succeeded = true;},
-                                    Err(_) => {operation_succeeded = false;}
-                        }
-
-                    } else if source_path.is_dir() {
-                        match std::process::Command::new("cmd /C mv -rf")
-                                .args([&source_path.to_string_lossy().into_owned(), &destination_path.to_string_lossy().into_owned()])
-                                .creation_flags(CREATE_NO_WINDOW)
-                                .output() {
-                                    Ok(_) => {operation_succeeded = true;},
-                                    Err(_) => {operation_succeeded = false;}
+                    if source_path.is_file() || source_path.is_dir() {
+                        let move_command = CommandSpec::new("mv")
+                                                .arg("-f")
+                                                .arg(container_engine.host_path(&source_path))
+                                                .arg(container_engine.host_path(&destination_path));
+                        match container_engine.runner.output(&move_command) {
+                            Ok(output) => {operation_succeeded = output.status.success();},
+                            Err(_) => {operation_succeeded = false;}
                         }
                     }
                     if operation_succeeded {comm_with_frontend.send(BackendResponse::FileCreated(Some(destination_path))).ok();}
@@ -838,25 +1061,62 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                                                     .entry(job_id.clone())
                                                     .or_insert_with(|| Arc::new(Mutex::new(Vec::new())));
 
+                    let token = SessionToken::new();
+                    session_token = Some(token.clone());
+
+                    // containers that publish no schema only have their configurations checked for being JSON
+                    let configuration_schema = match backend::singularity_app_configuration_options(&container_engine, &comm_partner_container) {
+                        Some(published) => match ConfigurationSchema::parse(&published) {
+                            Ok(schema) => Some(schema),
+                            Err(e) => { println!("{}", &e); None }
+                        },
+                        None => None,
+                    };
+
                     let comm_with_backend = comm_with_frontend.backsender.clone();
-                    backend::start_frontend_local_server(followup_page, comm_partner_container, job_id, comm_with_backend).await;
+                    backend::start_frontend_local_server(followup_page, comm_partner_container, job_id, token, configuration_schema, comm_with_backend).await;
 
                     // webserver thread will notify backend about success
 
                     println!("Frontend-local webserver has been requested");
                 },
-                BackendRequest::InformLocalWebserverStarted(maybe_port) => {
-                    match maybe_port {
-                        Ok(port_number) => {
-                            comm_with_frontend.send(BackendResponse::LocalWebServerStarted(Some(port_number))).ok();
-                            println!("Frontend-local webserver has started");
+                BackendRequest::InformChatChannelUpdated(_) | BackendRequest::ReadChatChannels => {
+                    if let Ok(store) = chat_store.lock() {
+                        comm_with_frontend.send(BackendResponse::ChatChannels(store.channels.clone())).ok();
+                    }
+                },
+                BackendRequest::AnswerChatChannel(channel_id, text) => {
+                    if let Ok(mut store) = chat_store.lock() {
+                        if let Err(e) = store.append_message(&channel_id, ChatAuthor::User, text) {
+                            println!("Could not answer chat channel: {:?}", &e);
+                        }
+                        comm_with_frontend.send(BackendResponse::ChatChannels(store.channels.clone())).ok();
+                    }
+                },
+                BackendRequest::CloseChatChannel(channel_id) => {
+                    if let Ok(mut store) = chat_store.lock() {
+                        store.close_channel(&channel_id).ok();
+                        comm_with_frontend.send(BackendResponse::ChatChannels(store.channels.clone())).ok();
+                    }
+                },
+                BackendRequest::InformLocalWebserverStarted(maybe_ports) => {
+                    match maybe_ports {
+                        Ok(ports) => {
+                            comm_with_frontend.send(BackendResponse::LocalWebServerStarted(Ok(ports))).ok();
+                            println!("Frontend-local webserver has started on port {}", ports.launcher);
                         },
-                        Err(_) => {
-                            comm_with_frontend.send(BackendResponse::LocalWebServerStarted(None)).ok();
-                            println!("Frontend-local webserver has failed to start");
+                        Err(e) => {
+                            comm_with_frontend.send(BackendResponse::LocalWebServerStarted(Err(e.to_string()))).ok();
+                            println!("Frontend-local webserver has failed to start: {:?}", &e);
                         }
                     }
                 },
+                BackendRequest::InformFrontendTaskServerStarted(maybe_port) => {
+                    match maybe_port {
+                        Ok(port) => { task_server_url = Some(frontend_task_url(port, &task_server_token)); },
+                        Err(e) => { println!("Runs will not be able to reach the frontend task server: {:?}", &e); }
+                    }
+                },
                 BackendRequest::InformContainerWebserverStarted(maybe_port) => {
                     match maybe_port {
                         Ok(port_number) => {
@@ -869,6 +1129,28 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         }
                     }
                 },
+                BackendRequest::StoreConfiguration(container, configuration) => {
+                    // the file dialog would block the backend otherwise
+                    let comm_with_backend = comm_with_frontend.backsender.clone();
+                    std::thread::spawn(move || {
+                        let Some(target) = backend::choose_configuration_target() else {
+                            println!("Saving the configuration was cancelled by the user");
+                            return;
+                        };
+                        match std::fs::write(&target, configuration) {
+                            Ok(()) => {
+                                comm_with_backend.send(BackendRequest::AcceptConfiguration(container, target.to_string_lossy().to_string())).ok();
+                            },
+                            Err(e) => {
+                                let violation = SchemaViolation::new("", format!("could not be written to {}: {}", target.to_string_lossy(), e));
+                                comm_with_backend.send(BackendRequest::InformConfigurationRejected(container, vec![violation])).ok();
+                            }
+                        }
+                    });
+                },
+                BackendRequest::InformConfigurationRejected(container, violations) => {
+                    comm_with_frontend.send(BackendResponse::ConfigurationRejected(container, violations)).ok();
+                },
                 BackendRequest::AcceptConfiguration(container, config_str) => {
                     println!("Received Config: {}", config_str);
 
@@ -892,11 +1174,11 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                             match backend::copy_config_and_file_references(&workdir, &config_file) {
                                 Ok(_) => {
                                     println!("Copy Job succeded!");
-                                    comm_with_frontend.send(BackendResponse::JobInfo(jid, JobState::Completed(ExitStatus::from_raw(0)))).ok();
+                                    comm_with_frontend.send(BackendResponse::JobInfo(jid, JobState::Succeeded)).ok();
                                 },
                                 Err(e) => {
                                     println!("Copy Job failed! Reason: {:?}", e);
-                                    comm_with_frontend.send(BackendResponse::JobInfo(jid, JobState::Completed(ExitStatus::from_raw(1)))).ok();
+                                    comm_with_frontend.send(BackendResponse::JobInfo(jid, JobState::Failed { exit_code: Some(1), signal: None })).ok();
                                 }
                             }
                         },
@@ -912,11 +1194,11 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                                                     .entry(job_id.clone())
                                                     .or_insert_with(|| Arc::new(Mutex::new(Vec::new())));
 
-                    match singularity_run(&containerpth, vec![config]) {
-                        Ok(mut child) => {
-                            let child_stdout = child.stdout.take().unwrap();
-                            backend::bufread_child_stdout_into_messages(output_collector, child_stdout).await;
-                            process_store.insert(job_id.clone(), Arc::new(Mutex::new(child)));
+                    let profile = persistent_state.run_profile_for(&containerpth, None);
+                    match backend::singularity_run(&container_engine, &profile, &job_id, &containerpth, vec![config]) {
+                        Ok(mut process) => {
+                            process.spawn_output_readers(output_collector);
+                            process_store.insert(job_id.clone(), Arc::new(Mutex::new(process)));
                         },
                         Err(_) => {}
                     }
@@ -929,26 +1211,55 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                         match process.lock() {
                             Ok(mut proc) => {
                                 match proc.try_wait() {
-                                    Ok(Some(status)) => {
-                                        comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Completed(status))).ok();
+                                    Ok(Some(state)) => {
+                                        let state = if cancelled_jobs.contains(&job_id) { JobState::Cancelled } else { state };
+                                        // a job that is being stopped gets its outcome once termination has answered
+                                        if !stopping_jobs.contains(&job_id) && job_history.finish(&job_id, state) { save_job_history(&job_history); }
+                                        comm_with_frontend.send(BackendResponse::JobInfo(job_id, state)).ok();
                                     },
                                     Ok(None) => {
                                         comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Running)).ok();
                                     }
                                     Err(_) => {
-                                        comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Unknown)).ok();
+                                        comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Orphaned)).ok();
                                     },
                                 }
                             },
                             Err(_) => {
-                                //Is this a definite failure or is this an actual JobState::Unknown?
-                                comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Unknown)).ok();
+                                // the process handle is lost, so the job can no longer be observed
+                                comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::Orphaned)).ok();
                             }
                         }
                     } else {
-                        comm_with_frontend.send(BackendResponse::JobInfo(job_id, JobState::JobNotListed)).ok();
+                        comm_with_frontend.send(BackendResponse::JobNotListed(job_id)).ok();
                     }
                 },
+                BackendRequest::InformJobOutputClosed(job_id) => {
+                    // only runs are recorded, not e.g. the local webserver
+                    if let Some(record) = job_history.get(&job_id).cloned() {
+                        // the process has usually exited by now, otherwise its outcome is recorded when it is polled next
+                        let exit_state = process_store.get(&job_id)
+                                            .and_then(|process| process.lock().ok().and_then(|mut process| process.try_wait().ok().flatten()));
+                        if let Some(state) = exit_state.filter(|_| !stopping_jobs.contains(&job_id)) {
+                            let state = if cancelled_jobs.contains(&job_id) { JobState::Cancelled } else { state };
+                            job_history.finish(&job_id, state);
+                        }
+
+                        let lines = process_outputs.get(&job_id)
+                                            .and_then(|output| output.lock().ok().map(|lines| lines.clone()))
+                                            .unwrap_or_default();
+                        let log_dir = Some(record.working_directory).filter(|dir| dir.is_dir()).or_else(|| exe_dir().clone());
+                        match log_dir.map(|dir| backend::write_job_log(&dir, &job_id, &lines)) {
+                            Some(Ok(log_path)) => { job_history.set_log_path(&job_id, log_path); },
+                            Some(Err(e)) => { println!("{}", &e); },
+                            None => { println!("No directory to write the log of job {:?} to", &job_id); }
+                        }
+                        save_job_history(&job_history);
+                    }
+                },
+                BackendRequest::ReadJobHistory => {
+                    comm_with_frontend.send(BackendResponse::JobHistory(job_history.search(""))).ok();
+                },
                 BackendRequest::SendJobOutput(job_id, offset) => {
                     // println!("All process outputs:");
                     //
@@ -968,58 +1279,93 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                             match output.lock() {
                                 Ok(lock) => {
                                     //println!("Backend sending slice from output of length: {}", lock.len());
-                                    let lines = lock.iter().skip(offset).map(|s| s.clone()).collect();
-                                    comm_with_frontend.send(BackendResponse::JobOutput(job_id, lines)).ok();
+                                    let lines = lock.iter().skip(offset).cloned().collect();
+                                    comm_with_frontend.send(BackendResponse::JobOutput(job_id, offset, lines)).ok();
                                 },
                                 Err(_) => {}
                             }
                         },
                         None => {
-                            comm_with_frontend.send(BackendResponse::JobOutput(job_id, vec!["No Output".to_string()])).ok();
+                            comm_with_frontend.send(BackendResponse::JobOutput(job_id, 0, vec![JobOutputLine::new(OutputStream::Stdout, "No Output")])).ok();
                         }
                     }
                 },
+                BackendRequest::SendJobInput(job_id, input) => {
+                    let sent = match process_store.get(&job_id) {
+                        Some(process) => match process.lock() {
+                            Ok(mut process) => process.send_input(&input, container_engine.runner.as_ref(), &job_id).map_err(|e| e.to_string()),
+                            Err(_) => Err("the job can no longer be reached".to_string()),
+                        },
+                        None => Err("the job is not running".to_string()),
+                    };
+                    let echo = match sent {
+                        Ok(()) => input.echo(),
+                        Err(e) => {
+                            println!("Could not send input to job {:?}: {}", &job_id, &e);
+                            Some(format!("{} (not sent: {})", input.echo().unwrap_or_default(), e))
+                        }
+                    };
+                    if let (Some(output), Some(echo)) = (process_outputs.get(&job_id), echo) {
+                        JobProcess::echo_input(output, &echo);
+                    }
+                },
                 BackendRequest::StopProcess(jid) => {
                     println!("Backend stops process with JobId {:?}", &jid);
-                    process_store.store.entry(jid.clone())
-                                        .and_modify(|arc| { arc.lock().unwrap().kill().ok(); });
-
-                    //TODO: store address of server properly (per job_id) or create yet another BackendRequest
-                    let _resp = reqwest::get("http://127.0.0.1:9283/terminate")
-                                .await;
+                    // a job that has already exited keeps its outcome, leftover processes are terminated all the same
+                    let process = process_store.get(&jid).cloned();
+                    let exited = process.as_ref().is_some_and(|process| process.lock().map(|mut process| matches!(process.try_wait(), Ok(Some(_)))).unwrap_or(false));
+                    if !exited { stopping_jobs.insert(jid); }
 
-                    //process_store.store.entry(jid)
-//                                       .and_modify(|arc| {
-//                            match arc.lock() {
-//                                Ok(mtx) => {
-//                                    if mtx.stdin.is_some() {
-//                                        mtx.stdin.unwrap().write()
-//                                    } else {
-//
-//                                    }
-//                                },
-//                                Err(_) => {
-//
-//                                }
-//                            }
-//                    });
-
-                    comm_with_frontend.send(BackendResponse::StoppedProcess(jid)).ok();
+                    spawn_job_termination(Arc::clone(&container_engine.runner), vec![(jid, process)], false, comm_with_frontend.backsender.clone());
                 },
                 BackendRequest::StopAllProcesses => {
-
-                    process_store.store.values_mut().for_each(|arc| {
-                        if let Ok(mut child) = arc.lock() { child.kill().ok(); }
-                    });
-
-                    //TODO: store address of server properly (per job_id) or create yet another BackendRequest
-                    let _resp = reqwest::get("http://127.0.0.1:9283/terminate")
-                                .await;
-
+                    println!("Backend stops all processes");
+                    // finished jobs keep their outcome
+                    let jobs = process_store.store.iter()
+                                    .filter(|(_, process)| process.lock().map(|mut process| matches!(process.try_wait(), Ok(None))).unwrap_or(false))
+                                    .map(|(jid, process)| (*jid, Some(Arc::clone(process))))
+                                    .collect_vec();
+                    stopping_jobs.extend(jobs.iter().map(|(jid, _)| *jid));
+
+                    spawn_job_termination(Arc::clone(&container_engine.runner), jobs, true, comm_with_frontend.backsender.clone());
+                },
+                BackendRequest::InformJobTerminated(jid, result) => {
+                    let was_running = stopping_jobs.remove(&jid);
+                    match result {
+                        Ok(()) => {
+                            if was_running {
+                                cancelled_jobs.insert(jid);
+                                if job_history.finish(&jid, JobState::Cancelled) { save_job_history(&job_history); }
+                            }
+                            comm_with_frontend.send(BackendResponse::StoppedProcess(jid)).ok();
+                        },
+                        Err(e) => {
+                            // still running, its outcome is recorded once it exits
+                            println!("Could not stop job {:?}: {}", &jid, &e);
+                            comm_with_frontend.send(BackendResponse::JobStopFailed(jid, e)).ok();
+                        }
+                    }
+                },
+                BackendRequest::InformAllJobsTerminated(results) => {
+                    for (jid, result) in results {
+                        stopping_jobs.remove(&jid);
+                        match result {
+                            Ok(()) => {
+                                cancelled_jobs.insert(jid);
+                                job_history.finish(&jid, JobState::Cancelled);
+                            },
+                            Err(e) => {
+                                println!("Could not stop job {:?}: {}", &jid, &e);
+                                comm_with_frontend.send(BackendResponse::JobStopFailed(jid, e)).ok();
+                            }
+                        }
+                    }
+                    save_job_history(&job_history);
                     comm_with_frontend.send(BackendResponse::StoppedAllProcesses).ok();
                 },
                 BackendRequest::StopProgram => {
-                    match backend::shut_down_colonywsl_child() {
+                    #[cfg(windows)]
+                    match backend::shut_down_colonywsl_child(&host_runner) {
                         Ok(mut child) => {
                             let begin = tokio::time::Instant::now();
                             loop {
@@ -1055,7 +1401,7 @@ pub async fn listen(comm_with_frontend: BackendCommChannel) {
                             list_dir_local_fs(&fs, path )
                         },
                         FilesystemData::LocalWSL => {
-                            list_dir_local_wsl_fs(&fs, path)
+                            list_dir_local_wsl_fs(container_engine.runner.as_ref(), &fs, path)
                         },
                         //TODO: actually communicate with helix (over async-ssh2-challenge)
                         FilesystemData::HelixSSH(_) => {
@@ -1087,7 +1433,7 @@ fn list_dir_local_fs(_fs: &FilesystemData, path: PathBuf) -> Result<DirectoryCon
                 if let Ok(ftype) = entry.file_type() {
                     if ftype.is_dir() { directories.push(entry.file_name().to_string_lossy().to_string()) }
                     else if ftype.is_symlink() {
-                        if ftype.is_symlink_dir() { directories.push(entry.file_name().to_string_lossy().to_string()) }
+                        if symlink_points_to_dir(&entry, &ftype) { directories.push(entry.file_name().to_string_lossy().to_string()) }
                         else { files.push(entry.file_name().to_string_lossy().to_string()) }
                     }
                     else { files.push(entry.file_name().to_string_lossy().to_string()) }
@@ -1101,12 +1447,23 @@ fn list_dir_local_fs(_fs: &FilesystemData, path: PathBuf) -> Result<DirectoryCon
     }
 }
 
-fn list_dir_local_wsl_fs(_fs: &FilesystemData, path: PathBuf) -> Result<DirectoryContents, Result<DirectoryContents, ()>> {
-    //TODO: directory names may contain malicious code
+// windows records in the link itself whether it points to a directory
+#[cfg(windows)]
+fn symlink_points_to_dir(_entry: &std::fs::DirEntry, ftype: &std::fs::FileType) -> bool {
+    use std::os::windows::fs::FileTypeExt;
+    return ftype.is_symlink_dir();
+}
+
+// elsewhere the link has to be followed, broken links count as files
+#[cfg(not(windows))]
+fn symlink_points_to_dir(entry: &std::fs::DirEntry, _ftype: &std::fs::FileType) -> bool {
+    return std::fs::metadata(entry.path()).map(|meta| meta.is_dir()).unwrap_or(false);
+}
+
+fn list_dir_local_wsl_fs(runner: &dyn CommandRunner, _fs: &FilesystemData, path: PathBuf) -> Result<DirectoryContents, Result<DirectoryContents, ()>> {
 
-    let pathnames = backend::run_wsl_command(&format!("ls -1Q {}", linux_path_display(&path)))
+    let pathnames = runner.output(&CommandSpec::new("ls").args(["-1Q", &linux_path_display(&path)]))
         .map_err(|_| ())
-        .and_then(|child| { child.wait_with_output().map_err(|_| ()) })
         .and_then(|out| String::from_utf8(out.stdout).map_err(|_| ()))
         .map(|string| {
             println!("Received Command output: {}", &string);
@@ -1117,9 +1474,8 @@ fn list_dir_local_wsl_fs(_fs: &FilesystemData, path: PathBuf) -> Result<Director
                     .unwrap_or_else(String::new)
             }).collect_vec()
         });
-    let pathmeta = backend::run_wsl_command(&format!("ls -1lQocF {}", linux_path_display(&path)))
+    let pathmeta = runner.output(&CommandSpec::new("ls").args(["-1lQocF", &linux_path_display(&path)]))
         .map_err(|_| ())
-        .and_then(|child| { child.wait_with_output().map_err(|_| ()) })
         .and_then(|out| String::from_utf8(out.stdout).map_err(|_| ()))
         .map(|string| {
             println!("Received Command output: {}", &string);
@@ -0,0 +1,194 @@
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use plugin_interface_elements::elements_v1::{self, ChannelId, ChatAuthor, ChatMessage, JobId, MessageId, RemoteOperationError};
+
+use crate::backend::json_file::{JsonFile, launcher_file_path};
+
+
+
+//################################################################################
+//## Chat channels
+//## (text channels between plugins / running containers and the user,
+//## every change is written to disk so the history survives restarts)
+//################################################################################
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ChatChannel {
+    pub id: ChannelId,
+    pub title: String,
+    pub job: Option<JobId>,
+    pub open: bool,
+    pub history: Vec<ChatMessage>,
+}
+
+impl ChatChannel {
+    // the plugin is waiting for the user if it wrote last
+    pub fn awaits_answer(&self) -> bool {
+        return self.open && self.history.last().is_some_and(|msg| msg.author == ChatAuthor::Plugin);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ChatStore {
+    pub channels: Vec<ChatChannel>,     // a Vec since JSON maps only allow string keys
+    #[serde(skip)]
+    file: JsonFile,                     // where every change is written
+}
+
+pub type SharedChatStore = Arc<Mutex<ChatStore>>;
+
+pub fn chat_history_path() -> Option<PathBuf> {
+    return launcher_file_path("colony_chat_channels.json");
+}
+
+fn unknown_channel(channel_id: &ChannelId) -> RemoteOperationError {
+    return RemoteOperationError::IncorrectParameters(format!("Unknown chat channel: {:?}", channel_id));
+}
+
+impl ChatStore {
+    pub fn from_file(path: &Option<PathBuf>) -> Self {
+        let mut file = JsonFile::new(path.clone(), "chat history");
+        let store: ChatStore = file.read();
+        return ChatStore { file, ..store };
+    }
+
+    // the channels stay usable if the history cannot be written
+    fn write_to_file(&self) {
+        if let Err(e) = self.file.write(self) {
+            println!("{}", &e);
+        }
+    }
+
+    pub fn channel(&self, channel_id: &ChannelId) -> Option<&ChatChannel> {
+        return self.channels.iter().find(|channel| &channel.id == channel_id);
+    }
+
+    fn channel_mut(&mut self, channel_id: &ChannelId) -> Result<&mut ChatChannel, RemoteOperationError> {
+        return self.channels.iter_mut().find(|channel| &channel.id == channel_id).ok_or_else(|| unknown_channel(channel_id));
+    }
+
+    pub fn open_channel(&mut self, title: String, job: Option<JobId>) -> ChannelId {
+        let id = ChannelId { inner: Uuid::new_v4() };
+        self.channels.push(ChatChannel { id, title, job, open: true, history: Vec::new() });
+        self.write_to_file();
+        return id;
+    }
+
+    pub fn close_channel(&mut self, channel_id: &ChannelId) -> Result<ChannelId, RemoteOperationError> {
+        self.channel_mut(channel_id)?.open = false;
+        self.write_to_file();
+        return Ok(*channel_id);
+    }
+
+    pub fn append_message(&mut self, channel_id: &ChannelId, author: ChatAuthor, text: String) -> Result<MessageId, RemoteOperationError> {
+        let channel = self.channel_mut(channel_id)?;
+        if !channel.open {
+            return Err(RemoteOperationError::IncorrectParameters(format!("Chat channel {:?} has been closed", channel_id)));
+        }
+
+        let id = MessageId { inner: Uuid::new_v4() };
+        channel.history.push(ChatMessage { id, author, creation_time: Utc::now(), text });
+        self.write_to_file();
+        return Ok(id);
+    }
+
+    pub fn messages_since(&self, channel_id: &ChannelId, since: usize) -> Result<(Vec<ChatMessage>, bool), RemoteOperationError> {
+        let channel = self.channel(channel_id).ok_or_else(|| unknown_channel(channel_id))?;
+        let messages = channel.history.iter().skip(since).cloned().collect();
+        return Ok((messages, channel.open));
+    }
+}
+
+
+
+//################################################################################
+//## Protocol handling
+//################################################################################
+
+// returns the response, and the channel whose content changed
+pub fn handle_chat_request(store: &SharedChatStore, request: &elements_v1::FrontendTaskRequest) -> Option<(elements_v1::FrontendTaskResponse, Option<ChannelId>)> {
+    let poisoned = || RemoteOperationError::InternalFailure("Chat store is unavailable".to_string());
+    let mut store_guard = store.lock();
+
+    return match request {
+        elements_v1::FrontendTaskRequest::OpenChatChannel(open_data) => {
+            let channel_id = store_guard.as_mut()
+                                .map(|store| store.open_channel(open_data.title.clone(), open_data.job))
+                                .map_err(|_| poisoned());
+            let changed = channel_id.clone().ok();
+            let response_data = elements_v1::OpenChatChannelResponse { channel_id };
+            Some((elements_v1::FrontendTaskResponse::OpenChatChannel(response_data), changed))
+        },
+        elements_v1::FrontendTaskRequest::CloseChatChannel(close_data) => {
+            let channel_id = store_guard.as_mut()
+                                .map_err(|_| poisoned())
+                                .and_then(|store| store.close_channel(&close_data.channel_id));
+            let changed = channel_id.clone().ok();
+            let response_data = elements_v1::CloseChatChannelResponse { channel_id };
+            Some((elements_v1::FrontendTaskResponse::CloseChatChannel(response_data), changed))
+        },
+        elements_v1::FrontendTaskRequest::SendChatMessage(send_data) => {
+            let message_id = store_guard.as_mut()
+                                .map_err(|_| poisoned())
+                                .and_then(|store| store.append_message(&send_data.channel_id, ChatAuthor::Plugin, send_data.text.clone()));
+            let changed = message_id.as_ref().ok().map(|_| send_data.channel_id);
+            let response_data = elements_v1::SendChatMessageResponse { message_id };
+            Some((elements_v1::FrontendTaskResponse::SendChatMessage(response_data), changed))
+        },
+        elements_v1::FrontendTaskRequest::ReceiveChatMessages(receive_data) => {
+            let result = store_guard.as_ref()
+                                .map_err(|_| poisoned())
+                                .and_then(|store| store.messages_since(&receive_data.channel_id, receive_data.since));
+            let response_data = match result {
+                Ok((messages, channel_open)) => elements_v1::ReceiveChatMessagesResponse { messages: Ok(messages), channel_open },
+                Err(e) => elements_v1::ReceiveChatMessagesResponse { messages: Err(e), channel_open: false },
+            };
+            Some((elements_v1::FrontendTaskResponse::ReceiveChatMessages(response_data), None))
+        },
+        _ => None
+    };
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channels_keep_their_history_until_closed() {
+        let path = std::env::temp_dir().join(format!("colony_chat_channels_test_{}.json", Uuid::new_v4()));
+        let mut store = ChatStore::from_file(&Some(path.clone()));
+
+        let channel_id = store.open_channel("Sample sheet".to_string(), None);
+        store.append_message(&channel_id, ChatAuthor::Plugin, "Which column holds the sample names?".to_string()).unwrap();
+        assert!(store.channel(&channel_id).unwrap().awaits_answer());
+        store.append_message(&channel_id, ChatAuthor::User, "The second".to_string()).unwrap();
+        assert!(!store.channel(&channel_id).unwrap().awaits_answer());
+
+        let (messages, open) = store.messages_since(&channel_id, 1).unwrap();
+        assert_eq!(messages.iter().map(|msg| msg.text.as_str()).collect::<Vec<_>>(), vec!["The second"]);
+        assert!(open);
+
+        assert_eq!(store.close_channel(&channel_id), Ok(channel_id));
+        assert!(store.append_message(&channel_id, ChatAuthor::Plugin, "Thanks".to_string()).is_err());
+        let unknown = ChannelId { inner: Uuid::new_v4() };
+        assert!(store.messages_since(&unknown, 0).is_err());
+
+        let restored = ChatStore::from_file(&Some(path.clone()));
+        std::fs::remove_file(&path).ok();
+        let (messages, open) = restored.messages_since(&channel_id, 0).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(!open);
+    }
+}
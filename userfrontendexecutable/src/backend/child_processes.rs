@@ -3,11 +3,14 @@
 
 use std::process::{Command, Child, ChildStdout};
 use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 use std::io::{Read, BufReader, BufRead}; // chunk.bytes()
 
 use duct::Handle as ProcessHandle;
 use itertools::Itertools; // Iterator.collect_vec();
 
+use crate::backend::jobs::{JobOutputLine, OutputStream, SharedJobOutput};
+
 
 
 
@@ -235,12 +238,277 @@ pub fn bufread_stdout_bytes_into_messages_v2(
 
 
 
+//################################################################################
+//## Job output streams
+//## (stdout and stderr are read by one thread each, both write into the same
+//## output list; a carriage return starts the current line over)
+//################################################################################
+
+pub fn read_job_output_stream<R: Read>(output: &SharedJobOutput, stream: OutputStream, reader: R) {
+    let mut reader = BufReader::new(reader);
+    let mut buf = [0 as u8; 4096];
+    let mut pending = Vec::new() as Vec<u8>;       // bytes of a character that was split between reads
+    let mut open_line = None as Option<usize>;     // the line this stream is currently writing
+    let mut text = String::new();
+    let mut carriage_return = false;
+
+    while let Ok(n) = reader.read(&mut buf[..]) {
+        if n == 0 { break; } // stream has closed
+
+        pending.extend_from_slice(&buf[..n]);
+        let decoded = take_valid_utf8(&mut pending);
+
+        let Ok(mut lines) = output.lock() else { return; }; // lock is poisened and will never return again
+        for ch in decoded.chars() {
+            match ch {
+                '\n' => {
+                    set_job_output_line(&mut lines, &mut open_line, stream, &text, true);
+                    text.clear();
+                    carriage_return = false;
+                },
+                '\r' => { carriage_return = true; },
+                '\u{8}' => { text.pop(); }, // backspace
+                _ => {
+                    if carriage_return { text.clear(); carriage_return = false; }
+                    text.push(ch);
+                }
+            }
+        }
+        if !text.is_empty() {
+            set_job_output_line(&mut lines, &mut open_line, stream, &text, false);
+        }
+    }
+
+    if let Ok(mut lines) = output.lock() {
+        text.push_str(&String::from_utf8_lossy(&pending));
+        if open_line.is_some() || !text.is_empty() {
+            set_job_output_line(&mut lines, &mut open_line, stream, &text, true);
+        }
+    }
+}
+
+fn set_job_output_line(lines: &mut Vec<JobOutputLine>, open_line: &mut Option<usize>, stream: OutputStream, text: &str, complete: bool) {
+    let index = match open_line.take() {
+        Some(index) => index,
+        None => {
+            lines.push(JobOutputLine::new(stream, ""));
+            lines.len() - 1
+        }
+    };
+    lines[index].text = text.to_string();
+    lines[index].complete = complete;
+    if !complete { *open_line = Some(index); }
+}
+
+// the readers run until the child closes its pipes, join them to know the output is complete
+pub fn spawn_job_output_readers(output: &SharedJobOutput, child: &mut Child) -> Vec<JoinHandle<()>> {
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let output = Arc::clone(output);
+        readers.push(std::thread::spawn(move || read_job_output_stream(&output, OutputStream::Stdout, stdout)));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let output = Arc::clone(output);
+        readers.push(std::thread::spawn(move || read_job_output_stream(&output, OutputStream::Stderr, stderr)));
+    }
+    return readers;
+}
+
+// keeps an incomplete character at the end for the next read
+fn take_valid_utf8(pending: &mut Vec<u8>) -> String {
+    let mut decoded = String::new();
+    let mut rest = Vec::new();
+    let mut chunks = pending.utf8_chunks().peekable();
+    while let Some(chunk) = chunks.next() {
+        decoded.push_str(chunk.valid());
+        if !chunk.invalid().is_empty() {
+            if chunks.peek().is_none() && chunk.invalid().len() < 4 {
+                rest = chunk.invalid().to_vec();
+            } else {
+                decoded.push_str("\u{FFFD}");
+            }
+        }
+    }
+    *pending = rest;
+    return decoded;
+}
+
+
+
+//################################################################################
+//## Pseudo-terminal output
+//## (the output of a terminal job is drawn on a vt100 screen; rows that scroll
+//## off the screen become finished lines, the screen itself stays open; input
+//## echoed into the output keeps its place between the rows)
+//################################################################################
+
+// rows kept by the parser until they are taken over into the job output
+const TERMINAL_SCROLLBACK: usize = 1000;
+
+pub fn read_terminal_output<R: Read>(output: &SharedJobOutput, reader: R, rows: u16, cols: u16) {
+    let mut reader = BufReader::new(reader);
+    let mut buf = [0 as u8; 4096];
+    let mut parser = vt100::Parser::new(rows, cols, TERMINAL_SCROLLBACK);
+    let mut first_screen_line = 0 as usize;     // the job output line shown in the top row of the screen
+    // a piece of input moves less than a screen's worth of rows into the scrollback, where they can still be read
+    let piece_rows = (rows as usize / 3).max(1);
+
+    while let Ok(n) = reader.read(&mut buf[..]) {
+        if n == 0 { break; } // terminal has closed
+
+        let Ok(mut lines) = output.lock() else { return; }; // lock is poisened and will never return again
+        for piece in split_terminal_input(&buf[..n], piece_rows, piece_rows * cols as usize) {
+            process_terminal_piece(&mut parser, &mut lines, &mut first_screen_line, piece);
+        }
+        set_terminal_screen(&parser, &mut lines, first_screen_line, false);
+    }
+
+    if let Ok(mut lines) = output.lock() {
+        set_terminal_screen(&parser, &mut lines, first_screen_line, true);
+    }
+}
+
+fn split_terminal_input(bytes: &[u8], max_newlines: usize, max_length: usize) -> Vec<&[u8]> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut newlines = 0;
+    for (index, byte) in bytes.iter().enumerate() {
+        if *byte == b'\n' { newlines += 1; }
+        if newlines == max_newlines || index + 1 - start == max_length {
+            pieces.push(&bytes[start..=index]);
+            start = index + 1;
+            newlines = 0;
+        }
+    }
+    if start < bytes.len() { pieces.push(&bytes[start..]); }
+    return pieces;
+}
+
+fn process_terminal_piece(parser: &mut vt100::Parser, lines: &mut Vec<JobOutputLine>, first_screen_line: &mut usize, piece: &[u8]) {
+    let (rows, cols) = parser.screen().size();
+
+    let mut scrolled_before = scrollback_length(parser);
+    if scrolled_before + rows as usize > TERMINAL_SCROLLBACK {
+        // start over before the scrollback is full, rows leaving the screen could not be counted anymore
+        let state = parser.screen().state_formatted();
+        *parser = vt100::Parser::new(rows, cols, TERMINAL_SCROLLBACK);
+        parser.process(&state);
+        scrolled_before = scrollback_length(parser);
+    }
+
+    parser.process(piece);
+    let left_screen = scrollback_length(parser) - scrolled_before;
+    if left_screen == 0 { return; }
+
+    // with an offset, the newest rows of the scrollback are shown on top of the screen
+    parser.set_scrollback(left_screen);
+    let texts = parser.screen().rows(0, cols).take(left_screen).collect::<Vec<_>>();
+    parser.set_scrollback(0);
+
+    for text in texts.iter() {
+        let index = terminal_row_line(lines, *first_screen_line, 0);
+        set_terminal_line(lines, index, text.trim_end(), true);
+        *first_screen_line = index + 1;
+    }
+}
+
+fn scrollback_length(parser: &mut vt100::Parser) -> usize {
+    parser.set_scrollback(usize::MAX);
+    let length = parser.screen().scrollback();
+    parser.set_scrollback(0);
+    return length;
+}
+
+// blank rows below the last output are left out
+fn set_terminal_screen(parser: &vt100::Parser, lines: &mut Vec<JobOutputLine>, first_screen_line: usize, complete: bool) {
+    let (_rows, cols) = parser.screen().size();
+    let mut texts = parser.screen().rows(0, cols).map(|row| row.trim_end().to_string()).collect::<Vec<_>>();
+    let used_rows = texts.iter().rposition(|text| !text.is_empty()).map(|index| index + 1).unwrap_or(0);
+    texts.truncate(used_rows);
+
+    for (row, text) in texts.iter().enumerate() {
+        let index = terminal_row_line(lines, first_screen_line, row);
+        set_terminal_line(lines, index, text, complete);
+    }
+
+    let mut index = terminal_row_line(lines, first_screen_line, used_rows);
+    while index < lines.len() {
+        if lines[index].stream == OutputStream::Terminal { lines.remove(index); } else { index += 1; }
+    }
+}
+
+// the line showing a row of the screen, other lines in between are skipped; past the end if the row is not shown yet
+fn terminal_row_line(lines: &[JobOutputLine], first_screen_line: usize, row: usize) -> usize {
+    return lines.iter().enumerate().skip(first_screen_line)
+                .filter(|(_index, line)| line.stream == OutputStream::Terminal)
+                .nth(row)
+                .map(|(index, _line)| index)
+                .unwrap_or(lines.len());
+}
+
+// keeps the time a line was started at, as long as its text does not change
+fn set_terminal_line(lines: &mut Vec<JobOutputLine>, index: usize, text: &str, complete: bool) {
+    match lines.get_mut(index) {
+        Some(line) if line.text == text => { line.complete = complete; },
+        Some(line) => {
+            *line = JobOutputLine::new(OutputStream::Terminal, text);
+            line.complete = complete;
+        },
+        None => {
+            let mut line = JobOutputLine::new(OutputStream::Terminal, text);
+            line.complete = complete;
+            lines.push(line);
+        }
+    }
+}
+
+
+
 //################################################################################
 //## Duct Library Processes
 //################################################################################
 
 
 
+//################################################################################
+//## Tests
+//################################################################################
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streams_are_interleaved_and_progress_lines_overwritten() {
+        let output: SharedJobOutput = Arc::new(Mutex::new(Vec::new()));
+
+        let stdout = (&b"start\nprogress 10%\rprogress 100%\r\ncaf\xc3"[..]).chain(&b"\xa9\nno newline"[..]);
+        read_job_output_stream(&output, OutputStream::Stdout, stdout);
+        read_job_output_stream(&output, OutputStream::Stderr, &b"warning\r\n"[..]);
+
+        let lines = output.lock().unwrap().clone();
+        let texts = lines.iter().map(|line| (line.stream, line.text.as_str())).collect_vec();
+        assert_eq!(texts, vec![
+            (OutputStream::Stdout, "start"),
+            (OutputStream::Stdout, "progress 100%"),
+            (OutputStream::Stdout, "café"),
+            (OutputStream::Stdout, "no newline"),
+            (OutputStream::Stderr, "warning"),
+        ]);
+        assert!(lines.iter().all(|line| line.complete));
+        assert!(lines[4].log_line().ends_with(" [stderr] warning"));
+    }
+
+    #[test]
+    fn terminal_rows_scroll_into_finished_lines() {
+        let output: SharedJobOutput = Arc::new(Mutex::new(Vec::new()));
 
+        let terminal = (&b"line 1\r\nline 2\r\nline 3\r\n"[..]).chain(&b"line 4\r\nline 5\r\n\x1b[1mprogress 50%\x1b[0m\rprogress 100%"[..]);
+        read_terminal_output(&output, terminal, 3, 20);
 
+        let lines = output.lock().unwrap().clone();
+        let texts = lines.iter().map(|line| line.text.as_str()).collect_vec();
+        assert_eq!(texts, vec!["line 1", "line 2", "line 3", "line 4", "line 5", "progress 100%"]);
+        assert!(lines.iter().all(|line| line.stream == OutputStream::Terminal && line.complete));
+    }
+}
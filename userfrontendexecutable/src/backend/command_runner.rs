@@ -0,0 +1,299 @@
+
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+
+use portable_pty::PtySize;
+
+use crate::backend::backend_misc::{hide_console_window, wslify_windows_path};
+use crate::backend::job_process::TerminalProcess;
+
+
+
+//################################################################################
+//## Command execution
+//## (backend operations describe what to run as a CommandSpec with a proper
+//## argument vector, a CommandRunner decides where and how it is executed)
+//################################################################################
+
+#[derive(Clone, PartialEq, Default)]
+pub struct CommandSpec {
+    pub program: String,
+    pub args: Vec<String>,
+    pub working_directory: Option<PathBuf>,     // as seen by the launcher, the runner translates it
+    pub env: Vec<(String, String)>,
+}
+
+impl CommandSpec {
+    pub fn new(program: &str) -> Self {
+        return CommandSpec { program: program.to_string(), ..Default::default() };
+    }
+
+    pub fn arg<S: AsRef<str>>(mut self, arg: S) -> Self {
+        self.args.push(arg.as_ref().to_string());
+        return self;
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where I: IntoIterator<Item = S>, S: AsRef<str> {
+        self.args.extend(args.into_iter().map(|arg| arg.as_ref().to_string()));
+        return self;
+    }
+
+    pub fn current_dir(mut self, directory: &Path) -> Self {
+        self.working_directory = Some(directory.to_path_buf());
+        return self;
+    }
+
+    pub fn env(mut self, key: &str, value: &str) -> Self {
+        self.env.push((key.to_string(), value.to_string()));
+        return self;
+    }
+}
+
+// commands are logged; values of the environment can be secrets like the session token, so only the keys are shown
+impl Debug for CommandSpec {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        return f.debug_struct("CommandSpec")
+                .field("program", &self.program)
+                .field("args", &self.args)
+                .field("working_directory", &self.working_directory)
+                .field("env", &self.env.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>())
+                .finish();
+    }
+}
+
+pub trait CommandRunner: Send + Sync + Debug {
+    // the process as it would be started, stdio is configured by output/spawn
+    fn command(&self, spec: &CommandSpec) -> Command;
+
+    // how the executed programs see a path of the launcher's file system
+    fn host_path(&self, path: &Path) -> String;
+
+    fn output(&self, spec: &CommandSpec) -> std::io::Result<Output> {
+        println!("Running command: {:?}", spec);
+        return self.command(spec)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .output();
+    }
+
+    fn spawn(&self, spec: &CommandSpec) -> std::io::Result<Child> {
+        println!("Spawning command: {:?}", spec);
+        return self.command(spec)
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn();
+    }
+
+    // the same process, attached to a pseudo-terminal instead of pipes
+    fn spawn_in_terminal(&self, spec: &CommandSpec, size: PtySize) -> std::io::Result<TerminalProcess> {
+        println!("Spawning command in a pseudo-terminal: {:?}", spec);
+        return TerminalProcess::spawn(&self.command(spec), size);
+    }
+}
+
+// the runner used for everything that happens inside the container host,
+// which is ColonyWSL on windows and the machine itself on linux
+// shared, so work that is moved off the backend loop goes through the same runner
+#[cfg(windows)]
+pub fn default_container_runner() -> Arc<dyn CommandRunner> {
+    return Arc::new(WslCommandRunner::colony());
+}
+
+#[cfg(not(windows))]
+pub fn default_container_runner() -> Arc<dyn CommandRunner> {
+    return Arc::new(NativeCommandRunner);
+}
+
+
+
+//################################################################################
+//## Runners
+//################################################################################
+
+// executes programs inside a WSL distribution, without a shell in between
+#[derive(Clone, PartialEq, Debug)]
+pub struct WslCommandRunner {
+    pub distribution: String,
+}
+
+impl WslCommandRunner {
+    pub fn colony() -> Self {
+        return WslCommandRunner { distribution: "ColonyWSL".to_string() };
+    }
+}
+
+impl CommandRunner for WslCommandRunner {
+    fn command(&self, spec: &CommandSpec) -> Command {
+        let mut command = Command::new("wsl");
+        hide_console_window(&mut command)
+               .env("WSL_UTF8", "1")
+               .args(["-d", &self.distribution]);
+        if let Some(directory) = &spec.working_directory {
+            command.args(["--cd", &self.host_path(directory)]);
+        }
+        command.arg("-e").arg(&spec.program).args(&spec.args);
+
+        // environment variables only cross into WSL when listed in WSLENV
+        if !spec.env.is_empty() {
+            let mut shared_names = std::env::var("WSLENV").ok().into_iter().filter(|names| !names.is_empty()).collect::<Vec<_>>();
+            shared_names.extend(spec.env.iter().map(|(key, _)| key.clone()));
+            command.envs(spec.env.iter().map(|(key, value)| (key, value)))
+                   .env("WSLENV", shared_names.join(":"));
+        }
+        return command;
+    }
+
+    fn host_path(&self, path: &Path) -> String {
+        return wslify_windows_path(&path.to_string_lossy());
+    }
+}
+
+// executes programs directly on the machine the launcher runs on
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct NativeCommandRunner;
+
+impl CommandRunner for NativeCommandRunner {
+    fn command(&self, spec: &CommandSpec) -> Command {
+        let mut command = Command::new(&spec.program);
+        hide_console_window(&mut command)
+               .args(&spec.args)
+               .envs(spec.env.iter().map(|(key, value)| (key, value)));
+        if let Some(directory) = &spec.working_directory {
+            command.current_dir(directory);
+        }
+        return command;
+    }
+
+    fn host_path(&self, path: &Path) -> String {
+        return path.to_string_lossy().into_owned();
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ScriptedResponse {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+impl ScriptedResponse {
+    pub fn success(stdout: &str) -> Self {
+        return ScriptedResponse { stdout: stdout.to_string(), ..Default::default() };
+    }
+
+    pub fn failure(stderr: &str, exit_code: i32) -> Self {
+        return ScriptedResponse { stderr: stderr.to_string(), exit_code, ..Default::default() };
+    }
+}
+
+// stands in for the container host in tests: records every invocation and answers with canned output
+#[allow(unused)]
+#[derive(Debug, Default)]
+pub struct ScriptedCommandRunner {
+    responses: Vec<(Vec<String>, ScriptedResponse)>,
+    invocations: Mutex<Vec<CommandSpec>>,
+}
+
+#[allow(unused)]
+impl ScriptedCommandRunner {
+    pub fn new() -> Self {
+        return ScriptedCommandRunner::default();
+    }
+
+    // answers every command whose program and arguments start with the given words, first match wins
+    pub fn respond_to(mut self, command_prefix: &[&str], response: ScriptedResponse) -> Self {
+        self.responses.push((command_prefix.iter().map(|word| word.to_string()).collect(), response));
+        return self;
+    }
+
+    pub fn invocations(&self) -> Vec<CommandSpec> {
+        return self.invocations.lock().map(|invocations| invocations.clone()).unwrap_or_default();
+    }
+
+    fn response_for(&self, spec: &CommandSpec) -> ScriptedResponse {
+        let words = std::iter::once(&spec.program).chain(spec.args.iter()).collect::<Vec<_>>();
+        return self.responses.iter()
+                    .find(|(prefix, _)| prefix.len() <= words.len() && prefix.iter().zip(words.iter()).all(|(expected, word)| &expected == word))
+                    .map(|(_, response)| response.clone())
+                    .unwrap_or_else(|| ScriptedResponse::failure(&format!("{}: command not found", &spec.program), 127));
+    }
+}
+
+impl CommandRunner for ScriptedCommandRunner {
+    fn command(&self, spec: &CommandSpec) -> Command {
+        if let Ok(mut invocations) = self.invocations.lock() {
+            invocations.push(spec.clone());
+        }
+        let response = self.response_for(spec);
+
+        let mut command = Command::new("sh");
+        command.args(["-c", r#"printf '%s' "$SCRIPTED_STDOUT"; printf '%s' "$SCRIPTED_STDERR" >&2; exit "$SCRIPTED_EXIT_CODE""#])
+               .env("SCRIPTED_STDOUT", &response.stdout)
+               .env("SCRIPTED_STDERR", &response.stderr)
+               .env("SCRIPTED_EXIT_CODE", response.exit_code.to_string());
+        return command;
+    }
+
+    fn host_path(&self, path: &Path) -> String {
+        return path.to_string_lossy().into_owned();
+    }
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::container_runtime::{ContainerEngine, ContainerRuntime, RuntimePreference};
+    use crate::backend::{singularity_app_list, singularity_help};
+
+    #[test]
+    fn container_queries_pass_paths_as_single_arguments() {
+        let runner = Arc::new(ScriptedCommandRunner::new()
+                        .respond_to(&["apptainer", "inspect", "--list-apps"], ScriptedResponse::success("app-requirements\napp-configurations\n")));
+        let engine = ContainerEngine::assume(runner.clone(), &RuntimePreference::Fixed(ContainerRuntime::Apptainer));
+        let container = PathBuf::from("/data/containers/my container.sif");
+
+        let apps = singularity_app_list(&engine, &container);
+
+        assert_eq!(apps, Some(vec!["app-requirements".to_string(), "app-configurations".to_string()]));
+        let invocations = runner.invocations();
+        assert_eq!(invocations.len(), 1);
+        assert_eq!(invocations[0].program, "apptainer");
+        assert_eq!(invocations[0].args, vec!["inspect", "--list-apps", "/data/containers/my container.sif"]);
+    }
+
+    #[test]
+    fn failed_help_queries_are_not_taken_for_help() {
+        let runner = Arc::new(ScriptedCommandRunner::new()
+                        .respond_to(&["apptainer", "run-help", "--app", "missing"], ScriptedResponse::failure("FATAL: no app found named missing", 255))
+                        .respond_to(&["apptainer", "run-help"], ScriptedResponse::success("  Counts the reads.\n")));
+        let engine = ContainerEngine::assume(runner.clone(), &RuntimePreference::Fixed(ContainerRuntime::Apptainer));
+        let container = PathBuf::from("/data/tool.sif");
+
+        assert_eq!(singularity_help(&engine, &container, None), Some("Counts the reads.".to_string()));
+        assert_eq!(singularity_help(&engine, &container, Some("missing")), None);
+    }
+
+    #[test]
+    fn logged_commands_do_not_show_environment_values() {
+        let spec = CommandSpec::new("apptainer").args(["run", "/data/tool.sif"])
+                                                .env("APPTAINERENV_COLONY_SESSION_TOKEN", "s3cr3t-token")
+                                                .env("APPTAINERENV_COLONY_FRONTEND_TASK_URL", "http://127.0.0.1:4321/frontend/task/s3cr3t-token");
+
+        let logged = format!("{:?}", spec);
+
+        assert!(!logged.contains("s3cr3t-token"));
+        assert!(logged.contains("APPTAINERENV_COLONY_SESSION_TOKEN"));
+        assert!(logged.contains("/data/tool.sif"));
+    }
+}
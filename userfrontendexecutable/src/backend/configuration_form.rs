@@ -0,0 +1,319 @@
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use serde_json::{Map, Number, Value};
+
+use crate::backend::backend_misc::wslify_windows_path;
+use crate::backend::configuration_schema::{ConfigurationSchema, SchemaViolation, escape_pointer_key, unescape_pointer_key};
+
+
+
+//################################################################################
+//## Configuration forms
+//## (containers without a GUI of their own are configured through a form built
+//## from the schema their app-configurations app publishes; the form produces
+//## the same configuration a container GUI posts to the frontend-local server)
+//################################################################################
+
+// deeper objects are typed in as JSON
+const MAX_FORM_DEPTH: usize = 8;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FieldKind {
+    Group,                  // heading of a nested object, its fields follow it
+    Text,
+    Integer,
+    Number,
+    Boolean,
+    Choice(Vec<Value>),     // enum
+    FilePath,               // keys ending in _path
+    DirectoryPath,          // keys ending in _dir_path or _directory_path, or strings with format "directory"
+    TextList,               // arrays of strings, one item per line
+    Json,                   // everything else is typed in as JSON
+}
+
+impl FieldKind {
+    fn of(key: &str, property: &Value, depth: usize) -> FieldKind {
+        if let Some(options) = property.get("enum").and_then(Value::as_array) {
+            return FieldKind::Choice(options.clone());
+        }
+        // ["string", "null"] describes an optional string
+        let type_name = match property.get("type") {
+            Some(Value::String(name)) => name.as_str(),
+            Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).find(|name| *name != "null").unwrap_or(""),
+            _ => "",
+        };
+        let is_directory = key.ends_with("_dir_path") || key.ends_with("_directory_path")
+                           || property.get("format").and_then(Value::as_str) == Some("directory");
+
+        return match type_name {
+            "boolean" => FieldKind::Boolean,
+            "integer" => FieldKind::Integer,
+            "number" => FieldKind::Number,
+            "object" | "" if property.get("properties").is_some() && depth < MAX_FORM_DEPTH => FieldKind::Group,
+            "array" if property.pointer("/items/type").and_then(Value::as_str) == Some("string") => FieldKind::TextList,
+            "string" | "" if is_directory => FieldKind::DirectoryPath,
+            "string" | "" if key.ends_with("_path") => FieldKind::FilePath,
+            "string" => FieldKind::Text,
+            _ => FieldKind::Json,
+        };
+    }
+
+    // what the input shows for a value, e.g. a default
+    pub fn input_text(&self, value: &Value) -> String {
+        return match (self, value) {
+            (FieldKind::Text | FieldKind::FilePath | FieldKind::DirectoryPath, Value::String(text)) => text.clone(),
+            (FieldKind::TextList, Value::Array(items)) => items.iter().map(|item| item.as_str().map(str::to_string).unwrap_or_else(|| item.to_string())).join("\n"),
+            (FieldKind::Json, value) => serde_json::to_string_pretty(value).unwrap_or_default(),
+            (FieldKind::Group, _) => String::new(),
+            // choices are kept as JSON, so "1" and 1 stay apart
+            (_, value) => value.to_string(),
+        };
+    }
+
+    // None for inputs left empty, optional values are then left out
+    pub fn value(&self, text: &str) -> Result<Option<Value>, String> {
+        match self {
+            FieldKind::Group => return Ok(None),
+            // an unticked checkbox is a decision as well
+            FieldKind::Boolean => return Ok(Some(Value::Bool(text == "true"))),
+            _ if text.trim().is_empty() => return Ok(None),
+            FieldKind::Text => return Ok(Some(Value::String(text.to_string()))),
+            FieldKind::Integer => {
+                return text.trim().parse::<i64>().map(|number| Some(Value::from(number))).map_err(|_| "must be a whole number".to_string());
+            },
+            FieldKind::Number => {
+                return text.trim().parse::<f64>().ok().and_then(Number::from_f64).map(|number| Some(Value::Number(number))).ok_or_else(|| "must be a number".to_string());
+            },
+            FieldKind::Choice(options) => {
+                return serde_json::from_str::<Value>(text).ok().filter(|value| options.contains(value)).map(Some).ok_or_else(|| "must be one of the offered options".to_string());
+            },
+            // as seen inside the container, like the paths the file dialogs of the frontend-local server return
+            FieldKind::FilePath | FieldKind::DirectoryPath => return Ok(Some(Value::String(wslify_windows_path(text.trim())))),
+            FieldKind::TextList => {
+                return Ok(Some(Value::Array(text.lines().map(str::trim).filter(|line| !line.is_empty()).map(|line| Value::String(line.to_string())).collect())));
+            },
+            FieldKind::Json => return serde_json::from_str(text).map(Some).map_err(|e| format!("is not valid JSON: {}", e)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConfigurationField {
+    pub pointer: String,                // where the value goes in the configuration, also identifies the input
+    pub title: String,
+    pub description: Option<String>,
+    pub kind: FieldKind,
+    pub required: bool,
+    pub default: Option<Value>,
+    pub depth: usize,                   // nesting level inside the configuration
+}
+
+impl ConfigurationField {
+    // an unticked checkbox or an untouched default is not something the user entered
+    fn is_entered(&self, value: &Value) -> bool {
+        return match (&self.default, value) {
+            (Some(default), value) => default != value,
+            (None, Value::Bool(checked)) => *checked,
+            (None, _) => true,
+        };
+    }
+}
+
+// inputs as typed, by field pointer
+pub type FormValues = BTreeMap<String, String>;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConfigurationForm {
+    pub schema: ConfigurationSchema,
+    pub fields: Vec<ConfigurationField>,
+}
+
+impl ConfigurationForm {
+    pub fn from_schema(schema: ConfigurationSchema) -> Result<Self, String> {
+        let root = schema.dereference(&schema.root);
+        if root.get("properties").and_then(Value::as_object).is_none_or(|properties| properties.is_empty()) {
+            return Err("The published configuration schema has no properties to fill in".to_string());
+        }
+        let mut fields = Vec::new();
+        collect_fields(&schema, root, "", 0, &mut fields);
+        return Ok(ConfigurationForm { schema, fields });
+    }
+
+    pub fn initial_values(&self) -> FormValues {
+        return self.fields.iter()
+                   .filter_map(|field| field.default.as_ref().map(|default| (field.pointer.clone(), field.kind.input_text(default))))
+                   .collect();
+    }
+
+    // the configuration a container GUI would post, checked against the schema like one
+    pub fn configuration(&self, values: &FormValues) -> Result<Value, Vec<SchemaViolation>> {
+        let mut entered = Vec::new();
+        let mut violations = Vec::new();
+        for field in self.fields.iter() {
+            let text = values.get(&field.pointer).map(String::as_str).unwrap_or("");
+            match field.kind.value(text) {
+                Ok(Some(value)) => entered.push((field, value)),
+                Ok(None) => {},
+                Err(message) => violations.push(SchemaViolation::new(&field.pointer, message)),
+            }
+        }
+        if !violations.is_empty() { return Err(violations); }
+
+        // optional groups only exist if something was entered inside them, otherwise their required fields would be missing
+        let left_out_groups = self.fields.iter()
+                                  .filter(|group| group.kind == FieldKind::Group && !group.required)
+                                  .map(|group| format!("{}/", group.pointer))
+                                  .filter(|prefix| !entered.iter().any(|(field, value)| field.pointer.starts_with(prefix) && field.is_entered(value)))
+                                  .collect_vec();
+        let mut configuration = Value::Object(Map::new());
+        for (field, value) in entered {
+            if !left_out_groups.iter().any(|prefix| field.pointer.starts_with(prefix)) {
+                insert_at(&mut configuration, &field.pointer, value);
+            }
+        }
+
+        let violations = self.schema.validate(&configuration);
+        if !violations.is_empty() { return Err(violations); }
+        return Ok(configuration);
+    }
+}
+
+fn collect_fields(schema: &ConfigurationSchema, object_schema: &Value, pointer: &str, depth: usize, fields: &mut Vec<ConfigurationField>) {
+    let required = object_schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str).collect_vec();
+    let Some(properties) = object_schema.get("properties").and_then(Value::as_object) else { return; };
+
+    for (key, property) in properties {
+        let property = schema.dereference(property);
+        let field_pointer = format!("{}/{}", pointer, escape_pointer_key(key));
+        let kind = FieldKind::of(key, property, depth);
+        fields.push(ConfigurationField {
+            pointer: field_pointer.clone(),
+            title: property.get("title").and_then(Value::as_str).unwrap_or(key).to_string(),
+            description: property.get("description").and_then(Value::as_str).map(str::to_string),
+            kind: kind.clone(),
+            required: required.contains(&key.as_str()),
+            default: property.get("default").cloned(),
+            depth,
+        });
+        if kind == FieldKind::Group {
+            collect_fields(schema, property, &field_pointer, depth + 1, fields);
+        }
+    }
+}
+
+// objects on the way are created as needed
+fn insert_at(configuration: &mut Value, pointer: &str, value: Value) {
+    let keys = pointer.split('/').skip(1).map(unescape_pointer_key).collect_vec();
+    let Some((last, parents)) = keys.split_last() else { return; };
+
+    let mut target = configuration;
+    for key in parents {
+        let Value::Object(object) = target else { return; };
+        target = object.entry(key.clone()).or_insert_with(|| Value::Object(Map::new()));
+    }
+    if let Value::Object(object) = target {
+        object.insert(last.clone(), value);
+    }
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn form_produces_the_configuration_a_gui_would_post() {
+        let schema = ConfigurationSchema::parse(r##"{
+            "type": "object",
+            "required": ["input_path", "threads", "output"],
+            "properties": {
+                "input_path": { "type": "string", "title": "Input file" },
+                "threads": { "type": "integer", "minimum": 1, "default": 4 },
+                "mode": { "enum": ["fast", "exact"], "default": "fast" },
+                "verbose": { "type": "boolean" },
+                "samples": { "type": "array", "items": { "type": "string" } },
+                "output": { "$ref": "#/$defs/output" }
+            },
+            "$defs": {
+                "output": {
+                    "type": "object",
+                    "required": ["results_dir_path"],
+                    "properties": { "results_dir_path": { "type": "string" }, "prefix": { "type": "string" } }
+                }
+            }
+        }"##).unwrap();
+        let form = ConfigurationForm::from_schema(schema).unwrap();
+
+        let kinds = form.fields.iter().map(|field| (field.pointer.as_str(), field.kind.clone())).collect::<Vec<_>>();
+        assert_eq!(kinds, vec![
+            ("/input_path", FieldKind::FilePath),
+            ("/mode", FieldKind::Choice(vec![json!("fast"), json!("exact")])),
+            ("/output", FieldKind::Group),
+            ("/output/prefix", FieldKind::Text),
+            ("/output/results_dir_path", FieldKind::DirectoryPath),
+            ("/samples", FieldKind::TextList),
+            ("/threads", FieldKind::Integer),
+            ("/verbose", FieldKind::Boolean),
+        ]);
+        assert_eq!(form.fields[0].title, "Input file");
+
+        let mut values = form.initial_values();
+        assert_eq!(values.get("/threads").map(String::as_str), Some("4"));
+        assert_eq!(values.get("/mode").map(String::as_str), Some("\"fast\""));
+
+        // missing required values are reported by the schema, typing errors by the form
+        let violations = form.configuration(&values).unwrap_err();
+        assert_eq!(violations.iter().map(|violation| violation.to_string()).collect::<Vec<_>>(), vec!["input_path is required", "output is required"]);
+        values.insert("/threads".to_string(), "many".to_string());
+        assert_eq!(form.configuration(&values).unwrap_err()[0].to_string(), "/threads: must be a whole number");
+
+        values.insert("/threads".to_string(), "8".to_string());
+        values.insert("/input_path".to_string(), "/data/reads.fastq".to_string());
+        values.insert("/output/results_dir_path".to_string(), "/data/results".to_string());
+        values.insert("/samples".to_string(), "S1\n\n S2 \n".to_string());
+        assert_eq!(form.configuration(&values).unwrap(), json!({
+            "input_path": "/data/reads.fastq",
+            "mode": "fast",
+            "output": { "results_dir_path": "/data/results" },
+            "samples": ["S1", "S2"],
+            "threads": 8,
+            "verbose": false,
+        }));
+    }
+
+    #[test]
+    fn optional_groups_are_left_out_until_something_is_entered() {
+        let schema = ConfigurationSchema::parse(r##"{
+            "type": "object",
+            "properties": {
+                "plot": {
+                    "type": "object",
+                    "required": ["title"],
+                    "properties": {
+                        "title": { "type": "string" },
+                        "log_scale": { "type": "boolean" },
+                        "width": { "type": "integer", "default": 800 }
+                    }
+                }
+            }
+        }"##).unwrap();
+        let form = ConfigurationForm::from_schema(schema).unwrap();
+
+        let mut values = form.initial_values();
+        assert_eq!(form.configuration(&values).unwrap(), json!({}));
+
+        values.insert("/plot/log_scale".to_string(), "true".to_string());
+        assert_eq!(form.configuration(&values).unwrap_err()[0].to_string(), "/plot: title is required");
+
+        values.insert("/plot/title".to_string(), "Coverage".to_string());
+        assert_eq!(form.configuration(&values).unwrap(), json!({ "plot": { "log_scale": true, "title": "Coverage", "width": 800 } }));
+    }
+}
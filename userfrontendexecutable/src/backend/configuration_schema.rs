@@ -0,0 +1,320 @@
+
+use std::fmt::Display;
+
+use itertools::Itertools;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+
+
+//################################################################################
+//## Configuration schemas
+//## (containers publish a JSON schema for their configuration through their
+//## app-configurations app; configurations are checked against it before they
+//## are saved; the commonly used keywords are supported, others are ignored)
+//################################################################################
+
+// self-references like {"$ref": "#"} must not recurse forever
+const MAX_SCHEMA_DEPTH: usize = 64;
+
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct SchemaViolation {
+    pub path: String,       // JSON pointer to the offending value, empty for the configuration as a whole
+    pub message: String,
+}
+
+impl SchemaViolation {
+    pub fn new(path: &str, message: impl Into<String>) -> Self {
+        return SchemaViolation { path: path.to_string(), message: message.into() };
+    }
+}
+
+impl Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() { return write!(f, "{}", self.message); }
+        return write!(f, "{}: {}", self.path, self.message);
+    }
+}
+
+// a configuration that is not even JSON is a violation of every schema
+pub fn parse_configuration(text: &str) -> Result<Value, SchemaViolation> {
+    return serde_json::from_str(text).map_err(|e| SchemaViolation::new("", format!("the configuration is not JSON: {}", e)));
+}
+
+// keys are escaped where they are part of a JSON pointer, e.g. "a/b" becomes "a~1b"
+pub fn escape_pointer_key(key: &str) -> String {
+    return key.replace('~', "~0").replace('/', "~1");
+}
+
+pub fn unescape_pointer_key(escaped: &str) -> String {
+    return escaped.replace("~1", "/").replace("~0", "~");
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ConfigurationSchema {
+    pub root: Value,
+}
+
+impl ConfigurationSchema {
+    // the output of app-configurations
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let root: Value = serde_json::from_str(text.trim()).map_err(|e| format!("The published configuration schema is not JSON: {}", e))?;
+        if !(root.is_object() || root.is_boolean()) {
+            return Err("The published configuration schema is neither an object nor a boolean".to_string());
+        }
+        return Ok(ConfigurationSchema { root });
+    }
+
+    pub fn validate(&self, configuration: &Value) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        self.validate_value(&self.root, configuration, "", 0, &mut violations);
+        return violations;
+    }
+
+    // follows references until the schema they point at, e.g. to build a form from it
+    pub fn dereference<'a>(&'a self, mut schema: &'a Value) -> &'a Value {
+        for _ in 0..MAX_SCHEMA_DEPTH {
+            match schema.get("$ref").and_then(Value::as_str).and_then(|reference| self.resolve(reference)) {
+                Some(target) => schema = target,
+                None => break,
+            }
+        }
+        return schema;
+    }
+
+    // only references into the schema itself, e.g. "#/$defs/input"
+    fn resolve(&self, reference: &str) -> Option<&Value> {
+        return reference.strip_prefix('#').and_then(|pointer| self.root.pointer(pointer));
+    }
+
+    fn matches(&self, schema: &Value, value: &Value, depth: usize) -> bool {
+        let mut violations = Vec::new();
+        self.validate_value(schema, value, "", depth, &mut violations);
+        return violations.is_empty();
+    }
+
+    fn validate_value(&self, schema: &Value, value: &Value, path: &str, depth: usize, violations: &mut Vec<SchemaViolation>) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => { violations.push(SchemaViolation::new(path, "no value is allowed here")); return; },
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+        if depth > MAX_SCHEMA_DEPTH {
+            violations.push(SchemaViolation::new(path, "the schema is nested too deeply to be checked"));
+            return;
+        }
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(reference) {
+                Some(target) => self.validate_value(target, value, path, depth + 1, violations),
+                None => violations.push(SchemaViolation::new(path, format!("the schema refers to {}, which it does not contain", reference))),
+            }
+        }
+
+        if let Some(types) = schema.get("type") {
+            let allowed = match types {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|name| has_type(value, name)) {
+                violations.push(SchemaViolation::new(path, format!("expected {}, found {}", allowed.join(" or "), type_name(value))));
+                // the remaining keywords would only repeat the mismatch
+                return;
+            }
+        }
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            if !options.contains(value) {
+                violations.push(SchemaViolation::new(path, format!("must be one of {}", options.iter().join(", "))));
+            }
+        }
+        if let Some(constant) = schema.get("const") {
+            if constant != value {
+                violations.push(SchemaViolation::new(path, format!("must be {}", constant)));
+            }
+        }
+
+        match value {
+            Value::Object(object) => self.validate_object(schema, object, path, depth, violations),
+            Value::Array(items) => self.validate_array(schema, items, path, depth, violations),
+            Value::String(text) => validate_string(schema, text, path, violations),
+            Value::Number(number) => validate_number(schema, number, path, violations),
+            _ => {},
+        }
+
+        if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+            for sub_schema in all {
+                self.validate_value(sub_schema, value, path, depth + 1, violations);
+            }
+        }
+        if let Some(any) = schema.get("anyOf").and_then(Value::as_array) {
+            if !any.iter().any(|sub_schema| self.matches(sub_schema, value, depth + 1)) {
+                violations.push(SchemaViolation::new(path, "does not match any of the allowed forms"));
+            }
+        }
+        if let Some(one) = schema.get("oneOf").and_then(Value::as_array) {
+            let matching = one.iter().filter(|sub_schema| self.matches(sub_schema, value, depth + 1)).count();
+            if matching != 1 {
+                violations.push(SchemaViolation::new(path, format!("must match exactly one of the allowed forms, matches {}", matching)));
+            }
+        }
+        if let Some(excluded) = schema.get("not") {
+            if self.matches(excluded, value, depth + 1) {
+                violations.push(SchemaViolation::new(path, "matches a form that is not allowed"));
+            }
+        }
+    }
+
+    fn validate_object(&self, schema: &Map<String, Value>, object: &Map<String, Value>, path: &str, depth: usize, violations: &mut Vec<SchemaViolation>) {
+        let properties = schema.get("properties").and_then(Value::as_object);
+
+        for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+            if !object.contains_key(name) {
+                violations.push(SchemaViolation::new(path, format!("{} is required", name)));
+            }
+        }
+        for (name, value) in object {
+            let value_path = format!("{}/{}", path, escape_pointer_key(name));
+            match (properties.and_then(|properties| properties.get(name)), schema.get("additionalProperties")) {
+                (Some(property_schema), _) => self.validate_value(property_schema, value, &value_path, depth + 1, violations),
+                (None, Some(Value::Bool(false))) => violations.push(SchemaViolation::new(path, format!("{} is not allowed", name))),
+                (None, Some(additional_schema)) => self.validate_value(additional_schema, value, &value_path, depth + 1, violations),
+                (None, None) => {},
+            }
+        }
+    }
+
+    fn validate_array(&self, schema: &Map<String, Value>, items: &[Value], path: &str, depth: usize, violations: &mut Vec<SchemaViolation>) {
+        if let Some(minimum) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < minimum { violations.push(SchemaViolation::new(path, format!("must have at least {} items", minimum))); }
+        }
+        if let Some(maximum) = schema.get("maxItems").and_then(Value::as_u64) {
+            if (items.len() as u64) > maximum { violations.push(SchemaViolation::new(path, format!("must have at most {} items", maximum))); }
+        }
+        match schema.get("items") {
+            // older schemas give one schema per position
+            Some(Value::Array(item_schemas)) => {
+                for (index, (item_schema, item)) in item_schemas.iter().zip(items).enumerate() {
+                    self.validate_value(item_schema, item, &format!("{}/{}", path, index), depth + 1, violations);
+                }
+            },
+            Some(item_schema) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.validate_value(item_schema, item, &format!("{}/{}", path, index), depth + 1, violations);
+                }
+            },
+            None => {},
+        }
+    }
+}
+
+fn validate_string(schema: &Map<String, Value>, text: &str, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let length = text.chars().count() as u64;
+    if let Some(minimum) = schema.get("minLength").and_then(Value::as_u64) {
+        if length < minimum { violations.push(SchemaViolation::new(path, format!("must be at least {} characters long", minimum))); }
+    }
+    if let Some(maximum) = schema.get("maxLength").and_then(Value::as_u64) {
+        if length > maximum { violations.push(SchemaViolation::new(path, format!("must be at most {} characters long", maximum))); }
+    }
+    // patterns the regex crate cannot compile are not checked
+    if let Some(Ok(pattern)) = schema.get("pattern").and_then(Value::as_str).map(Regex::new) {
+        if !pattern.is_match(text) { violations.push(SchemaViolation::new(path, format!("must match the pattern {}", pattern.as_str()))); }
+    }
+}
+
+fn validate_number(schema: &Map<String, Value>, number: &Number, path: &str, violations: &mut Vec<SchemaViolation>) {
+    let Some(number) = number.as_f64() else { return; };
+    let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+
+    if let Some(minimum) = bound("minimum") {
+        if number < minimum { violations.push(SchemaViolation::new(path, format!("must be at least {}", minimum))); }
+    }
+    if let Some(maximum) = bound("maximum") {
+        if number > maximum { violations.push(SchemaViolation::new(path, format!("must be at most {}", maximum))); }
+    }
+    if let Some(minimum) = bound("exclusiveMinimum") {
+        if number <= minimum { violations.push(SchemaViolation::new(path, format!("must be greater than {}", minimum))); }
+    }
+    if let Some(maximum) = bound("exclusiveMaximum") {
+        if number >= maximum { violations.push(SchemaViolation::new(path, format!("must be less than {}", maximum))); }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    return match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|number| number.fract() == 0.0),
+        _ => true,
+    };
+}
+
+fn type_name(value: &Value) -> &'static str {
+    return match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    };
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn violations_point_at_the_offending_values() {
+        let schema = ConfigurationSchema::parse(r##"{
+            "type": "object",
+            "required": ["input", "threads"],
+            "additionalProperties": false,
+            "properties": {
+                "input": { "$ref": "#/$defs/path" },
+                "threads": { "type": "integer", "minimum": 1 },
+                "mode": { "enum": ["fast", "exact"] },
+                "samples": { "type": "array", "items": { "type": "string", "pattern": "^S[0-9]+$" } }
+            },
+            "$defs": { "path": { "type": "string", "minLength": 1 } }
+        }"##).unwrap();
+
+        let valid = parse_configuration(r#"{"input": "/data", "threads": 4, "mode": "fast", "samples": ["S1", "S22"]}"#).unwrap();
+        assert_eq!(schema.validate(&valid), Vec::new());
+
+        let invalid = parse_configuration(r#"{"input": "", "threads": 2.5, "mode": "slow", "samples": ["S1", "X"], "extra": true}"#).unwrap();
+        // properties are visited in the order of the configuration's map, which depends on serde_json's features
+        let violations = schema.validate(&invalid).iter().map(|violation| violation.to_string()).sorted().collect::<Vec<_>>();
+        assert_eq!(violations, vec![
+            "/input: must be at least 1 characters long",
+            "/mode: must be one of \"fast\", \"exact\"",
+            "/samples/1: must match the pattern ^S[0-9]+$",
+            "/threads: expected integer, found a number",
+            "extra is not allowed",
+        ]);
+
+        assert_eq!(schema.validate(&parse_configuration(r#"["not", "an", "object"]"#).unwrap()).len(), 1);
+        assert!(parse_configuration("threads = 4").is_err());
+        assert!(ConfigurationSchema::parse("no schema").is_err());
+    }
+
+    #[test]
+    fn pointer_keys_survive_escaping() {
+        assert_eq!(escape_pointer_key("a/b~c"), "a~1b~0c");
+        // "~01" is an escaped "~1", not an escaped "/"
+        assert_eq!(unescape_pointer_key("~01"), "~1");
+        assert_eq!(unescape_pointer_key(&escape_pointer_key("~/~1")), "~/~1");
+    }
+}
@@ -0,0 +1,159 @@
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use plugin_interface_elements::elements_v1::{self, ConfigurationVersion, RemoteOperationError};
+
+use crate::backend::json_file::{JsonFile, launcher_file_path};
+
+
+
+//################################################################################
+//## Configuration store
+//## (every stored configuration is kept as a new version, nothing is overwritten)
+//################################################################################
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct StoredConfiguration {
+    pub container: String,
+    pub app: Option<String>,
+    pub info: ConfigurationVersion,
+    pub configuration: String,
+}
+
+impl StoredConfiguration {
+    fn belongs_to(&self, container: &str, app: &Option<String>) -> bool {
+        return self.container == container && &self.app == app;
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct ConfigurationStore {
+    pub configurations: Vec<StoredConfiguration>,
+    #[serde(skip)]
+    file: JsonFile,                     // where every new version is written before it counts as stored
+}
+
+pub type SharedConfigurationStore = Arc<Mutex<ConfigurationStore>>;
+
+pub fn configuration_store_path() -> Option<PathBuf> {
+    return launcher_file_path("colony_configuration_store.json");
+}
+
+impl ConfigurationStore {
+    pub fn from_file(path: &Option<PathBuf>) -> Self {
+        let mut file = JsonFile::new(path.clone(), "configuration store");
+        let store: ConfigurationStore = file.read();
+        return ConfigurationStore { file, ..store };
+    }
+
+    pub fn versions(&self, container: &str, app: &Option<String>) -> Vec<&StoredConfiguration> {
+        return self.configurations.iter().filter(|stored| stored.belongs_to(container, app)).collect();
+    }
+
+    pub fn latest(&self, container: &str, app: &Option<String>) -> Option<&StoredConfiguration> {
+        return self.versions(container, app).into_iter().max_by_key(|stored| stored.info.version);
+    }
+
+    // names may be reused, the newest version carrying the name wins
+    pub fn named(&self, container: &str, app: &Option<String>, name: &str) -> Option<&StoredConfiguration> {
+        return self.versions(container, app).into_iter()
+                    .filter(|stored| stored.info.name.as_deref() == Some(name))
+                    .max_by_key(|stored| stored.info.version);
+    }
+
+    pub fn store(&mut self, container: String, app: Option<String>, name: Option<String>, author: String, configuration: String) -> Result<ConfigurationVersion, RemoteOperationError> {
+        let version = self.latest(&container, &app).map(|stored| stored.info.version + 1).unwrap_or(1);
+        let info = ConfigurationVersion { version, name, author, creation_time: Utc::now() };
+
+        self.configurations.push(StoredConfiguration { container, app, info: info.clone(), configuration });
+        if let Err(e) = self.file.write(self) {
+            self.configurations.pop();
+            return Err(RemoteOperationError::InternalFailure(e));
+        }
+        return Ok(info);
+    }
+}
+
+
+
+//################################################################################
+//## Protocol handling
+//################################################################################
+
+pub fn handle_configuration_request(store: &SharedConfigurationStore, request: &elements_v1::FrontendTaskRequest) -> Option<elements_v1::FrontendTaskResponse> {
+    let poisoned = || RemoteOperationError::InternalFailure("Configuration store is unavailable".to_string());
+
+    return match request {
+        elements_v1::FrontendTaskRequest::RequestConfiguration(request_data) => {
+            let found = store.lock()
+                            .map_err(|_| poisoned())
+                            .map(|store| {
+                                let stored = match &request_data.name {
+                                    Some(name) => store.named(&request_data.container, &request_data.app, name),
+                                    None => store.latest(&request_data.container, &request_data.app),
+                                };
+                                stored.cloned()
+                            });
+            let response_data = match found {
+                Ok(stored) => elements_v1::RequestConfigurationResponse {
+                    configuration: Ok(stored.as_ref().map(|stored| stored.configuration.clone())),
+                    version: stored.map(|stored| stored.info),
+                },
+                Err(e) => elements_v1::RequestConfigurationResponse { configuration: Err(e), version: None },
+            };
+            Some(elements_v1::FrontendTaskResponse::RequestConfiguration(response_data))
+        },
+        elements_v1::FrontendTaskRequest::HaveConfigurationStored(store_data) => {
+            let version = store.lock()
+                            .map_err(|_| poisoned())
+                            .and_then(|mut store| {
+                                store.store(store_data.container.clone(), store_data.app.clone(), store_data.name.clone(),
+                                            store_data.author.clone(), store_data.configuration.clone())
+                            });
+            let response_data = elements_v1::HaveConfigurationStoredResponse { version };
+            Some(elements_v1::FrontendTaskResponse::HaveConfigurationStored(response_data))
+        },
+        _ => None
+    };
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_count_up_per_container_and_app() {
+        let path = std::env::temp_dir().join(format!("colony_configuration_store_test_{}.json", uuid::Uuid::new_v4()));
+        let mut store = ConfigurationStore::from_file(&Some(path.clone()));
+        let align = Some("align".to_string());
+
+        let first = store.store("rnaseq.sif".to_string(), align.clone(), Some("defaults".to_string()), "user".to_string(), "{\"threads\": 4}".to_string()).unwrap();
+        let second = store.store("rnaseq.sif".to_string(), align.clone(), None, "plugin".to_string(), "{\"threads\": 8}".to_string()).unwrap();
+        let other_app = store.store("rnaseq.sif".to_string(), None, Some("defaults".to_string()), "user".to_string(), "{}".to_string()).unwrap();
+        assert_eq!((first.version, second.version, other_app.version), (1, 2, 1));
+
+        assert_eq!(store.latest("rnaseq.sif", &align).unwrap().configuration, "{\"threads\": 8}");
+        assert_eq!(store.named("rnaseq.sif", &align, "defaults").unwrap().info.version, 1);
+        assert!(store.named("rnaseq.sif", &align, "unknown").is_none());
+        assert!(store.latest("qc.sif", &None).is_none());
+
+        let restored = ConfigurationStore::from_file(&Some(path.clone()));
+        std::fs::remove_file(&path).ok();
+        assert_eq!(restored.versions("rnaseq.sif", &align).len(), 2);
+
+        // a version that could not be written is not kept either
+        let mut in_memory = ConfigurationStore::default();
+        assert!(in_memory.store("rnaseq.sif".to_string(), None, None, "user".to_string(), "{}".to_string()).is_err());
+        assert!(in_memory.configurations.is_empty());
+    }
+}
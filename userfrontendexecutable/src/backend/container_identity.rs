@@ -0,0 +1,134 @@
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::mpsc::Sender;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::backend::BackendRequest;
+
+
+
+//################################################################################
+//## Container identity
+//## (a container is the same container if its content is the same, wherever the
+//## image file lives; hashing takes a while, so the result is cached together
+//## with the file's size and modification time)
+//################################################################################
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ContainerDigest(pub String);     // "sha256:<hex>"
+
+impl ContainerDigest {
+    // enough to tell containers apart in the UI
+    pub fn short(&self) -> &str {
+        let hex = self.0.strip_prefix("sha256:").unwrap_or(&self.0);
+        return &hex[..hex.len().min(12)];
+    }
+}
+
+impl fmt::Display for ContainerDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", &self.0);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct FileFingerprint {
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+impl FileFingerprint {
+    pub fn of(path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        return Ok(FileFingerprint { size: metadata.len(), modified: metadata.modified().ok() });
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ContainerIdentity {
+    pub digest: ContainerDigest,
+    pub fingerprint: FileFingerprint,     // of the file the digest was computed from
+}
+
+impl ContainerIdentity {
+    // false if the file changed (or vanished) since it was hashed
+    pub fn is_current(&self, path: &Path) -> bool {
+        return FileFingerprint::of(path).is_ok_and(|fingerprint| fingerprint == self.fingerprint);
+    }
+}
+
+pub fn sha256_file(path: &Path) -> std::io::Result<ContainerDigest> {
+    let mut reader = BufReader::with_capacity(1 << 20, File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 { break; }
+        hasher.update(&buffer[..read]);
+    }
+
+    let hex = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+    return Ok(ContainerDigest(format!("sha256:{}", hex)));
+}
+
+pub fn identify_container(path: &Path) -> std::io::Result<ContainerIdentity> {
+    let before = FileFingerprint::of(path)?;
+    let digest = sha256_file(path)?;
+    let fingerprint = FileFingerprint::of(path)?;
+    if before != fingerprint {
+        return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "Container image changed while it was hashed"));
+    }
+    return Ok(ContainerIdentity { digest, fingerprint });
+}
+
+// containers waiting to be hashed
+pub type IdentificationQueue = Sender<(Uuid, PathBuf)>;
+
+// hashes in the background, one image after another, since reading several multi-GB images side by side
+// only makes each of them slower; the backend is informed through the usual request channel
+pub fn start_container_identification(comm_with_backend: Sender<BackendRequest>) -> IdentificationQueue {
+    let (queue, waiting) = mpsc::channel::<(Uuid, PathBuf)>();
+    std::thread::spawn(move || {
+        for (container_id, path) in waiting {
+            println!("Computing digest of container {:?}", &path);
+            let identity = identify_container(&path).map_err(|e| format!("Could not hash {:?}: {}", &path, e));
+            comm_with_backend.send(BackendRequest::InformContainerIdentified(container_id, identity)).ok();
+        }
+    });
+    return queue;
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digest_is_sha256_of_file_content() {
+        let path = std::env::temp_dir().join(format!("colony_identity_test_{}.sif", Uuid::new_v4()));
+        std::fs::write(&path, b"abc").unwrap();
+
+        let identity = identify_container(&path).unwrap();
+        assert_eq!(identity.digest.0, "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(identity.digest.short(), "ba7816bf8f01");
+        assert!(identity.is_current(&path));
+
+        std::fs::write(&path, b"abcd").unwrap();
+        assert!(!identity.is_current(&path));
+        std::fs::remove_file(&path).ok();
+        assert!(!identity.is_current(&path));
+    }
+}
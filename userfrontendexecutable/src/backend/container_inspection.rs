@@ -0,0 +1,199 @@
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::backend::container_runtime::{ContainerRuntime, RuntimeVersion};
+
+
+
+//################################################################################
+//## Typed `inspect --all` output
+//## (singularity and apptainer both answer with {"data": {"attributes": {...}}},
+//## but differ in label names and in how the environment is reported)
+//################################################################################
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct AppInspection {
+    pub name: String,
+    pub help: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub runscript: Option<String>,
+    pub environment: BTreeMap<String, String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ContainerInspection {
+    pub labels: BTreeMap<String, String>,
+    pub environment: BTreeMap<String, String>,      // variables exported by the environment scripts
+    pub environment_scripts: Vec<String>,
+    pub runscript: Option<String>,
+    pub deffile: Option<String>,
+    pub help: Option<String>,
+    pub apps: BTreeMap<String, AppInspection>,
+    pub build_date: Option<String>,
+    pub built_with: Option<RuntimeVersion>,         // the runtime version that built the image
+}
+
+const BUILD_DATE_LABEL: &str = "org.label-schema.build-date";
+
+impl ContainerInspection {
+    pub fn from_json(json: &Value) -> Result<Self, String> {
+        let attributes = json.pointer("/data/attributes")
+                            .and_then(Value::as_object)
+                            .ok_or_else(|| "Inspection output contains no data.attributes".to_string())?;
+
+        let labels = string_map(attributes.get("labels"));
+        let environment_scripts = scripts(attributes.get("environment"));
+
+        let apps = attributes.get("apps")
+                        .and_then(Value::as_object)
+                        .map(|apps| {
+                            apps.iter().map(|(name, app)| {
+                                let app_inspection = AppInspection {
+                                    name: name.clone(),
+                                    help: text(app.get("helpfile")),
+                                    labels: string_map(app.get("labels")),
+                                    runscript: text(app.get("runscript")),
+                                    environment: exported_variables(&scripts(app.get("environment"))),
+                                };
+                                (name.clone(), app_inspection)
+                            }).collect()
+                        })
+                        .unwrap_or_default();
+
+        // e.g. "Thursday_8_February_2024_10:0:35_CET"
+        let build_date = labels.get(BUILD_DATE_LABEL).map(|date| date.replace('_', " "));
+
+        let built_with = ContainerRuntime::ALL.into_iter().find_map(|runtime| {
+            labels.get(&format!("org.label-schema.usage.{}.version", runtime.binary()))
+                .map(|version| RuntimeVersion { runtime, flavor: runtime.binary().to_string(), version: version.clone() })
+        });
+
+        return Ok(ContainerInspection {
+            environment: exported_variables(&environment_scripts),
+            environment_scripts,
+            runscript: text(attributes.get("runscript")),
+            deffile: text(attributes.get("deffile")),
+            help: text(attributes.get("helpfile")),
+            apps,
+            build_date,
+            built_with,
+            labels,
+        });
+    }
+
+    // labels added by the build tooling are left out
+    pub fn user_labels(&self) -> impl Iterator<Item = (&String, &String)> {
+        return self.labels.iter().filter(|(key, _)| !key.starts_with("org.label-schema."));
+    }
+
+    pub fn app_help(&self, app: &str) -> Option<&String> {
+        return self.apps.get(app).and_then(|app| app.help.as_ref());
+    }
+}
+
+// output of `inspect --json --labels`, with or without `--app`
+pub fn labels_from_json(json: &Value) -> Result<BTreeMap<String, String>, String> {
+    let labels = json.pointer("/data/attributes/labels")
+                    .ok_or_else(|| "Label output contains no data.attributes.labels".to_string())?;
+    return Ok(string_map(Some(labels)));
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    return value.and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from);
+}
+
+// labels are strings, but are not always written as such
+pub(crate) fn string_map(value: Option<&Value>) -> BTreeMap<String, String> {
+    return value.and_then(Value::as_object)
+                .map(|map| {
+                    map.iter().map(|(key, value)| {
+                        let value = value.as_str().map(String::from).unwrap_or_else(|| value.to_string());
+                        (key.clone(), value)
+                    }).collect()
+                })
+                .unwrap_or_default();
+}
+
+// singularity reports a single script, apptainer (and newer singularity) a map from file name to script
+fn scripts(value: Option<&Value>) -> Vec<String> {
+    return match value {
+        Some(Value::String(script)) => vec![script.clone()],
+        Some(Value::Object(files)) => files.values().filter_map(Value::as_str).map(String::from).collect(),
+        _ => Vec::new(),
+    };
+}
+
+// only picks up plain assignments, anything computed at runtime stays in the scripts
+fn exported_variables(scripts: &[String]) -> BTreeMap<String, String> {
+    return scripts.iter()
+            .flat_map(|script| script.lines())
+            .filter_map(|line| {
+                let line = line.trim();
+                let assignment = line.strip_prefix("export ").unwrap_or(line);
+                let (name, value) = assignment.split_once('=')?;
+                let is_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !is_name || line.starts_with('#') { return None; }
+
+                let value = value.trim();
+                let unquoted = value.strip_prefix('"').and_then(|v| v.strip_suffix('"'))
+                                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                                .unwrap_or(value);
+                Some((name.to_string(), unquoted.to_string()))
+            })
+            .collect();
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apptainer_and_singularity_output_parse_alike() {
+        let apptainer = serde_json::json!({
+            "data": { "attributes": {
+                "labels": {
+                    "org.label-schema.build-date": "Thursday_8_February_2024_10:0:35_CET",
+                    "org.label-schema.usage.apptainer.version": "1.2.5",
+                    "maintainer": "colony"
+                },
+                "environment": { "90-environment.sh": "#!/bin/sh\nexport REFERENCE=\"/opt/ref.fa\"\nexport PATH=/opt/bin:$PATH\n" },
+                "runscript": "#!/bin/sh\nexec align \"$@\"\n",
+                "apps": { "app-requirements": { "helpfile": "Lists requirements", "labels": { "cores": 4 } } }
+            }},
+            "type": "container"
+        });
+        let singularity = serde_json::json!({
+            "data": { "attributes": {
+                "labels": { "org.label-schema.usage.singularity.version": "3.11.4", "maintainer": "colony" },
+                "environment": "export REFERENCE='/opt/ref.fa'\n"
+            }}
+        });
+
+        let apptainer = ContainerInspection::from_json(&apptainer).unwrap();
+        let singularity = ContainerInspection::from_json(&singularity).unwrap();
+
+        assert_eq!(apptainer.build_date.as_deref(), Some("Thursday 8 February 2024 10:0:35 CET"));
+        assert_eq!(apptainer.built_with.as_ref().map(|version| version.runtime), Some(ContainerRuntime::Apptainer));
+        assert_eq!(singularity.built_with.as_ref().map(|version| version.version.as_str()), Some("3.11.4"));
+        assert_eq!(apptainer.environment.get("REFERENCE"), singularity.environment.get("REFERENCE"));
+        assert_eq!(apptainer.environment.get("PATH").map(String::as_str), Some("/opt/bin:$PATH"));
+        assert_eq!(apptainer.user_labels().collect::<Vec<_>>(), singularity.user_labels().collect::<Vec<_>>());
+        assert_eq!(apptainer.app_help("app-requirements").map(String::as_str), Some("Lists requirements"));
+        assert_eq!(apptainer.apps["app-requirements"].labels.get("cores").map(String::as_str), Some("4"));
+        assert!(ContainerInspection::from_json(&serde_json::json!({"type": "container"})).is_err());
+
+        let app_labels = serde_json::json!({ "data": { "attributes": { "labels": { "cores": 4, "maintainer": "colony" } } } });
+        assert_eq!(labels_from_json(&app_labels).unwrap().get("cores").map(String::as_str), Some("4"));
+    }
+}
@@ -0,0 +1,215 @@
+
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::mpsc::Sender;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::BackendRequest;
+use crate::backend::command_runner::{CommandRunner, CommandSpec};
+
+
+
+//################################################################################
+//## Container runtimes
+//## (Apptainer is the renamed fork of Singularity: every subcommand and flag the
+//## launcher passes is the same for both, what differs is the binary name, the
+//## prefix of variables passed into the container and the version output)
+//################################################################################
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ContainerRuntime {
+    #[default]
+    Singularity,
+    Apptainer,
+}
+
+impl ContainerRuntime {
+    pub const ALL: [ContainerRuntime; 2] = [ContainerRuntime::Singularity, ContainerRuntime::Apptainer];
+
+    pub fn binary(&self) -> &'static str {
+        return match self {
+            ContainerRuntime::Singularity => "singularity",
+            ContainerRuntime::Apptainer => "apptainer",
+        };
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        return match self {
+            ContainerRuntime::Singularity => "Singularity",
+            ContainerRuntime::Apptainer => "Apptainer",
+        };
+    }
+
+    // variables with this prefix are passed into the container without it
+    pub fn container_env_prefix(&self) -> &'static str {
+        return match self {
+            ContainerRuntime::Singularity => "SINGULARITYENV_",
+            ContainerRuntime::Apptainer => "APPTAINERENV_",
+        };
+    }
+
+    // e.g. "singularity-ce version 4.1.2", "singularity version 3.8.7" or "apptainer version 1.3.4";
+    // apptainer installs a `singularity` alias, so the output decides, not the binary that was called
+    pub fn parse_version(output: &str) -> Option<RuntimeVersion> {
+        let line = output.lines().map(str::trim).find(|line| !line.is_empty())?;
+        let mut words = line.split_whitespace();
+        let flavor = words.next()?.to_ascii_lowercase();
+
+        let runtime = if flavor.starts_with("apptainer") {
+            ContainerRuntime::Apptainer
+        } else if flavor.starts_with("singularity") {
+            ContainerRuntime::Singularity
+        } else {
+            return None;
+        };
+        let version = words.skip_while(|word| *word == "version").next()?.to_string();
+
+        return Some(RuntimeVersion { runtime, flavor, version });
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum RuntimePreference {
+    #[default]
+    Automatic,          // whatever is installed, Singularity first
+    Fixed(ContainerRuntime),
+}
+
+impl RuntimePreference {
+    fn candidates(&self) -> Vec<ContainerRuntime> {
+        return match self {
+            RuntimePreference::Automatic => ContainerRuntime::ALL.to_vec(),
+            RuntimePreference::Fixed(runtime) => vec![*runtime],
+        };
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RuntimeVersion {
+    pub runtime: ContainerRuntime,
+    pub flavor: String,         // as reported, e.g. "singularity-ce"
+    pub version: String,
+}
+
+impl fmt::Display for RuntimeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{} version {}", &self.flavor, &self.version);
+    }
+}
+
+pub fn runtime_version(runner: &dyn CommandRunner, runtime: ContainerRuntime) -> Option<RuntimeVersion> {
+    let output = runner.output(&CommandSpec::new(runtime.binary()).arg("--version")).ok()?;
+    if !output.status.success() {
+        println!("{} --version exited with {}", runtime.binary(), output.status);
+        return None;
+    }
+    return ContainerRuntime::parse_version(&String::from_utf8_lossy(&output.stdout));
+}
+
+pub fn detect_runtime(runner: &dyn CommandRunner, preference: &RuntimePreference) -> Option<RuntimeVersion> {
+    return preference.candidates().into_iter().find_map(|runtime| runtime_version(runner, runtime));
+}
+
+// --version can take seconds, e.g. while WSL starts up, so the backend does not wait for the answer
+pub fn spawn_runtime_detection(runner: Arc<dyn CommandRunner>, preference: RuntimePreference, comm_with_backend: Sender<BackendRequest>) {
+    std::thread::spawn(move || {
+        let version = detect_runtime(runner.as_ref(), &preference);
+        comm_with_backend.send(BackendRequest::InformRuntimeDetected(preference, version)).ok();
+    });
+}
+
+fn assumed_runtime(preference: &RuntimePreference) -> ContainerRuntime {
+    return preference.candidates().first().copied().unwrap_or_default();
+}
+
+// falls back to the preferred (or default) runtime, if nothing could be found yet
+fn choose_runtime(version: &Option<RuntimeVersion>, preference: &RuntimePreference) -> ContainerRuntime {
+    return match version {
+        Some(version) => {
+            println!("Using container runtime: {}", version);
+            version.runtime
+        },
+        None => {
+            let fallback = assumed_runtime(preference);
+            println!("No container runtime found, assuming {}", fallback.display_name());
+            fallback
+        }
+    };
+}
+
+
+
+//################################################################################
+//## Container engine
+//## (a runtime together with the place it is executed at)
+//################################################################################
+
+#[derive(Debug)]
+pub struct ContainerEngine {
+    pub runner: Arc<dyn CommandRunner>,
+    pub runtime: ContainerRuntime,
+    version: Option<RuntimeVersion>,    // as found by the last detection, None if nothing was found (yet)
+}
+
+impl ContainerEngine {
+    // until detection has answered, the preferred runtime is assumed
+    pub fn assume(runner: Arc<dyn CommandRunner>, preference: &RuntimePreference) -> Self {
+        return ContainerEngine { runner, runtime: assumed_runtime(preference), version: None };
+    }
+
+    pub fn redetect(&mut self, preference: &RuntimePreference) {
+        let version = detect_runtime(self.runner.as_ref(), preference);
+        self.use_detected(version, preference);
+    }
+
+    // the answer of spawn_runtime_detection
+    pub fn use_detected(&mut self, version: Option<RuntimeVersion>, preference: &RuntimePreference) {
+        self.runtime = choose_runtime(&version, preference);
+        self.version = version;
+    }
+
+    // the runtime does not change between detections, so the version is not asked for again
+    pub fn version(&self) -> Option<&RuntimeVersion> {
+        return self.version.as_ref();
+    }
+
+    // the runtime binary, arguments are added by the caller
+    pub fn command(&self) -> CommandSpec {
+        return CommandSpec::new(self.runtime.binary());
+    }
+
+    // makes a variable visible inside the container under the given name
+    pub fn container_env(&self, spec: CommandSpec, name: &str, value: &str) -> CommandSpec {
+        return spec.env(&format!("{}{}", self.runtime.container_env_prefix(), name), value);
+    }
+
+    pub fn host_path(&self, path: &Path) -> String {
+        return self.runner.host_path(path);
+    }
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtime_is_taken_from_version_output() {
+        let apptainer = ContainerRuntime::parse_version("apptainer version 1.3.4\n").unwrap();
+        assert_eq!(apptainer.runtime, ContainerRuntime::Apptainer);
+        assert_eq!(apptainer.version, "1.3.4");
+
+        let singularity = ContainerRuntime::parse_version("singularity-ce version 4.1.2-jammy").unwrap();
+        assert_eq!(singularity.runtime, ContainerRuntime::Singularity);
+        assert_eq!(singularity.to_string(), "singularity-ce version 4.1.2-jammy");
+
+        assert_eq!(ContainerRuntime::parse_version("bash: singularity: command not found"), None);
+    }
+}
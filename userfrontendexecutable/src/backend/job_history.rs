@@ -0,0 +1,227 @@
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{JobId, JobState, JobOutputLine};
+use crate::backend::backend_misc::unwslify_wsl_linux_path;
+use crate::backend::container_identity::ContainerDigest;
+use crate::backend::json_file::{JsonFile, launcher_file_path};
+use crate::backend::run_manifest::RunManifest;
+use crate::backend::run_profile::RunProfile;
+use crate::backend::singularity_interactions::COLONY_INTEROP_ARGUMENT;
+
+
+
+//################################################################################
+//## Job history
+//## (every container run is remembered with its settings and outcome, so it can
+//## be found again after a restart and be run once more)
+//################################################################################
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct JobRecord {
+    pub job_id: JobId,
+    pub container_path: PathBuf,
+    pub container_digest: Option<ContainerDigest>,
+    pub app: Option<String>,                        // None for the main app
+    pub arguments: Vec<String>,                     // as passed to the container
+    #[serde(default)]
+    pub run_profile: RunProfile,                    // as run, the container's profile may have changed since
+    pub working_directory: PathBuf,
+    pub configuration_path: Option<PathBuf>,        // the argument naming a configuration file, if any
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,            // None while the job runs
+    pub state: JobState,
+    pub log_path: Option<PathBuf>,                  // written once the job's output has closed
+}
+
+impl JobRecord {
+    pub fn from_manifest(manifest: &RunManifest) -> Self {
+        return JobRecord {
+            job_id: manifest.job_id,
+            container_path: manifest.container_path.clone(),
+            container_digest: manifest.container_digest.clone(),
+            app: manifest.app.clone(),
+            arguments: manifest.arguments.clone(),
+            run_profile: manifest.run_profile.clone(),
+            working_directory: manifest.working_directory.clone(),
+            configuration_path: configuration_argument(&manifest.arguments, &manifest.working_directory),
+            start_time: manifest.start_time,
+            end_time: None,
+            state: JobState::Running,
+            log_path: None,
+        };
+    }
+
+    // case-insensitive, on everything shown in the history
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() { return true; }
+
+        let searched = [
+            self.container_path.to_string_lossy().to_string(),
+            self.app.clone().unwrap_or_else(|| "main".to_string()),
+            self.arguments.join(" "),
+            self.working_directory.to_string_lossy().to_string(),
+            format!("{:?}", &self.state),
+            self.start_time.format("%Y-%m-%d %H:%M").to_string(),
+        ];
+        return searched.iter().any(|text| text.to_lowercase().contains(&query));
+    }
+
+    // self-configurator runs are tied to the ports and session token of the launcher that started them
+    pub fn can_be_repeated(&self) -> bool {
+        return !self.arguments.iter().any(|arg| arg == COLONY_INTEROP_ARGUMENT);
+    }
+}
+
+// containers get their configuration as a file argument, in the notation of the container host;
+// relative arguments are relative to the directory the container was run in
+fn configuration_argument(arguments: &[String], working_directory: &Path) -> Option<PathBuf> {
+    return arguments.iter()
+        .flat_map(|arg| [PathBuf::from(arg), PathBuf::from(unwslify_wsl_linux_path(arg))])
+        .map(|path| working_directory.join(path))
+        .find(|path| path.is_file());
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct JobHistory {
+    pub jobs: Vec<JobRecord>,       // in the order they were started
+    #[serde(skip)]
+    file: JsonFile,
+}
+
+pub fn job_history_path() -> Option<PathBuf> {
+    return launcher_file_path("colony_job_history.json");
+}
+
+impl JobHistory {
+    pub fn from_file(path: &Option<PathBuf>) -> Self {
+        let mut file = JsonFile::new(path.clone(), "job history");
+        let history: JobHistory = file.read();
+        let mut history = JobHistory { file, ..history };
+        history.orphan_unfinished();
+        return history;
+    }
+
+    pub fn write_to_file(&self) -> Result<(), String> {
+        return self.file.write(self);
+    }
+
+    pub fn record(&mut self, record: JobRecord) {
+        self.jobs.retain(|known| known.job_id != record.job_id);
+        self.jobs.push(record);
+    }
+
+    pub fn get(&self, job_id: &JobId) -> Option<&JobRecord> {
+        return self.jobs.iter().find(|record| &record.job_id == job_id);
+    }
+
+    // the first outcome is kept, e.g. a cancellation over the exit status of the killed process;
+    // false if nothing changed
+    pub fn finish(&mut self, job_id: &JobId, state: JobState) -> bool {
+        match self.jobs.iter_mut().find(|record| &record.job_id == job_id) {
+            Some(record) if record.end_time.is_none() => {
+                record.end_time = Some(Utc::now());
+                record.state = state;
+                return true;
+            },
+            _ => return false,
+        }
+    }
+
+    pub fn set_log_path(&mut self, job_id: &JobId, log_path: PathBuf) -> bool {
+        match self.jobs.iter_mut().find(|record| &record.job_id == job_id) {
+            Some(record) => {
+                record.log_path = Some(log_path);
+                return true;
+            },
+            None => return false,
+        }
+    }
+
+    // newest first
+    pub fn search(&self, query: &str) -> Vec<JobRecord> {
+        return self.jobs.iter().rev().filter(|record| record.matches(query)).cloned().collect();
+    }
+
+    // jobs that were running when the launcher closed can no longer be observed
+    fn orphan_unfinished(&mut self) {
+        for record in self.jobs.iter_mut().filter(|record| record.end_time.is_none()) {
+            record.state = JobState::Orphaned;
+        }
+    }
+}
+
+// written next to the run manifest, see RunManifest::file_name
+pub fn write_job_log(directory: &Path, job_id: &JobId, lines: &[JobOutputLine]) -> Result<PathBuf, String> {
+    let path = directory.join(format!("colony_run_{}.log", job_id.id));
+    let file = File::create(&path).map_err(|e| format!("Error creating job log at {:?}: {:?}", &path, &e))?;
+
+    let mut writer = BufWriter::new(file);
+    for line in lines.iter() {
+        writeln!(writer, "{}", line.log_line()).map_err(|e| format!("Error writing job log to {:?}: {:?}", &path, &e))?;
+    }
+    writer.flush().map_err(|e| format!("Error writing job log to {:?}: {:?}", &path, &e))?;
+    return Ok(path);
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_survives_a_restart() {
+        let job_id = JobId::new();
+        let manifest = RunManifest::new(job_id, Path::new("/data/run"), Path::new("/containers/rnaseq.sif"),
+                                        Some("align".to_string()), vec!["--threads".to_string(), "8".to_string()], RunProfile::default());
+        let finished_id = JobId::new();
+        let finished = RunManifest::new(finished_id, Path::new("/data/other"), Path::new("/containers/qc.sif"), None, Vec::new(), RunProfile::default());
+
+        let path = std::env::temp_dir().join(format!("colony_job_history_test_{}.json", uuid::Uuid::new_v4()));
+        let mut history = JobHistory::from_file(&Some(path.clone()));
+        history.record(JobRecord::from_manifest(&manifest));
+        history.record(JobRecord::from_manifest(&finished));
+        assert!(history.finish(&finished_id, JobState::Cancelled));
+        assert!(!history.finish(&finished_id, JobState::Succeeded));
+
+        history.write_to_file().unwrap();
+        let restored = JobHistory::from_file(&Some(path.clone()));
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(restored.get(&job_id).unwrap().state, JobState::Orphaned);
+        assert_eq!(restored.get(&finished_id).unwrap().state, JobState::Cancelled);
+        assert_eq!(restored.search("").iter().map(|record| record.job_id).collect::<Vec<_>>(), vec![finished_id, job_id]);
+        assert_eq!(restored.search("ALIGN").len(), 1);
+        assert_eq!(restored.search("threads 8")[0].job_id, job_id);
+    }
+
+    #[test]
+    fn records_keep_what_a_rerun_needs() {
+        let workdir = std::env::temp_dir().join(format!("colony_job_history_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&workdir).unwrap();
+        std::fs::write(workdir.join("config.json"), "{}").unwrap();
+        let profile = RunProfile { clean_env: true, ..RunProfile::default() };
+
+        let manifest = RunManifest::new(JobId::new(), &workdir, Path::new("/containers/qc.sif"), None, vec!["config.json".to_string()], profile.clone());
+        let record = JobRecord::from_manifest(&manifest);
+        let interop = RunManifest::new(JobId::new(), &workdir, Path::new("/containers/qc.sif"), Some("self-configurator".to_string()),
+                                       vec![COLONY_INTEROP_ARGUMENT.to_string(), "--gui-port".to_string(), "9283".to_string()], RunProfile::default());
+        std::fs::remove_dir_all(&workdir).ok();
+
+        assert_eq!(record.configuration_path, Some(workdir.join("config.json")));
+        assert_eq!(record.run_profile, profile);
+        assert!(record.can_be_repeated());
+        assert!(!JobRecord::from_manifest(&interop).can_be_repeated());
+    }
+}
@@ -0,0 +1,230 @@
+
+use std::fmt::Debug;
+use std::io::{ErrorKind, Read, Write};
+use std::process::{Child, Command};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
+
+use crate::backend::JobId;
+use crate::backend::command_runner::CommandRunner;
+use crate::backend::jobs::{JobInput, JobOutputLine, JobState, OutputStream, SharedJobOutput};
+use crate::backend::process_tree::interrupt_job_processes;
+use crate::backend::child_processes::{read_terminal_output, spawn_job_output_readers};
+
+
+
+//################################################################################
+//## Job processes
+//## (a job either talks to the launcher through pipes, or is attached to a
+//## pseudo-terminal, so that progress bars and the like are drawn as on a console)
+//################################################################################
+
+// typical screen size, as for the output parsing in child_processes
+pub const DEFAULT_TERMINAL_SIZE: PtySize = PtySize { rows: 30, cols: 120, pixel_width: 0, pixel_height: 0 };
+
+#[derive(Debug)]
+pub enum JobProcess {
+    Piped(Child),
+    Terminal(TerminalProcess),
+}
+
+impl JobProcess {
+    pub fn try_wait(&mut self) -> std::io::Result<Option<JobState>> {
+        match self {
+            JobProcess::Piped(child) => {
+                return child.try_wait().map(|status| status.map(JobState::from_exit_status));
+            },
+            JobProcess::Terminal(process) => {
+                let status = process.child.try_wait()?;
+                if status.is_some() { process.close(); }
+                return Ok(status.map(|status| terminal_job_state(&status)));
+            },
+        }
+    }
+
+    pub fn kill(&mut self) -> std::io::Result<()> {
+        match self {
+            JobProcess::Piped(child) => return child.kill(),
+            JobProcess::Terminal(process) => return process.child.kill(),
+        }
+    }
+
+    // the runner is the one the job was started by, pipes carry no control characters and Ctrl-C is sent as a signal through it
+    pub fn send_input(&mut self, input: &JobInput, runner: &dyn CommandRunner, job_id: &JobId) -> std::io::Result<()> {
+        match self {
+            JobProcess::Piped(child) => {
+                match input {
+                    JobInput::Line(line) => {
+                        let Some(stdin) = child.stdin.as_mut() else { return Err(std::io::Error::new(ErrorKind::BrokenPipe, "stdin of the job has been closed")); };
+                        stdin.write_all(format!("{}\n", line).as_bytes())?;
+                        return stdin.flush();
+                    },
+                    JobInput::Interrupt => return interrupt_job_processes(runner, job_id).map_err(std::io::Error::other),
+                    JobInput::EndOfInput => {
+                        child.stdin.take();
+                        return Ok(());
+                    },
+                    JobInput::Keys(_) => return Err(std::io::Error::new(ErrorKind::Unsupported, "keys can only be typed into a terminal")),
+                }
+            },
+            JobProcess::Terminal(process) => {
+                let Some(writer) = process.writer.as_mut() else { return Err(std::io::Error::new(ErrorKind::BrokenPipe, "the terminal of the job has been closed")); };
+                // typed as on a keyboard, the terminal turns control characters into signals and end of input
+                let bytes = match input {
+                    JobInput::Line(line) => format!("{}\r", line).into_bytes(),
+                    JobInput::Interrupt => vec![0x03],
+                    JobInput::EndOfInput => vec![0x04],
+                    JobInput::Keys(keys) => keys.clone().into_bytes(),
+                };
+                writer.write_all(&bytes)?;
+                return writer.flush();
+            },
+        }
+    }
+
+    // marked as input in the output and the log; terminal output keeps it below the row it was typed in
+    pub fn echo_input(output: &SharedJobOutput, text: &str) {
+        if let Ok(mut lines) = output.lock() {
+            lines.push(JobOutputLine::new(OutputStream::Input, text));
+        }
+    }
+
+    // the readers run until the job's output has closed, join them to know the output is complete
+    pub fn spawn_output_readers(&mut self, output: &SharedJobOutput) -> Vec<JoinHandle<()>> {
+        match self {
+            JobProcess::Piped(child) => return spawn_job_output_readers(output, child),
+            JobProcess::Terminal(process) => {
+                let Some(reader) = process.reader.take() else { return Vec::new(); };
+                let output = Arc::clone(output);
+                let size = process.size;
+                return vec![std::thread::spawn(move || read_terminal_output(&output, reader, size.rows, size.cols))];
+            },
+        }
+    }
+}
+
+// a pseudo-terminal only reports the exit code, signals show up as failures
+fn terminal_job_state(status: &portable_pty::ExitStatus) -> JobState {
+    if status.success() { return JobState::Succeeded; }
+    return JobState::Failed { exit_code: Some(status.exit_code() as i32), signal: None };
+}
+
+pub struct TerminalProcess {
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+    master: Option<Box<dyn MasterPty + Send>>,      // None once the job has exited
+    reader: Option<Box<dyn Read + Send>>,           // handed to the output reader
+    writer: Option<Box<dyn Write + Send>>,          // what is typed into the terminal
+    size: PtySize,
+}
+
+impl TerminalProcess {
+    // takes over program, arguments, environment and working directory, so runners stay the same for both kinds of jobs
+    pub fn spawn(command: &Command, size: PtySize) -> std::io::Result<Self> {
+        let pty = native_pty_system().openpty(size).map_err(pty_error)?;
+
+        let mut builder = CommandBuilder::new(command.get_program());
+        builder.args(command.get_args());
+        for (key, value) in command.get_envs() {
+            match value {
+                Some(value) => builder.env(key, value),
+                None => builder.env_remove(key),
+            }
+        }
+        if let Some(directory) = command.get_current_dir() {
+            builder.cwd(directory);
+        }
+
+        let child = pty.slave.spawn_command(builder).map_err(pty_error)?;
+        // otherwise the terminal stays open after the job has exited and its output never ends
+        drop(pty.slave);
+
+        let reader = pty.master.try_clone_reader().map_err(pty_error)?;
+        let writer = pty.master.take_writer().map_err(pty_error)?;
+        return Ok(TerminalProcess { child, master: Some(pty.master), reader: Some(reader), writer: Some(writer), size });
+    }
+
+    // on windows, the output of a pseudo console only ends once the console is closed
+    fn close(&mut self) {
+        self.writer.take();
+        self.master.take();
+    }
+}
+
+impl Debug for TerminalProcess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return f.debug_struct("TerminalProcess")
+                .field("child", &self.child)
+                .field("rows", &self.size.rows)
+                .field("cols", &self.size.cols)
+                .finish();
+    }
+}
+
+fn pty_error<E: std::fmt::Display>(e: E) -> std::io::Error {
+    return std::io::Error::other(format!("Pseudo-terminal error: {}", e));
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use crate::backend::command_runner::{CommandRunner, CommandSpec, NativeCommandRunner};
+
+    #[test]
+    fn terminal_jobs_see_a_terminal_and_report_their_exit() {
+        let spec = CommandSpec::new("sh").args(["-c", "test -t 1 && printf 'on a terminal\\r\\n'; printf 'progress 50%%\\rprogress 100%%\\r\\n'; \
+                                                   printf 'Continue? '; read answer; printf 'got %s\\r\\n' \"$answer\"; exit 3"]);
+        let mut process = JobProcess::Terminal(NativeCommandRunner.spawn_in_terminal(&spec, DEFAULT_TERMINAL_SIZE).unwrap());
+        let output: SharedJobOutput = Arc::new(Mutex::new(Vec::new()));
+
+        let readers = process.spawn_output_readers(&output);
+        while !output.lock().unwrap().iter().any(|line| line.text.starts_with("Continue?")) {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+        process.send_input(&JobInput::Line("yes".to_string()), &NativeCommandRunner, &JobId::new()).unwrap();
+        JobProcess::echo_input(&output, "yes");
+
+        let state = loop {
+            if let Some(state) = process.try_wait().unwrap() { break state; }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+        readers.into_iter().for_each(|reader| { reader.join().ok(); });
+
+        assert_eq!(state, JobState::Failed { exit_code: Some(3), signal: None });
+        let lines = output.lock().unwrap().clone();
+        assert!(lines.iter().all(|line| line.complete));
+        assert_eq!(lines.iter().map(|line| (line.stream.tag(), line.text.as_str())).collect::<Vec<_>>(), vec![
+            ("terminal", "on a terminal"),
+            ("terminal", "progress 100%"),
+            ("terminal", "Continue? yes"),
+            ("stdin", "yes"),
+            ("terminal", "got yes"),
+        ]);
+    }
+
+    // the processes of a job are found through /proc
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn piped_jobs_are_interrupted_through_the_runner() {
+        let job_id = JobId::new();
+        let spec = CommandSpec::new("sleep").arg("60").env(crate::backend::process_tree::JOB_MARKER_VARIABLE, &job_id.id.to_string());
+        let mut process = JobProcess::Piped(NativeCommandRunner.spawn(&spec).unwrap());
+
+        process.send_input(&JobInput::Interrupt, &NativeCommandRunner, &job_id).unwrap();
+        let state = loop {
+            if let Some(state) = process.try_wait().unwrap() { break state; }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        };
+
+        assert_eq!(state, JobState::Failed { exit_code: None, signal: Some(2) });
+        assert!(process.send_input(&JobInput::Interrupt, &NativeCommandRunner, &job_id).is_err());
+    }
+}
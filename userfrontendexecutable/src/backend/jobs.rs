@@ -1,48 +1,86 @@
+// JobId and JobState are shared with the central server and its plugins
+pub use plugin_interface_elements::elements_v1::{JobId, JobState};
 
+use std::sync::{Arc, Mutex};
 
-use std::time::SystemTime;
-use uuid::Uuid;
-use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct JobId {
-    pub order_time: UnixTime,
-    pub uuidv4: Uuid,
-}
 
-impl JobId {
-    pub fn new() -> Self {
-        return Self { order_time: UnixTime::now(), uuidv4: Uuid::new_v4() };
-    }
+
+//################################################################################
+//## Job output
+//## (stdout and stderr of a job end up in one list, in the order they arrived)
+//################################################################################
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+    Terminal,       // rendered rows of a pseudo-terminal, which mixes stdout and stderr
+    Input,          // what the user sent to the job
 }
 
-impl Default for JobId {
-    fn default() -> Self { return Self::new(); }
+impl OutputStream {
+    pub fn tag(&self) -> &'static str {
+        return match self {
+            OutputStream::Stdout => "stdout",
+            OutputStream::Stderr => "stderr",
+            OutputStream::Terminal => "terminal",
+            OutputStream::Input => "stdin",
+        };
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct UnixTime {
-    pub seconds: u64
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct JobOutputLine {
+    pub stream: OutputStream,
+    pub time: DateTime<Utc>,        // when the line was started
+    pub text: String,
+    pub complete: bool,             // false while the line may still change, e.g. a progress bar
 }
 
-impl UnixTime {
-    pub fn from(t: SystemTime) -> UnixTime {
-        return UnixTime { seconds: t.duration_since(SystemTime::UNIX_EPOCH).expect("System Time appears to be before 1970").as_secs() };
+impl JobOutputLine {
+    pub fn new(stream: OutputStream, text: &str) -> Self {
+        return JobOutputLine { stream, time: Utc::now(), text: text.to_string(), complete: true };
     }
 
-    pub fn now() -> UnixTime {
-        return UnixTime::from(SystemTime::now());
+    // as written to log files
+    pub fn log_line(&self) -> String {
+        return format!("{} [{}] {}", self.time.format("%Y-%m-%dT%H:%M:%S%.3fZ"), self.stream.tag(), &self.text);
     }
 }
 
+pub type SharedJobOutput = Arc<Mutex<Vec<JobOutputLine>>>;
 
+// lines before the first incomplete one will not change anymore
+pub fn first_open_line(lines: &[JobOutputLine]) -> usize {
+    return lines.iter().position(|line| !line.complete).unwrap_or(lines.len());
+}
 
 
 
+//################################################################################
+//## Job input
+//## (sent by the user to a running job, e.g. to answer a confirmation prompt)
+//################################################################################
 
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum JobInput {
+    Line(String),       // the line break is added when sending
+    Interrupt,          // Ctrl-C
+    EndOfInput,         // Ctrl-D, closes stdin of jobs that are not attached to a terminal
+    Keys(String),       // typed into a terminal as they are, including escape sequences
+}
 
-
-
-
-
-
+impl JobInput {
+    // as shown in the job output, keys are echoed by the terminal itself
+    pub fn echo(&self) -> Option<String> {
+        return match self {
+            JobInput::Line(line) => Some(line.clone()),
+            JobInput::Interrupt => Some("^C".to_string()),
+            JobInput::EndOfInput => Some("^D".to_string()),
+            JobInput::Keys(_) => None,
+        };
+    }
+}
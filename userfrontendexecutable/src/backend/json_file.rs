@@ -0,0 +1,106 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::backend::persistent_state::exe_dir;
+
+
+
+//################################################################################
+//## JSON files
+//## (the stores of the launcher, like the chat history, are kept next to it and
+//## rewritten on every change; a file that cannot be read is set aside instead
+//## of being overwritten, so nothing stored in it is lost)
+//################################################################################
+
+// None if the launcher does not know where it is
+pub fn launcher_file_path(file_name: &str) -> Option<PathBuf> {
+    return exe_dir().as_ref().map(|dir| dir.join(file_name));
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct JsonFile {
+    path: Option<PathBuf>,      // None keeps the content in memory only
+    description: String,        // e.g. "chat history", for messages
+}
+
+impl JsonFile {
+    pub fn new(path: Option<PathBuf>, description: &str) -> Self {
+        return JsonFile { path, description: description.to_string() };
+    }
+
+    // a missing file is an empty store; if an unreadable file cannot be set aside either,
+    // the content is kept in memory only, since writing would overwrite the file
+    pub fn read<T: DeserializeOwned + Default>(&mut self) -> T {
+        let Some(path) = self.path.clone() else { return T::default(); };
+        if !path.exists() { return T::default(); }
+
+        let content = fs::read_to_string(&path).map_err(|e| e.to_string())
+                        .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()));
+        match content {
+            Ok(content) => return content,
+            Err(e) => {
+                let unreadable_path = unreadable_file_path(&path);
+                match fs::rename(&path, &unreadable_path) {
+                    Ok(()) => {
+                        println!("Could not read {} at {:?}: {}. It was moved to {:?}, starting anew.", &self.description, &path, e, &unreadable_path);
+                    },
+                    Err(rename_error) => {
+                        println!("Could not read {} at {:?}: {}, nor move it aside: {}. Changes are kept in memory only.", &self.description, &path, e, rename_error);
+                        self.path = None;
+                    }
+                }
+                return T::default();
+            }
+        }
+    }
+
+    pub fn write<T: Serialize>(&self, content: &T) -> Result<(), String> {
+        let Some(path) = &self.path else { return Err(format!("No location to store the {} at", &self.description)); };
+
+        let json = serde_json::to_string_pretty(content).map_err(|e| format!("{:?}", &e))?;
+        fs::write(path, json).map_err(|e| format!("Error writing {} to {:?}: {:?}", &self.description, path, &e))?;
+        return Ok(());
+    }
+}
+
+// e.g. colony_chat_channels.unreadable-20250101120000.json
+fn unreadable_file_path(path: &Path) -> PathBuf {
+    return path.with_extension(format!("unreadable-{}.json", Utc::now().format("%Y%m%d%H%M%S")));
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_files_are_set_aside() {
+        let directory = std::env::temp_dir().join(format!("colony_json_file_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("store.json");
+
+        let mut file = JsonFile::new(Some(path.clone()), "test store");
+        assert_eq!(file.read::<Vec<String>>(), Vec::<String>::new());
+        file.write(&vec!["kept".to_string()]).unwrap();
+        assert_eq!(file.read::<Vec<String>>(), vec!["kept".to_string()]);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert_eq!(file.read::<Vec<String>>(), Vec::<String>::new());
+        let set_aside = fs::read_dir(&directory).unwrap().filter_map(|entry| entry.ok()).map(|entry| entry.path())
+                           .filter(|entry| entry != &path).collect::<Vec<_>>();
+        assert_eq!(set_aside.len(), 1);
+        assert_eq!(fs::read_to_string(&set_aside[0]).unwrap(), "{ not json");
+        fs::remove_dir_all(&directory).ok();
+
+        assert!(JsonFile::default().write(&Vec::<String>::new()).is_err());
+    }
+}
@@ -14,6 +14,21 @@ pub use backend_runtime::*;
 pub mod jobs;
 pub use jobs::*;
 
+pub mod chat_channels;
+pub use chat_channels::*;
+
+pub mod configuration_store;
+pub use configuration_store::*;
+
+pub mod configuration_schema;
+pub use configuration_schema::*;
+
+pub mod configuration_form;
+pub use configuration_form::*;
+
+pub mod json_file;
+pub use json_file::*;
+
 // mod labbook_data;
 // pub use labbook_data::*;
 
@@ -28,9 +43,39 @@ pub use utils::*;
 
 
 
+pub mod command_runner;
+pub use command_runner::*;
+
+pub mod container_runtime;
+pub use container_runtime::*;
+
+pub mod container_inspection;
+pub use container_inspection::*;
+
+pub mod sif;
+pub use sif::*;
+
+pub mod container_identity;
+pub use container_identity::*;
+
+pub mod run_profile;
+pub use run_profile::*;
+
+pub mod run_manifest;
+pub use run_manifest::*;
+
+pub mod job_history;
+pub use job_history::*;
+
 mod child_processes;
 pub use child_processes::*;
 
+pub mod job_process;
+pub use job_process::*;
+
+pub mod process_tree;
+pub use process_tree::*;
+
 mod wsl_setup;
 pub use wsl_setup::*;
 
@@ -1,5 +1,6 @@
 
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use std::env;
@@ -11,6 +12,11 @@ use std::io::BufReader;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::backend::container_runtime::RuntimePreference;
+use crate::backend::container_identity::{ContainerDigest, ContainerIdentity};
+use crate::backend::run_profile;
+use crate::backend::run_profile::RunProfile;
+
 
 
 
@@ -27,6 +33,10 @@ pub enum PersistentStateUpdate {
     RemoveContainer(Uuid),
     // last_selected_container_dir
     SetlastSelectedContainerDir(Option<PathBuf>),
+    // container runtime
+    SetContainerRuntime(RuntimePreference),
+    // run profiles: container, app (None for the container itself), profile (None lets an app use the container's)
+    SetRunProfile(Uuid, Option<String>, Option<RunProfile>),
     // protocols
     //AddRecentProtocol(PathBuf),
 //    DeleteProtocol(PathBuf),
@@ -38,6 +48,12 @@ pub enum PersistentStateUpdate {
 pub struct BackendContainerDescription {
     pub id: Uuid,
     pub path: PathBuf,
+    #[serde(default)]
+    pub identity: Option<ContainerIdentity>,    // cached content digest, None until hashed
+    #[serde(default)]
+    pub run_profile: RunProfile,
+    #[serde(default)]
+    pub app_run_profiles: BTreeMap<String, RunProfile>,  // only apps that deviate from the container's profile
 }
 
 impl PartialOrd for BackendContainerDescription {
@@ -54,8 +70,34 @@ impl Ord for BackendContainerDescription {
 
 impl BackendContainerDescription {
     pub fn from_path(pth: PathBuf) -> Self {
-        return BackendContainerDescription { id: Uuid::new_v4(), path: pth };
+        return BackendContainerDescription { id: Uuid::new_v4(), path: pth, identity: None, run_profile: RunProfile::default(), app_run_profiles: BTreeMap::new() };
+    }
+
+    pub fn digest(&self) -> Option<&ContainerDigest> {
+        return self.identity.as_ref().map(|identity| &identity.digest);
+    }
+
+    pub fn run_profile_for(&self, app: Option<&str>) -> &RunProfile {
+        return app.and_then(|app| self.app_run_profiles.get(app)).unwrap_or(&self.run_profile);
     }
+
+    pub fn set_run_profile(&mut self, app: Option<String>, profile: Option<RunProfile>) {
+        run_profile::set_run_profile(&mut self.run_profile, &mut self.app_run_profiles, app, profile);
+    }
+
+    // hashing is only repeated if the file changed since
+    pub fn needs_identification(&self) -> bool {
+        return self.path.is_file() && !self.identity.as_ref().is_some_and(|identity| identity.is_current(&self.path));
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum IdentificationOutcome {
+    Identified(BackendContainerDescription),
+    // the same image was registered before under a path that is gone (moved or renamed);
+    // the older entry is kept and takes over the new path, copies the user registered stay separate
+    Moved { removed: Uuid, kept: BackendContainerDescription },
+    UnknownContainer,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
@@ -64,7 +106,8 @@ pub struct PersistentStateDeserialzed {
     pub containersv2: Option<Vec<BackendContainerDescription>>,
     pub last_selected_container_dir: Option<PathBuf>,
     pub recent_protocols: Option<Vec<PathBuf>>,
-    pub archived_protocols: Option<Vec<PathBuf>>
+    pub archived_protocols: Option<Vec<PathBuf>>,
+    pub container_runtime: Option<RuntimePreference>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
@@ -73,7 +116,8 @@ pub struct PersistentState {
     pub containers: Vec<BackendContainerDescription>,
     pub last_selected_container_dir: Option<PathBuf>,
     pub recent_protocols: Vec<PathBuf>,
-    pub archived_protocols: Vec<PathBuf>
+    pub archived_protocols: Vec<PathBuf>,
+    pub container_runtime: RuntimePreference,
 }
 
 impl From<PersistentStateDeserialzed> for PersistentState {
@@ -100,12 +144,42 @@ impl From<PersistentStateDeserialzed> for PersistentState {
             last_selected_container_dir: other.last_selected_container_dir,
             recent_protocols: other.recent_protocols.unwrap_or_default(),
             archived_protocols: other.archived_protocols.unwrap_or_default(),
+            container_runtime: other.container_runtime.unwrap_or_default(),
         }
     }
 }
 
 
 impl PersistentState {
+    // containers that are not registered run with the defaults
+    pub fn run_profile_for(&self, container_path: &PathBuf, app: Option<&str>) -> RunProfile {
+        return self.containers.iter()
+                    .find(|bcd| &bcd.path == container_path)
+                    .map(|bcd| bcd.run_profile_for(app).clone())
+                    .unwrap_or_default();
+    }
+
+    pub fn apply_identity(&mut self, container_id: Uuid, identity: ContainerIdentity) -> IdentificationOutcome {
+        let Some(position) = self.containers.iter().position(|bcd| bcd.id == container_id) else {
+            return IdentificationOutcome::UnknownContainer;
+        };
+        self.containers[position].identity = Some(identity.clone());
+
+        let moved_from = self.containers.iter().position(|bcd| bcd.id != container_id && bcd.digest() == Some(&identity.digest) && !bcd.path.is_file());
+        return match moved_from {
+            None => IdentificationOutcome::Identified(self.containers[position].clone()),
+            Some(earlier) => {
+                let moved = self.containers.remove(position);
+                let earlier = if earlier > position { earlier - 1 } else { earlier };
+                let kept = &mut self.containers[earlier];
+                println!("Container {:?} has moved to {:?}", &kept.path, &moved.path);
+                kept.path = moved.path;
+                kept.identity = Some(identity);
+                IdentificationOutcome::Moved { removed: moved.id, kept: kept.clone() }
+            }
+        };
+    }
+
     pub fn from_file(path: &Option<PathBuf>) -> Self {
         return match path {
             Some(pathstr) => {
@@ -123,7 +197,8 @@ impl PersistentState {
                             containers: Vec::new() as Vec<BackendContainerDescription>,
                             last_selected_container_dir: None,
                             recent_protocols: Vec::new(),
-                            archived_protocols: Vec::new()
+                            archived_protocols: Vec::new(),
+                            container_runtime: RuntimePreference::default(),
                         }
                     }
                 }
@@ -133,7 +208,8 @@ impl PersistentState {
                     containers: Vec::new() as Vec<BackendContainerDescription>,
                     last_selected_container_dir: None,
                     recent_protocols: Vec::new(),
-                    archived_protocols: Vec::new()
+                    archived_protocols: Vec::new(),
+                    container_runtime: RuntimePreference::default(),
                 }
             }
         };
@@ -203,18 +279,43 @@ pub fn config_path() -> &'static Option<PathBuf> {
 
 
 
+//################################################################################
+//## Tests
+//################################################################################
 
-
-
-
-
-
-
-
-
-
-
-
-
-
-
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::container_identity::identify_container;
+
+    #[test]
+    fn only_moved_images_are_merged() {
+        let directory = std::env::temp_dir().join(format!("colony_persistent_state_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&directory).unwrap();
+        let [original, copy, moved] = ["tool.sif", "tool_copy.sif", "tool_moved.sif"].map(|name| directory.join(name));
+        fs::write(&original, "same image").unwrap();
+        fs::write(&copy, "same image").unwrap();
+
+        let mut state = PersistentState::from_file(&None);
+        let [original_entry, copy_entry, moved_entry] = [&original, &copy, &moved].map(|path| BackendContainerDescription::from_path(path.clone()));
+        state.containers = vec![original_entry.clone(), copy_entry.clone(), moved_entry.clone()];
+
+        assert!(matches!(state.apply_identity(original_entry.id, identify_container(&original).unwrap()), IdentificationOutcome::Identified(_)));
+        // a copy the user registered is kept, with the same digest
+        assert!(matches!(state.apply_identity(copy_entry.id, identify_container(&copy).unwrap()), IdentificationOutcome::Identified(_)));
+        assert_eq!(state.containers.len(), 3);
+
+        fs::rename(&original, &moved).unwrap();
+        let outcome = state.apply_identity(moved_entry.id, identify_container(&moved).unwrap());
+        fs::remove_dir_all(&directory).ok();
+
+        match outcome {
+            IdentificationOutcome::Moved { removed, kept } => {
+                assert_eq!(removed, moved_entry.id);
+                assert_eq!((kept.id, kept.path), (original_entry.id, moved.clone()));
+            },
+            outcome => panic!("expected the original entry to take over the new path, got {:?}", outcome),
+        }
+        assert_eq!(state.containers.iter().map(|bcd| bcd.id).collect::<Vec<_>>(), vec![original_entry.id, copy_entry.id]);
+    }
+}
@@ -0,0 +1,120 @@
+
+use std::time::{Duration, Instant};
+
+use itertools::Itertools;
+
+use crate::backend::JobId;
+use crate::backend::command_runner::{CommandRunner, CommandSpec};
+use crate::backend::container_runtime::ContainerEngine;
+
+
+
+//################################################################################
+//## Process trees
+//## (the process the launcher started is only a wrapper, e.g. wsl.exe; every process
+//## of a job carries a marker variable instead, so all of them can be found on the
+//## container host and terminated, whatever they were started by)
+//################################################################################
+
+pub const JOB_MARKER_VARIABLE: &str = "COLONY_JOB_ID";
+
+// how long a job may take to shut down after SIGTERM, before it is killed
+pub const TERMINATION_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// also passed into the container, so it survives a clean environment
+pub fn mark_job_processes(engine: &ContainerEngine, spec: CommandSpec, job_id: &JobId) -> CommandSpec {
+    let marker = job_id.id.to_string();
+    let spec = spec.env(JOB_MARKER_VARIABLE, &marker);
+    return engine.container_env(spec, JOB_MARKER_VARIABLE, &marker);
+}
+
+// processes whose environment contains the job's marker, as process ids of the container host
+pub fn job_process_ids(runner: &dyn CommandRunner, job_id: &JobId) -> Result<Vec<u32>, String> {
+    let script = r#"for p in /proc/[0-9]*; do grep -qzx "$1" "$p/environ" 2>/dev/null && echo "${p#/proc/}"; done; exit 0"#;
+    let spec = CommandSpec::new("sh").args(["-c", script, "sh", &format!("{}={}", JOB_MARKER_VARIABLE, job_id.id)]);
+
+    let output = runner.output(&spec).map_err(|e| format!("Could not list the processes of job {}: {:?}", job_id.id, e))?;
+    if !output.status.success() {
+        return Err(format!("Could not list the processes of job {}: {}", job_id.id, String::from_utf8_lossy(&output.stderr)));
+    }
+    return Ok(String::from_utf8_lossy(&output.stdout).lines().filter_map(|line| line.trim().parse().ok()).collect());
+}
+
+fn signal_processes(runner: &dyn CommandRunner, signal: &str, process_ids: &[u32]) {
+    let spec = CommandSpec::new("kill").arg(signal).args(process_ids.iter().map(|pid| pid.to_string()));
+    // processes that exit in the meantime make kill fail, which is fine
+    runner.output(&spec).ok();
+}
+
+// polls until no process of the job is left, returns the ones still running after the timeout
+fn wait_for_job_processes(runner: &dyn CommandRunner, job_id: &JobId, timeout: Duration) -> Result<Vec<u32>, String> {
+    let start = Instant::now();
+    loop {
+        let remaining = job_process_ids(runner, job_id)?;
+        if remaining.is_empty() || start.elapsed() >= timeout { return Ok(remaining); }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+// what Ctrl-C in a terminal does, the signal reaches every process of the job
+pub fn interrupt_job_processes(runner: &dyn CommandRunner, job_id: &JobId) -> Result<(), String> {
+    let process_ids = job_process_ids(runner, job_id)?;
+    if process_ids.is_empty() { return Err(format!("No process of job {} is running", job_id.id)); }
+
+    println!("Interrupting processes {:?} of job {}", &process_ids, job_id.id);
+    signal_processes(runner, "-INT", &process_ids);
+    return Ok(());
+}
+
+// SIGTERM first, SIGKILL for whatever is left after the grace period; Ok only once no process of the job is left
+pub fn terminate_job_processes(runner: &dyn CommandRunner, job_id: &JobId, grace_period: Duration) -> Result<(), String> {
+    let process_ids = job_process_ids(runner, job_id)?;
+    if process_ids.is_empty() { return Ok(()); }
+
+    println!("Terminating processes {:?} of job {}", &process_ids, job_id.id);
+    signal_processes(runner, "-TERM", &process_ids);
+    let remaining = wait_for_job_processes(runner, job_id, grace_period)?;
+    if remaining.is_empty() { return Ok(()); }
+
+    println!("Killing processes {:?} of job {}, they did not exit within {:?}", &remaining, job_id.id, grace_period);
+    signal_processes(runner, "-KILL", &remaining);
+    let remaining = wait_for_job_processes(runner, job_id, Duration::from_secs(2))?;
+    if remaining.is_empty() { return Ok(()); }
+
+    return Err(format!("Processes {} of job {} are still running", remaining.iter().join(", "), job_id.id));
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::backend::command_runner::NativeCommandRunner;
+    use crate::backend::container_runtime::{ContainerRuntime, RuntimePreference};
+
+    #[test]
+    fn whole_tree_is_terminated() {
+        let engine = ContainerEngine::assume(Arc::new(NativeCommandRunner), &RuntimePreference::Fixed(ContainerRuntime::Apptainer));
+        let job_id = JobId::new();
+        // the child ignores SIGTERM and has to be killed, its parent does not wait for it
+        let spec = mark_job_processes(&engine, CommandSpec::new("sh").args(["-c", "sh -c 'trap \"\" TERM; sleep 60' & sleep 60"]), &job_id);
+        let mut wrapper = engine.runner.spawn(&spec).unwrap();
+
+        let start = Instant::now();
+        while job_process_ids(&NativeCommandRunner, &job_id).unwrap().len() < 3 && start.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(job_process_ids(&NativeCommandRunner, &job_id).unwrap().len() >= 3);
+
+        terminate_job_processes(&NativeCommandRunner, &job_id, Duration::from_millis(500)).unwrap();
+        wrapper.wait().ok();
+
+        assert!(job_process_ids(&NativeCommandRunner, &job_id).unwrap().is_empty());
+        assert!(job_process_ids(&NativeCommandRunner, &JobId::new()).unwrap().is_empty());
+    }
+}
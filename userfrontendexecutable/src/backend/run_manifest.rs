@@ -0,0 +1,59 @@
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::JobId;
+use crate::backend::container_identity::ContainerDigest;
+use crate::backend::run_profile::RunProfile;
+
+
+
+//################################################################################
+//## Run manifests
+//## (written next to the results of every run, so an analysis can be traced
+//## back to the exact container image and arguments it was produced with)
+//################################################################################
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct RunManifest {
+    pub job_id: JobId,
+    pub container_path: PathBuf,
+    pub container_digest: Option<ContainerDigest>,  // None if the image had not been hashed yet
+    pub app: Option<String>,
+    pub arguments: Vec<String>,
+    pub run_profile: RunProfile,
+    pub working_directory: PathBuf,
+    pub runtime: Option<String>,                    // e.g. "apptainer version 1.3.4"
+    pub start_time: DateTime<Utc>,
+}
+
+impl RunManifest {
+    // digest and runtime are filled in by the backend, which knows them
+    pub fn new(job_id: JobId, working_directory: &Path, container_path: &Path, app: Option<String>, arguments: Vec<String>, run_profile: RunProfile) -> Self {
+        return RunManifest {
+            job_id,
+            container_path: container_path.to_path_buf(),
+            container_digest: None,
+            app,
+            arguments,
+            run_profile,
+            working_directory: working_directory.to_path_buf(),
+            runtime: None,
+            start_time: Utc::now(),
+        };
+    }
+
+    pub fn file_name(&self) -> String {
+        return format!("colony_run_{}.json", self.job_id.id);
+    }
+
+    pub fn write_to_dir(&self, directory: &Path) -> Result<PathBuf, String> {
+        let path = directory.join(self.file_name());
+        let json = serde_json::to_string_pretty(&self).map_err(|e| format!("{:?}", &e))?;
+        fs::write(&path, json).map_err(|e| format!("Error writing run manifest to {:?}: {:?}", &path, &e))?;
+        return Ok(path);
+    }
+}
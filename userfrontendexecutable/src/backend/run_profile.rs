@@ -0,0 +1,150 @@
+
+use std::collections::BTreeMap;
+use std::path::{Component, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::command_runner::CommandSpec;
+use crate::backend::container_runtime::ContainerEngine;
+
+
+
+//################################################################################
+//## Run profiles
+//## (how a container or one of its apps is started: mounts, environment and
+//## isolation flags; stored with the container in the persistent state)
+//################################################################################
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BindMount {
+    pub source: PathBuf,        // as seen by the launcher, translated for the container host
+    pub target: String,         // inside the container, empty for the same path
+    pub read_only: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RunProfile {
+    pub binds: Vec<BindMount>,
+    pub environment: Vec<(String, String)>,
+    pub clean_env: bool,                // --cleanenv: nothing from the host environment
+    pub contain_all: bool,              // --containall: own /home, /tmp, pid and ipc namespace
+    pub overlay: Option<PathBuf>,       // persistent overlay, otherwise changes go to a tmpfs
+    #[serde(default)]
+    pub terminal: bool,                 // attach to a pseudo-terminal, for progress displays
+}
+
+// what every run used before profiles existed
+impl Default for RunProfile {
+    fn default() -> Self {
+        return RunProfile {
+            binds: vec![BindMount { source: PathBuf::from("/mnt"), target: "/mnt".to_string(), read_only: false }],
+            environment: Vec::new(),
+            clean_env: false,
+            contain_all: false,
+            overlay: None,
+            terminal: false,
+        };
+    }
+}
+
+impl RunProfile {
+    // `--bind src:dst[:ro]` separates mounts by commas and fields by colons, so neither may appear in a path;
+    // a drive prefix of a Windows source is translated away before the runtime sees it
+    pub fn check(&self) -> Result<(), String> {
+        for bind in self.binds.iter().filter(|bind| !bind.source.as_os_str().is_empty()) {
+            let source_parts = bind.source.components().filter_map(|part| match part {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            });
+            let mut parts = source_parts.chain(std::iter::once(bind.target.clone()));
+            if parts.any(|part| part.contains(',') || part.contains(':')) {
+                return Err(format!("The mount {:?} -> {:?} contains ',' or ':', which the runtime cannot take", &bind.source, &bind.target));
+            }
+        }
+        return Ok(());
+    }
+
+    // the options go between the subcommand and the container path
+    pub fn apply(&self, engine: &ContainerEngine, mut spec: CommandSpec) -> CommandSpec {
+        for bind in self.binds.iter().filter(|bind| !bind.source.as_os_str().is_empty()) {
+            let source = engine.host_path(&bind.source);
+            let target = if bind.target.is_empty() { source.clone() } else { bind.target.clone() };
+            let mode = if bind.read_only { ":ro" } else { "" };
+            spec = spec.arg("--bind").arg(format!("{}:{}{}", source, target, mode));
+        }
+        if self.clean_env { spec = spec.arg("--cleanenv"); }
+        if self.contain_all { spec = spec.arg("--containall"); }
+        spec = match &self.overlay {
+            Some(overlay) => spec.arg("--overlay").arg(engine.host_path(overlay)),
+            None => spec.arg("--writable-tmpfs"),
+        };
+        // passed through the runtime's prefixed variables, which survive --cleanenv
+        for (name, value) in self.environment.iter().filter(|(name, _)| !name.is_empty()) {
+            spec = engine.container_env(spec, name, value);
+        }
+        return spec;
+    }
+}
+
+
+
+// an app without a profile of its own uses the container's, so saving None for it drops its profile
+pub fn set_run_profile(run_profile: &mut RunProfile, app_run_profiles: &mut BTreeMap<String, RunProfile>, app: Option<String>, profile: Option<RunProfile>) {
+    match (app, profile) {
+        (None, Some(profile)) => { *run_profile = profile; },
+        (None, None) => { *run_profile = RunProfile::default(); },
+        (Some(app), Some(profile)) => { app_run_profiles.insert(app, profile); },
+        (Some(app), None) => { app_run_profiles.remove(&app); },
+    }
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::backend::command_runner::NativeCommandRunner;
+    use crate::backend::container_runtime::{ContainerRuntime, RuntimePreference};
+
+    #[test]
+    fn profile_becomes_runtime_options() {
+        let engine = ContainerEngine::assume(Arc::new(NativeCommandRunner), &RuntimePreference::Fixed(ContainerRuntime::Apptainer));
+        let profile = RunProfile {
+            binds: vec![
+                BindMount { source: PathBuf::from("/data/reference genomes"), target: "/ref".to_string(), read_only: true },
+                BindMount { source: PathBuf::from("/scratch"), target: String::new(), read_only: false },
+            ],
+            environment: vec![("THREADS".to_string(), "8".to_string())],
+            clean_env: true,
+            contain_all: false,
+            overlay: None,
+            terminal: false,
+        };
+
+        let spec = profile.apply(&engine, engine.command().arg("run"));
+
+        assert_eq!(spec.args, vec!["run", "--bind", "/data/reference genomes:/ref:ro", "--bind", "/scratch:/scratch", "--cleanenv", "--writable-tmpfs"]);
+        assert_eq!(spec.env, vec![("APPTAINERENV_THREADS".to_string(), "8".to_string())]);
+        assert_eq!(RunProfile::default().apply(&engine, CommandSpec::new("apptainer")).args, vec!["--bind", "/mnt:/mnt", "--writable-tmpfs"]);
+    }
+
+    #[test]
+    fn mounts_the_runtime_would_misread_are_rejected() {
+        let with_bind = |source: &str, target: &str| RunProfile {
+            binds: vec![BindMount { source: PathBuf::from(source), target: target.to_string(), read_only: false }],
+            ..RunProfile::default()
+        };
+
+        assert!(with_bind("/data/reference genomes", "/ref").check().is_ok());
+        assert!(with_bind("/data", "").check().is_ok());
+        assert!(with_bind("/data/a,b", "/ref").check().is_err());
+        assert!(with_bind("/data/a:b", "/ref").check().is_err());
+        assert!(with_bind("/data", "/ref:ro").check().is_err());
+        assert!(with_bind("/data", "/ref,/other").check().is_err());
+    }
+}
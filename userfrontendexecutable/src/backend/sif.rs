@@ -0,0 +1,414 @@
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::backend::container_inspection::{ContainerInspection, string_map};
+
+
+
+//################################################################################
+//## SIF images
+//## (the Singularity Image Format starts with a fixed global header, followed by
+//## a table of descriptors pointing at the data objects: definition file, labels,
+//## metadata, filesystem partitions and signatures; all integers are little endian.
+//## The launcher shows what deffile, labels and inspect metadata tell; partitions
+//## and signatures are only read for callers of this module, nothing shows them yet)
+//################################################################################
+
+const SIF_MAGIC: &[u8] = b"SIF_MAGIC\0";
+const HEADER_SIZE: usize = 128;
+const DESCRIPTOR_SIZE: usize = 585;
+const GROUP_MASK: u32 = 0xf000_0000;
+const MAX_METADATA_SIZE: i64 = 16 << 20;         // partitions are never read, metadata is small
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SifDataType {
+    Deffile,
+    EnvVar,
+    Labels,
+    Partition,
+    Signature,
+    GenericJson,
+    Generic,
+    CryptoMessage,
+    Sbom,
+    OciRootIndex,
+    OciBlob,
+    Unknown(i32),
+}
+
+impl SifDataType {
+    fn from_raw(raw: i32) -> Self {
+        return match raw {
+            0x4001 => SifDataType::Deffile,
+            0x4002 => SifDataType::EnvVar,
+            0x4003 => SifDataType::Labels,
+            0x4004 => SifDataType::Partition,
+            0x4005 => SifDataType::Signature,
+            0x4006 => SifDataType::GenericJson,
+            0x4007 => SifDataType::Generic,
+            0x4008 => SifDataType::CryptoMessage,
+            0x4009 => SifDataType::Sbom,
+            0x400a => SifDataType::OciRootIndex,
+            0x400b => SifDataType::OciBlob,
+            other => SifDataType::Unknown(other),
+        };
+    }
+}
+
+// a link points either at a single object or at all objects of a group, e.g. a signature covering the whole image
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SifLink {
+    Object(u32),
+    Group(u32),
+}
+
+impl SifLink {
+    fn from_raw(raw: u32) -> Option<Self> {
+        return match raw {
+            0 => None,
+            raw if raw & GROUP_MASK != 0 => Some(SifLink::Group(raw & !GROUP_MASK)),
+            raw => Some(SifLink::Object(raw)),
+        };
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SifHeader {
+    pub version: String,
+    pub arch: String,
+    pub id: Uuid,
+    pub created_at: i64,            // unix time
+    pub modified_at: i64,
+    pub descriptors_free: i64,
+    pub descriptors_total: i64,
+    pub descriptors_offset: i64,
+    pub data_offset: i64,
+    pub data_size: i64,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SifPartition {
+    pub fs_type: String,            // e.g. "Squashfs"
+    pub part_type: String,          // e.g. "Primary system"
+    pub arch: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SifSignatureInfo {
+    pub hash_type: String,
+    pub fingerprint: String,        // hex of the signing key's fingerprint
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SifDescriptor {
+    pub data_type: SifDataType,
+    pub id: u32,
+    pub group_id: Option<u32>,
+    pub linked: Option<SifLink>,    // what this one refers to, e.g. the partition a signature covers
+    pub offset: i64,
+    pub size: i64,
+    pub created_at: i64,
+    pub name: String,
+    pub partition: Option<SifPartition>,
+    pub signature: Option<SifSignatureInfo>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SifImage {
+    pub header: SifHeader,
+    pub descriptors: Vec<SifDescriptor>,        // only the ones in use
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SifSignature {
+    pub descriptor_id: u32,
+    pub signed: SifLink,
+    pub info: SifSignatureInfo,
+    pub message: String,            // clear-signed PGP message
+}
+
+// everything `inspect` and `verify` would tell about an image, read without a container runtime
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SifMetadata {
+    pub image: SifImage,
+    pub deffile: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub inspection: Option<ContainerInspection>,    // full inspect metadata, if the image carries it
+    pub signatures: Vec<SifSignature>,
+}
+
+impl SifImage {
+    pub fn read<R: Read + Seek>(reader: &mut R) -> Result<Self, String> {
+        let mut raw_header = [0u8; HEADER_SIZE];
+        reader.seek(SeekFrom::Start(0)).and_then(|_| reader.read_exact(&mut raw_header))
+            .map_err(|e| format!("Could not read SIF header: {}", e))?;
+        let header = parse_header(&raw_header)?;
+
+        // the header is not trusted with the allocation, the descriptor table has to fit into the file
+        let file_length = reader.seek(SeekFrom::End(0)).map_err(|e| format!("Could not read SIF header: {}", e))?;
+        let offset = u64::try_from(header.descriptors_offset).map_err(|_| "Invalid SIF descriptor offset".to_string())?;
+        let table_size = usize::try_from(header.descriptors_total).ok()
+                            .and_then(|total| total.checked_mul(DESCRIPTOR_SIZE))
+                            .filter(|size| offset.checked_add(*size as u64).is_some_and(|end| end <= file_length))
+                            .ok_or_else(|| format!("Invalid SIF descriptor count {}", header.descriptors_total))?;
+        let mut raw_descriptors = vec![0u8; table_size];
+        reader.seek(SeekFrom::Start(offset)).and_then(|_| reader.read_exact(&mut raw_descriptors))
+            .map_err(|e| format!("Could not read SIF descriptors: {}", e))?;
+
+        let descriptors = raw_descriptors.chunks_exact(DESCRIPTOR_SIZE).filter_map(parse_descriptor).collect();
+        return Ok(SifImage { header, descriptors });
+    }
+
+    pub fn partitions(&self) -> impl Iterator<Item = &SifDescriptor> {
+        return self.descriptors.iter().filter(|descriptor| descriptor.data_type == SifDataType::Partition);
+    }
+
+    pub fn first_of(&self, data_type: SifDataType) -> Option<&SifDescriptor> {
+        return self.descriptors.iter().find(|descriptor| descriptor.data_type == data_type);
+    }
+
+    pub fn data<R: Read + Seek>(&self, reader: &mut R, descriptor: &SifDescriptor) -> Result<Vec<u8>, String> {
+        if descriptor.size > MAX_METADATA_SIZE {
+            return Err(format!("SIF object {} is too large to be read as metadata", descriptor.id));
+        }
+        let mut data = vec![0u8; descriptor.size.max(0) as usize];
+        reader.seek(SeekFrom::Start(descriptor.offset as u64)).and_then(|_| reader.read_exact(&mut data))
+            .map_err(|e| format!("Could not read SIF object {}: {}", descriptor.id, e))?;
+        return Ok(data);
+    }
+}
+
+impl SifMetadata {
+    pub fn read<R: Read + Seek>(reader: &mut R) -> Result<Self, String> {
+        let image = SifImage::read(reader)?;
+        let mut text = |descriptor: &SifDescriptor| image.data(reader, descriptor).map(|data| String::from_utf8_lossy(&data).trim_end_matches('\0').to_string());
+
+        let deffile = image.first_of(SifDataType::Deffile).map(&mut text).transpose()?;
+        let labels = match image.first_of(SifDataType::Labels).map(&mut text).transpose()? {
+            Some(json) => string_map(Some(&serde_json::from_str::<Value>(&json).map_err(|e| format!("Invalid SIF labels: {}", e))?)),
+            None => BTreeMap::new(),
+        };
+        // images built by newer runtimes carry the complete `inspect` output
+        let inspection = image.descriptors.iter()
+                            .filter(|descriptor| descriptor.data_type == SifDataType::GenericJson)
+                            .filter_map(|descriptor| text(descriptor).ok())
+                            .filter_map(|json| serde_json::from_str::<Value>(&json).ok())
+                            .find_map(|json| ContainerInspection::from_json(&json).ok());
+        let signatures = image.descriptors.iter()
+                            .filter_map(|descriptor| {
+                                let info = descriptor.signature.clone()?;
+                                let signed = descriptor.linked?;
+                                let message = text(descriptor).ok()?;
+                                Some(SifSignature { descriptor_id: descriptor.id, signed, info, message })
+                            })
+                            .collect();
+
+        return Ok(SifMetadata { deffile, labels, inspection, signatures, image });
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| format!("Could not open {:?}: {}", path, e))?;
+        return SifMetadata::read(&mut BufReader::new(file));
+    }
+
+    // the full inspect metadata if present, otherwise what deffile and labels tell
+    pub fn to_inspection(&self) -> ContainerInspection {
+        if let Some(inspection) = &self.inspection {
+            return inspection.clone();
+        }
+        let json = serde_json::json!({ "data": { "attributes": { "labels": &self.labels, "deffile": &self.deffile } } });
+        return ContainerInspection::from_json(&json).unwrap_or_default();
+    }
+}
+
+fn parse_header(raw: &[u8]) -> Result<SifHeader, String> {
+    if &raw[32..42] != SIF_MAGIC {
+        return Err("Not a SIF image".to_string());
+    }
+    return Ok(SifHeader {
+        version: c_string(&raw[42..45]),
+        arch: arch_name(&raw[45..48]),
+        id: Uuid::from_slice(&raw[48..64]).unwrap_or_default(),
+        created_at: i64_at(raw, 64),
+        modified_at: i64_at(raw, 72),
+        descriptors_free: i64_at(raw, 80),
+        descriptors_total: i64_at(raw, 88),
+        descriptors_offset: i64_at(raw, 96),
+        data_offset: i64_at(raw, 112),
+        data_size: i64_at(raw, 120),
+    });
+}
+
+// layout: type, used, id, group, link, offset, size, size with padding, created, modified, uid, gid, name[128], extra[384]
+fn parse_descriptor(raw: &[u8]) -> Option<SifDescriptor> {
+    if raw[4] == 0 { return None; }
+
+    let data_type = SifDataType::from_raw(i32_at(raw, 0));
+    let group = u32_at(raw, 9);
+    let extra = &raw[201..585];
+
+    let partition = (data_type == SifDataType::Partition).then(|| SifPartition {
+        fs_type: match i32_at(extra, 0) {
+            1 => "Squashfs", 2 => "Ext3", 3 => "Immutable object", 4 => "Raw", 5 => "Encrypted squashfs",
+            _ => "Unknown",
+        }.to_string(),
+        part_type: match i32_at(extra, 4) {
+            1 => "System", 2 => "Primary system", 3 => "Data", 4 => "Overlay",
+            _ => "Unknown",
+        }.to_string(),
+        arch: arch_name(&extra[8..11]),
+    });
+    let signature = (data_type == SifDataType::Signature).then(|| SifSignatureInfo {
+        hash_type: match i32_at(extra, 0) {
+            1 => "SHA256", 2 => "SHA384", 3 => "SHA512", 4 => "BLAKE2s", 5 => "BLAKE2b",
+            _ => "Unknown",
+        }.to_string(),
+        fingerprint: extra[4..24].iter().map(|byte| format!("{:02X}", byte)).collect(),
+    });
+
+    return Some(SifDescriptor {
+        data_type,
+        id: u32_at(raw, 5),
+        group_id: (group & GROUP_MASK != 0).then_some(group & !GROUP_MASK),
+        linked: SifLink::from_raw(u32_at(raw, 13)),
+        offset: i64_at(raw, 17),
+        size: i64_at(raw, 25),
+        created_at: i64_at(raw, 41),
+        name: c_string(&raw[73..201]),
+        partition,
+        signature,
+    });
+}
+
+fn arch_name(raw: &[u8]) -> String {
+    return match c_string(raw).as_str() {
+        "01" => "386", "02" => "amd64", "03" => "arm", "04" => "arm64", "05" => "ppc64", "06" => "ppc64le",
+        "07" => "mips", "08" => "mipsle", "09" => "mips64", "10" => "mips64le", "11" => "s390x", "12" => "riscv64",
+        _ => "unknown",
+    }.to_string();
+}
+
+fn c_string(raw: &[u8]) -> String {
+    let end = raw.iter().position(|byte| *byte == 0).unwrap_or(raw.len());
+    return String::from_utf8_lossy(&raw[..end]).into_owned();
+}
+
+fn i32_at(raw: &[u8], offset: usize) -> i32 {
+    return i32::from_le_bytes(raw[offset..offset + 4].try_into().unwrap());
+}
+
+fn u32_at(raw: &[u8], offset: usize) -> u32 {
+    return u32::from_le_bytes(raw[offset..offset + 4].try_into().unwrap());
+}
+
+fn i64_at(raw: &[u8], offset: usize) -> i64 {
+    return i64::from_le_bytes(raw[offset..offset + 8].try_into().unwrap());
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn descriptor(data_type: i32, id: u32, link: u32, offset: i64, size: i64, name: &str, extra: &[u8]) -> Vec<u8> {
+        let mut raw = Vec::new();
+        raw.extend(data_type.to_le_bytes());
+        raw.push(1);
+        raw.extend(id.to_le_bytes());
+        raw.extend((GROUP_MASK | 1).to_le_bytes());
+        raw.extend(link.to_le_bytes());
+        for value in [offset, size, size, 1700000000, 1700000000, 0, 0] { raw.extend(value.to_le_bytes()); }
+        let mut name_field = name.as_bytes().to_vec(); name_field.resize(128, 0);
+        let mut extra_field = extra.to_vec(); extra_field.resize(384, 0);
+        raw.extend(name_field);
+        raw.extend(extra_field);
+        return raw;
+    }
+
+    fn header(descriptor_count: i64, data_offset: i64) -> Vec<u8> {
+        let mut raw = b"#!/usr/bin/env run-singularity\n\0".to_vec();
+        raw.extend(SIF_MAGIC);
+        raw.extend(b"01\0");
+        raw.extend(b"02\0");
+        raw.extend([7u8; 16]);
+        for value in [1700000000i64, 1700000000, 11, descriptor_count, HEADER_SIZE as i64, descriptor_count.wrapping_mul(DESCRIPTOR_SIZE as i64), data_offset, 0] {
+            raw.extend(value.to_le_bytes());
+        }
+        return raw;
+    }
+
+    #[test]
+    fn metadata_is_read_from_descriptors() {
+        let deffile = b"Bootstrap: docker\nFrom: ubuntu:22.04\n".to_vec();
+        let labels = br#"{"maintainer": "colony", "org.label-schema.usage.apptainer.version": "1.3.4"}"#.to_vec();
+        let signature = b"-----BEGIN PGP SIGNED MESSAGE-----\n".to_vec();
+        let descriptor_count = 6;
+        let data_offset = (HEADER_SIZE + descriptor_count * DESCRIPTOR_SIZE) as i64;
+
+        let mut partition_extra = 1i32.to_le_bytes().to_vec();
+        partition_extra.extend(2i32.to_le_bytes());
+        partition_extra.extend(b"02\0");
+        let mut signature_extra = 1i32.to_le_bytes().to_vec();
+        signature_extra.extend([0xAB; 20]);
+
+        let mut image = header(descriptor_count as i64, data_offset);
+        let mut offset = data_offset;
+        image.extend(descriptor(0x4001, 1, 0, offset, deffile.len() as i64, "", &[])); offset += deffile.len() as i64;
+        image.extend(descriptor(0x4003, 2, 0, offset, labels.len() as i64, "", &[])); offset += labels.len() as i64;
+        image.extend(descriptor(0x4004, 3, 0, offset, 4096, "rootfs", &partition_extra));
+        image.extend(descriptor(0x4005, 4, 3, offset + 4096, signature.len() as i64, "", &signature_extra));
+        image.extend(descriptor(0x4005, 6, GROUP_MASK | 1, offset + 4096, signature.len() as i64, "", &signature_extra));
+        let mut unused = descriptor(0x4007, 5, 0, 0, 0, "", &[]); unused[4] = 0;
+        image.extend(unused);
+        image.extend(&deffile);
+        image.extend(&labels);
+        image.extend(vec![0u8; 4096]);
+        image.extend(&signature);
+
+        let metadata = SifMetadata::read(&mut Cursor::new(image)).unwrap();
+
+        assert_eq!(metadata.image.header.arch, "amd64");
+        assert_eq!(metadata.image.descriptors.len(), 5);
+        let partition = metadata.image.partitions().next().unwrap();
+        assert_eq!(partition.name, "rootfs");
+        assert_eq!(partition.group_id, Some(1));
+        assert_eq!(partition.partition.as_ref().map(|part| part.part_type.as_str()), Some("Primary system"));
+        assert_eq!(metadata.deffile.as_deref(), Some("Bootstrap: docker\nFrom: ubuntu:22.04\n"));
+        assert_eq!(metadata.signatures.len(), 2);
+        assert_eq!(metadata.signatures[0].signed, SifLink::Object(3));
+        assert_eq!(metadata.signatures[1].signed, SifLink::Group(1));
+        assert_eq!(metadata.signatures[0].info.fingerprint, "AB".repeat(20));
+
+        let inspection = metadata.to_inspection();
+        assert_eq!(inspection.labels.get("maintainer").map(String::as_str), Some("colony"));
+        assert_eq!(inspection.built_with.map(|version| version.version), Some("1.3.4".to_string()));
+
+        assert!(SifMetadata::read(&mut Cursor::new(vec![0u8; 256])).is_err());
+    }
+
+    #[test]
+    fn descriptor_counts_are_bounded_by_the_file() {
+        let mut image = header(2, HEADER_SIZE as i64);
+        image.extend(descriptor(0x4001, 1, 0, 0, 0, "", &[]));
+        assert!(SifImage::read(&mut Cursor::new(image.clone())).is_err());
+
+        image.extend(descriptor(0x4001, 2, 0, 0, 0, "", &[]));
+        assert_eq!(SifImage::read(&mut Cursor::new(image)).unwrap().descriptors.len(), 2);
+
+        for count in [i64::MAX, i64::MAX / DESCRIPTOR_SIZE as i64, -1] {
+            assert!(SifImage::read(&mut Cursor::new(header(count, HEADER_SIZE as i64))).is_err());
+        }
+    }
+}
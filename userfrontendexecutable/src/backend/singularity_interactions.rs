@@ -5,11 +5,11 @@
 
 
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::str::FromStr;   //Pathbuf::from_str
 
-use std::process::{Command, Child};
-use std::os::windows::process::CommandExt; // Command.creation_flags
+use std::process::Child;
 use std::io::{Write, BufReader};
 
 use std::sync::Mutex;
@@ -21,12 +21,21 @@ use actix_cors::Cors;
 use actix_web::{get, post, web, web::Data, App, HttpServer, Responder};
 use actix_web::web::PayloadConfig;
 use itertools::Itertools; // Iterator.collect_vec();
+use plugin_interface_elements::elements_v1;
 
 
 use crate::{pages::*, JobId};
 use crate::backend;
-use crate::backend::backend_misc::{wslify_windows_path, unwslify_wsl_linux_path, CREATE_NO_WINDOW};
-use crate::backend::BackendRequest;
+use crate::backend::backend_misc::{wslify_windows_path, unwslify_wsl_linux_path};
+use crate::backend::command_runner::CommandSpec;
+use crate::backend::container_runtime::ContainerEngine;
+use crate::backend::container_inspection::{ContainerInspection, labels_from_json};
+use crate::backend::sif::SifMetadata;
+use crate::backend::run_profile::RunProfile;
+use crate::backend::job_process::{JobProcess, DEFAULT_TERMINAL_SIZE};
+use crate::backend::process_tree::mark_job_processes;
+use crate::backend::configuration_schema::{parse_configuration, ConfigurationSchema};
+use crate::backend::{BackendRequest, SharedChatStore, SharedConfigurationStore};
 use crate::backend::persistent_state::exe_dir;
 
 
@@ -38,21 +47,14 @@ use crate::backend::persistent_state::exe_dir;
 
 //################################################################################
 //## query a singularity container for its capabilities
-//## TODO: commands depend on operating system
-//## TODO: !!!! ensure shell escaping
 //################################################################################
 
 //TODO: introduce error enum, since having no app list is different from not being able to read from it
 //TODO: serialize response into struct
 
-fn singularity_command(args: Vec<&str>) -> Option<String>  {
+fn singularity_command(engine: &ContainerEngine, spec: CommandSpec) -> Option<String>  {
 
-    let output_res = Command::new("cmd")
-                            .creation_flags(CREATE_NO_WINDOW)
-                            .args(&args)
-                            .stdout(std::process::Stdio::piped())
-                            .stderr(std::process::Stdio::piped())
-                            .output();
+    let output_res = engine.runner.output(&spec);
 
     return match output_res {
         Ok(out) => match out.status.code() {
@@ -73,175 +75,178 @@ fn singularity_command(args: Vec<&str>) -> Option<String>  {
             }
         },
         Err(e) => {
-            println!("Error executing {:?}: {:?}", &args, &e);
+            println!("Error executing {:?}: {:?}", &spec, &e);
             None
         }
     }
 }
 
-pub fn singularity_inspection(container_path: &PathBuf) -> Option<serde_json::Value> {
-    let pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    let response = singularity_command(vec!["/C", "wsl -d ColonyWSL -e singularity inspect --all", &pth]);
+pub fn singularity_inspection(engine: &ContainerEngine, container_path: &PathBuf) -> Option<ContainerInspection> {
+    let pth = engine.host_path(container_path);
+    let response = singularity_command(engine, engine.command().args(["inspect", "--all", &pth]));
 
-    let json: Option<serde_json::Value> = match response {
+    let inspection = match response {
         Some(s) => {
-            match serde_json::from_str(&s) {
-                Ok(js) => {
-                    println!("Success interpreting json data");
-                    Some(js)
+            match serde_json::from_str(&s).map_err(|e| e.to_string()).and_then(|json| ContainerInspection::from_json(&json)) {
+                Ok(inspection) => {
+                    println!("Success interpreting inspection data");
+                    Some(inspection)
                 },
-                Err(_) => {
-                    println!("Error interpreting json data: {:?}", &s);
+                Err(e) => {
+                    println!("Error interpreting inspection data: {} in {:?}", &e, &s);
                     None
                 }
             }
         },
         None => {
-            println!("Error reading json data");
+            println!("Error reading inspection data");
             None
         }
     };
 
-    return json;
+    return inspection;
 }
 
-pub fn singularity_app_list(container_path: &PathBuf) -> Option<Vec<String>> {
-    let pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    let response = singularity_command(vec!["/C", "wsl -d ColonyWSL -e", "singularity", "inspect", "--list-apps", &pth]);
+// reads the image directly where it carries the full inspect metadata, which is instant and needs no container runtime;
+// otherwise the runtime is asked, and only if that fails too, what deffile and labels in the image tell is used
+pub fn container_inspection(engine: &ContainerEngine, container_path: &PathBuf) -> Option<ContainerInspection> {
+    let metadata = match SifMetadata::from_file(container_path) {
+        Ok(metadata) => Some(metadata),
+        Err(e) => {
+            println!("Could not read SIF metadata of {:?}: {}", container_path, &e);
+            None
+        }
+    };
+    if let Some(inspection) = metadata.as_ref().and_then(|metadata| metadata.inspection.clone()) {
+        return Some(inspection);
+    }
+    return singularity_inspection(engine, container_path).or_else(|| metadata.map(|metadata| metadata.to_inspection()));
+}
+
+// only images carrying the full inspect metadata know their apps
+pub fn sif_app_list(container_path: &PathBuf) -> Option<Vec<String>> {
+    let metadata = SifMetadata::from_file(container_path).ok()?;
+    return metadata.inspection.map(|inspection| inspection.apps.into_keys().collect());
+}
+
+pub fn singularity_app_list(engine: &ContainerEngine, container_path: &PathBuf) -> Option<Vec<String>> {
+    let pth = engine.host_path(container_path);
+    let response = singularity_command(engine, engine.command().args(["inspect", "--list-apps", &pth]));
     let result = response.map(|resp| resp.lines().map(String::from).collect_vec());
     return result;
 }
 
-#[allow(unused)]
-pub fn singularity_help(container_path: &PathBuf) -> Option<String> {
-    let pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    return singularity_command(vec!["/C", "wsl -d ColonyWSL -e", "singularity help", &pth]);
+// the %help section of the container, or of one of its apps
+pub fn singularity_help(engine: &ContainerEngine, container_path: &PathBuf, app: Option<&str>) -> Option<String> {
+    let pth = engine.host_path(container_path);
+    let mut spec = engine.command().arg("run-help");
+    if let Some(app) = app { spec = spec.args(["--app", app]); }
+
+    // a failed run-help prints its error instead, which must not be taken for the help text
+    let spec = spec.arg(&pth);
+    return match engine.runner.output(&spec) {
+        Ok(out) if out.status.success() => Some(String::from_utf8_lossy(&out.stdout).trim().to_string()).filter(|help| !help.is_empty()),
+        Ok(out) => {
+            println!("No help text, run-help resulted in {}: {}", &out.status, String::from_utf8_lossy(&out.stderr));
+            None
+        },
+        Err(e) => {
+            println!("Error executing {:?}: {:?}", &spec, &e);
+            None
+        }
+    };
 }
 
-#[allow(unused)]
-pub fn singularity_label(container_path: &PathBuf) -> Option<String> {
-    let pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    return singularity_command(vec!["/C", "wsl -d ColonyWSL -e", "singularity inspect --json --labels", &pth]);
+pub fn singularity_labels(engine: &ContainerEngine, container_path: &PathBuf, app: Option<&str>) -> Option<BTreeMap<String, String>> {
+    let pth = engine.host_path(container_path);
+    let mut spec = engine.command().args(["inspect", "--json", "--labels"]);
+    if let Some(app) = app { spec = spec.args(["--app", app]); }
+    let response = singularity_command(engine, spec.arg(&pth))?;
+
+    return match serde_json::from_str(&response).map_err(|e| e.to_string()).and_then(|json| labels_from_json(&json)) {
+        Ok(labels) => Some(labels),
+        Err(e) => {
+            println!("Error interpreting label data: {} in {:?}", &e, &response);
+            None
+        }
+    };
 }
 
 //TODO: serialize response into struct
-pub fn singularity_app_requirements(container_path: &PathBuf) -> Option<String> {
-    let pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    return singularity_command(vec!["/C", "wsl -d ColonyWSL -e", "singularity run --app app-requirements", &pth]);
+pub fn singularity_app_requirements(engine: &ContainerEngine, container_path: &PathBuf) -> Option<String> {
+    let pth = engine.host_path(container_path);
+    return singularity_command(engine, engine.command().args(["run", "--app", "app-requirements", &pth]));
 }
 
 //TODO: serialize response into json
-pub fn singularity_app_configuration_options(container_path: &PathBuf) -> Option<String> {
-    let pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    return singularity_command(vec!["/C", "wsl -d ColonyWSL -e", "singularity run --app app-configurations", &pth]);
+pub fn singularity_app_configuration_options(engine: &ContainerEngine, container_path: &PathBuf) -> Option<String> {
+    let pth = engine.host_path(container_path);
+    return singularity_command(engine, engine.command().args(["run", "--app", "app-configurations", &pth]));
 }
 
-// pub fn singularity_app_help(container_path: &str, app: &str) -> Option<String> {
-//     return singularity_command(vec!["/C", "wsl -d ColonyWSL -e", "singularity help --app", &app, &container_path]);
-// }
-
-// pub fn singularity_app_label(container_path: &str, app: &str) -> Option<String> {
-//     return singularity_command(vec!["/C", "wsl -d ColonyWSL -e", "singularity inspect --json --labels --app", &app, &container_path]);
-// }
-
-// pub fn singularity_test_app(container_path: &str, app: &str) -> Result<Child,std::io::Error> {
-//     return Command::new("cmd")
-//                 .args(["/C", "wsl", "-e", "singularity", "test", "--app", &app, &container_path])
-//                 .spawn();
-// }
-
 //################################################################################
 //## running and testing a container
-//## TODO: commands depend on operating system
-//## TODO: !!!! ensure shell escaping
 //################################################################################
 
-pub fn singularity_run(container_path: &PathBuf, container_args: Vec<String>) -> Result<Child,std::io::Error> {
-    let pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    println!("Command: /C wsl -d ColonyWSL -e singularity run --bind /mnt:/mnt {:?} {:?}", &pth, &container_args);
-    return Command::new("cmd")
-    .creation_flags(CREATE_NO_WINDOW)
-    .args(["/C", "wsl -d ColonyWSL -e", "singularity", "run", "--bind", "/mnt:/mnt", "--writable-tmpfs", &pth])
-    .args(container_args)
-    .arg("2>&1")
-    .stdin(std::process::Stdio::piped())
-    .stderr(std::process::Stdio::piped())
-    .stdout(std::process::Stdio::piped())
-    .spawn();
-}
-
-pub fn singularity_run_in_dir(working_directory: &PathBuf, container_path: &PathBuf, container_args: Vec<String>) -> Result<Child,std::io::Error> {
-    let work_dir = wslify_windows_path(&working_directory.to_string_lossy().to_string());
+pub fn singularity_run(engine: &ContainerEngine, profile: &RunProfile, job_id: &JobId, container_path: &PathBuf, container_args: Vec<String>) -> Result<JobProcess,std::io::Error> {
+    let pth = engine.host_path(container_path);
+    let spec = profile.apply(engine, engine.command().arg("run"));
+    return spawn_job_process(engine, profile, job_id, &spec.arg(&pth).args(container_args));
+}
+
+pub fn singularity_run_in_dir(engine: &ContainerEngine, profile: &RunProfile, job_id: &JobId, working_directory: &PathBuf, container_path: &PathBuf, container_args: Vec<String>) -> Result<JobProcess,std::io::Error> {
+    let work_dir = engine.host_path(working_directory);
     println!("Working Directory: {}", &work_dir);
-    let container_pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
+    let container_pth = engine.host_path(container_path);
     println!("Container Path: {}", &container_pth);
 
-    let mut base_cmd = Command::new("cmd");
-    let cmd = base_cmd
-    .creation_flags(CREATE_NO_WINDOW)
-    .current_dir(&working_directory)
-    .args(["/C", "wsl -d ColonyWSL --shell-type standard", "singularity", "run", "--pwd", &work_dir, "--bind", "/mnt:/mnt", "--writable-tmpfs", &container_pth])
-    .args(container_args)
-    //.arg("2>&1")
-    .stdin(std::process::Stdio::piped())
-    .stderr(std::process::Stdio::piped())
-    .stdout(std::process::Stdio::piped());
-
-    println!("Command: {:?}", &cmd);
-    return cmd.spawn();
+    let spec = profile.apply(engine, engine.command().current_dir(working_directory).args(["run", "--pwd", &work_dir]));
+    return spawn_job_process(engine, profile, job_id, &spec.arg(&container_pth).args(container_args));
 }
 
 #[allow(unused)]
-pub fn singularity_run_app(container_path: &PathBuf, app: &str, container_args: Vec<String>) -> Result<Child,std::io::Error> {
-    let container_pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    return Command::new("cmd")
-    .creation_flags(CREATE_NO_WINDOW)
-    .args(["/C", "wsl -d ColonyWSL -e", "singularity", "run", "--writable-tmpfs", "--app", &app, &container_pth])
-    .args(container_args)
-    //.arg("2>&1")
-    .stdin(std::process::Stdio::piped())
-    .stderr(std::process::Stdio::piped())
-    .stdout(std::process::Stdio::piped())
-    .spawn();
-}
-
-pub fn singularity_run_app_in_dir(working_directory: &PathBuf, container_path: &PathBuf, app: &str, container_args: Vec<String>) -> Result<Child,std::io::Error> {
-    let wsl_workdir = wslify_windows_path(&working_directory.to_string_lossy().to_string());
-    let container_pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-
-    println!("container app workdir: {:?}", &wsl_workdir);
+pub fn singularity_run_app(engine: &ContainerEngine, profile: &RunProfile, job_id: &JobId, container_path: &PathBuf, app: &str, container_args: Vec<String>) -> Result<JobProcess,std::io::Error> {
+    let container_pth = engine.host_path(container_path);
+    let spec = profile.apply(engine, engine.command().arg("run"));
+    return spawn_job_process(engine, profile, job_id, &spec.args(["--app", app, &container_pth]).args(container_args));
+}
+
+pub fn singularity_run_app_in_dir(engine: &ContainerEngine, profile: &RunProfile, job_id: &JobId, working_directory: &PathBuf, container_path: &PathBuf, app: &str, container_args: Vec<String>) -> Result<JobProcess,std::io::Error> {
+    let workdir = engine.host_path(working_directory);
+    let container_pth = engine.host_path(container_path);
+
+    println!("container app workdir: {:?}", &workdir);
     println!("container path: {:?}", &container_pth);
     println!("container app args: {:?}", &container_args);
-    println!("Command: {:?}", &["--shell-type", "standard", "singularity", "run", "--pwd", &wsl_workdir, "--bind", "/mnt:/mnt", "--writable-tmpfs", "--app", &app, &container_pth]);
-
-    let singularity_cmd = format!(
-        r#"wsl -d ColonyWSL -e "singularity run --pwd {} --bind /mnt:/mnt --app {} {} {} 2>&1 | tee raw_output.log""#,
-        wsl_workdir,
-        app,
-        container_pth,
-        container_args.join(" "),
-    );
-
-    return Command::new("cmd")
-    .creation_flags(CREATE_NO_WINDOW)
-    //.args(["/C", &singularity_cmd])
-    .args(["/C", "wsl -d ColonyWSL -e", "singularity", "run", "--pwd", &wsl_workdir, "--bind", "/mnt:/mnt", "--writable-tmpfs", "--app", &app, &container_pth])
-    .args(container_args)
-    //.arg("2>&1 | tee raw_output.log")
-    .stdin(std::process::Stdio::piped())
-    .stderr(std::process::Stdio::piped())
-    .stdout(std::process::Stdio::piped())
-    .spawn();
+
+    let spec = profile.apply(engine, engine.command().current_dir(working_directory).args(["run", "--pwd", &workdir]));
+    return spawn_job_process(engine, profile, job_id, &spec.args(["--app", app, &container_pth]).args(container_args));
+}
+
+// interactive, so always attached to a terminal; mounts and isolation are the same as for runs
+pub fn singularity_shell_in_dir(engine: &ContainerEngine, profile: &RunProfile, job_id: &JobId, working_directory: &PathBuf, container_path: &PathBuf) -> Result<JobProcess,std::io::Error> {
+    let work_dir = engine.host_path(working_directory);
+    let container_pth = engine.host_path(container_path);
+
+    let spec = profile.apply(engine, engine.command().current_dir(working_directory).args(["shell", "--pwd", &work_dir]));
+    let spec = mark_job_processes(engine, spec.arg(&container_pth), job_id);
+    return engine.runner.spawn_in_terminal(&spec, DEFAULT_TERMINAL_SIZE).map(JobProcess::Terminal);
+}
+
+// the profile decides whether the job draws on a terminal or writes into pipes;
+// its processes are marked, so they can be stopped on the container host
+fn spawn_job_process(engine: &ContainerEngine, profile: &RunProfile, job_id: &JobId, spec: &CommandSpec) -> Result<JobProcess,std::io::Error> {
+    let spec = mark_job_processes(engine, spec.clone(), job_id);
+    if profile.terminal {
+        return engine.runner.spawn_in_terminal(&spec, DEFAULT_TERMINAL_SIZE).map(JobProcess::Terminal);
+    }
+    return engine.runner.spawn(&spec).map(JobProcess::Piped);
 }
 
 #[allow(unused)]
-pub fn singularity_test(container_path: &PathBuf) -> Result<Child,std::io::Error> {
-    let container_pth = wslify_windows_path(&container_path.to_string_lossy().to_string());
-    return Command::new("cmd")
-    .creation_flags(CREATE_NO_WINDOW)
-    .args(["/C", "wsl -d ColonyWSL -e", "singularity", "test", &container_pth])
-    .arg("2>&1")
-    .spawn();
+pub fn singularity_test(engine: &ContainerEngine, container_path: &PathBuf) -> Result<Child,std::io::Error> {
+    let container_pth = engine.host_path(container_path);
+    return engine.runner.spawn(&engine.command().args(["test", &container_pth]));
 }
 
 
@@ -344,8 +349,69 @@ pub fn copy_config_and_file_references(workdir: &PathBuf, config_file_path: &Pat
 //################################################################################
 
 
+// marks apps that talk to the launcher, see the self-configurator contract in the README
+pub const COLONY_INTEROP_ARGUMENT: &str = "--colony-interop";
+
+// both ends of a self-configurator handshake, picked when the frontend-local server starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelfConfiguratorPorts {
+    pub launcher: u16,  // the frontend-local server, where the container sends its configuration
+    pub gui: u16,       // where the container serves its configuration GUI
+}
+
+impl SelfConfiguratorPorts {
+    // the self-configurator app learns both ports from its arguments; containers built for the
+    // fixed ports 20311 and 9283 have to be updated, see the self-configurator contract in the README
+    pub fn container_args(&self) -> Vec<String> {
+        return vec![COLONY_INTEROP_ARGUMENT.to_string(),
+                    "--launcher-port".to_string(), self.launcher.to_string(),
+                    "--gui-port".to_string(), self.gui.to_string()];
+    }
+
+    pub fn gui_address(&self) -> String {
+        return format!("http://localhost:{}/", self.gui);
+    }
+}
+
+// every request of the container to the frontend-local server carries this token,
+// other local processes can neither trigger file dialogs nor hand in configurations
+#[derive(Clone, PartialEq, Eq)]
+pub struct SessionToken(String);
+
+// the token reaches the container through its environment, arguments are visible to every process and end up in the manifest
+pub const SESSION_TOKEN_VARIABLE: &str = "COLONY_SESSION_TOKEN";
+
+impl SessionToken {
+    pub fn new() -> Self {
+        return SessionToken(rand::random::<[u8; 32]>().iter().map(|byte| format!("{:02x}", byte)).join(""));
+    }
+
+    pub fn as_str(&self) -> &str {
+        return &self.0;
+    }
+
+    // compares every byte, so the token cannot be guessed one byte at a time
+    pub fn matches(&self, candidate: &str) -> bool {
+        if candidate.len() != self.0.len() { return false; }
+        return self.0.bytes().zip(candidate.bytes()).fold(0, |difference, (a, b)| difference | (a ^ b)) == 0;
+    }
+}
+
+// kept out of logs
+impl std::fmt::Debug for SessionToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "SessionToken(..)");
+    }
+}
+
+// the port is only reserved until the listener is dropped, the container binds it shortly after
+pub fn free_local_port() -> std::io::Result<u16> {
+    return std::net::TcpListener::bind(("127.0.0.1", 0))?.local_addr().map(|address| address.port());
+}
+
 pub async fn start_frontend_local_server(followup_page: AppState, comm_partner_container: PathBuf,
-                                         this_servers_job_id: JobId,
+                                         this_servers_job_id: JobId, session_token: SessionToken,
+                                         configuration_schema: Option<ConfigurationSchema>,
                                          comm_with_backend: Sender<BackendRequest>) {
 
     //TODO: implement timeout with a tokio::select! statement or similar
@@ -354,19 +420,26 @@ pub async fn start_frontend_local_server(followup_page: AppState, comm_partner_c
         let tsj_data = Data::new(Mutex::new(this_servers_job_id));
         let cpc_data = Data::new(Mutex::new(comm_partner_container));
         let cwb_data = Data::new(Mutex::new(comm_with_backend.clone()));
+        let token_data = Data::new(session_token);
+        let schema_data = Data::new(configuration_schema);
         let adr = "127.0.0.1";
-        let port = 20311;
-
-        println!("Making termination request...");
-        reqwest::get("http://127.0.0.1:9283/terminate").await.ok();
-        println!("Completed termination request");
 
+        let gui_port = match free_local_port() {
+            Ok(port) => port,
+            Err(e) => {
+                println!("No free port for the self-configurator GUI: {:?}", &e);
+                comm_with_backend.send(BackendRequest::InformLocalWebserverStarted(Err(std::sync::Arc::new(Box::new(e))))).ok();
+                return;
+            }
+        };
+        let gui_data = Data::new(Mutex::new(gui_port));
 
-        println!("Listening on {}:{}...", &adr, &port);
+        // port 0 lets the system pick a free port, which is read back after binding
+        println!("Listening on {}, self-configurator GUI expected on port {}...", &adr, &gui_port);
         let server_result = HttpServer::new(move || {
             let cors = Cors::default()
-            .allowed_origin("http://localhost:9283")
-            .allowed_origin("http://127.0.0.1:9283")
+            .allowed_origin(&format!("http://localhost:{}", gui_port))
+            .allowed_origin(&format!("http://127.0.0.1:{}", gui_port))
             .allowed_headers(vec![actix_web::http::header::AUTHORIZATION, actix_web::http::header::ACCEPT])
             .allowed_header(actix_web::http::header::CONTENT_TYPE)
             .allowed_methods(vec!["GET", "POST"])
@@ -380,6 +453,9 @@ pub async fn start_frontend_local_server(followup_page: AppState, comm_partner_c
             .app_data(Data::clone(&tsj_data))
             .app_data(Data::clone(&cpc_data))
             .app_data(Data::clone(&cwb_data))
+            .app_data(Data::clone(&gui_data))
+            .app_data(Data::clone(&token_data))
+            .app_data(Data::clone(&schema_data))
             .service(health_check)
             .service(pick_local_file)
             .service(pick_local_files)
@@ -387,31 +463,74 @@ pub async fn start_frontend_local_server(followup_page: AppState, comm_partner_c
             .service(pick_local_directories)
             .service(download_json_config)
         })
-        .bind((adr,port));
+        .bind((adr, 0));
+
+        match server_result {
+            Ok(server) => {
+                let launcher_port = server.addrs().first().map(|address| address.port()).unwrap_or_default();
+                let ports = SelfConfiguratorPorts { launcher: launcher_port, gui: gui_port };
+                println!("Server is running on port {}!", &launcher_port);
+                comm_with_backend.send(BackendRequest::InformLocalWebserverStarted(Ok(ports))).ok();
+                server.run().await.ok();
+            },
+            Err(err) => {
+                println!("Server is NOT running: {:?}", &err);
+                comm_with_backend.send(BackendRequest::InformLocalWebserverStarted(Err(std::sync::Arc::new(Box::new(err))))).ok();
+            }
+        };
+    });
+}
 
 
-        //TODO: handle server start failure
-        comm_with_backend.send(BackendRequest::InformLocalWebserverStarted(Ok(port))).ok();
-        server_result.unwrap().run().await.ok();
 
-        //match server_result {
-        //            Ok(server) => {
-        //                let srv = Some(server.run());
-        //                println!("Server is running!");
-        //                comm_with_backend.send(BackendRequest::InformLocalWebserverStarted(Ok(port))).ok();
-        //            },
-        //            Err(err) => {
-        //                let err_boxed = Err(std::sync::Arc::new(Box::new(err)));
-        //                println!("Server is NOT running!");
-        //                comm_with_backend.send(BackendRequest::InformLocalWebserverStarted(err_boxed)).ok();
-        //            }
-        //        };
+// plugins and running containers reach the launcher here for as long as the launcher runs;
+// every run finds the complete address, session token included, in this variable
+pub const FRONTEND_TASK_URL_VARIABLE: &str = "COLONY_FRONTEND_TASK_URL";
 
+pub fn frontend_task_url(port: u16, session_token: &SessionToken) -> String {
+    return format!("http://127.0.0.1:{}/frontend/task/{}", port, session_token.as_str());
+}
 
+pub async fn start_frontend_task_server(chat_store: SharedChatStore,
+                                        configuration_store: SharedConfigurationStore,
+                                        session_token: SessionToken,
+                                        comm_with_backend: Sender<BackendRequest>) {
 
-    });
+    tokio::spawn( async move {
+        let chat_data = Data::new(chat_store);
+        let config_store_data = Data::new(configuration_store);
+        let cwb_data = Data::new(Mutex::new(comm_with_backend.clone()));
+        let token_data = Data::new(session_token);
+        let adr = "127.0.0.1";
+
+        // port 0 lets the system pick a free port, runs are told about it through their environment
+        let server_result = HttpServer::new(move || {
+            let payload_config = PayloadConfig::new(256*1024);
 
-    //return Ok(port);
+            App::new()
+            .app_data(payload_config)
+            .app_data(Data::clone(&chat_data))
+            .app_data(Data::clone(&config_store_data))
+            .app_data(Data::clone(&cwb_data))
+            .app_data(Data::clone(&token_data))
+            .service(health_check)
+            .service(frontend_task)
+        })
+        .bind((adr, 0));
+
+        match server_result {
+            Ok(server) => {
+                let port = server.addrs().first().map(|address| address.port()).unwrap_or_default();
+                println!("Frontend task server listening on {}:{}...", &adr, &port);
+                comm_with_backend.send(BackendRequest::InformFrontendTaskServerStarted(Ok(port))).ok();
+                server.run().await.ok();
+            },
+            Err(e) => {
+                println!("Frontend task server could not be started: {:?}", &e);
+                comm_with_backend.send(BackendRequest::InformFrontendTaskServerStarted(Err(std::sync::Arc::new(Box::new(e))))).ok();
+            }
+        }
+    });
 }
 
 
@@ -426,29 +545,39 @@ async fn health_check() -> impl Responder {
     format!("Thie web server is up and running.")
 }
 
-#[get("/choosefile/{request_id}/{filename}")]
-async fn pick_local_file(path: web::Path<(String,String)>) -> impl Responder {
-    let (_request_id, _filename) = path.into_inner();
+// every endpoint the container calls takes the session token as a path segment
+fn reject_unknown_token(session_token: &SessionToken, candidate: &str) -> Option<actix_web::HttpResponse> {
+    if session_token.matches(candidate) { return None; }
+    println!("Rejected a request to a frontend server with an unknown session token");
+    return Some(actix_web::HttpResponse::Forbidden().body("unknown session token"));
+}
+
+#[get("/choosefile/{token}/{filename}")]
+async fn pick_local_file(path: web::Path<(String,String)>, session_token: Data<SessionToken>) -> impl Responder {
+    let (token, _filename) = path.into_inner();
+    if let Some(rejection) = reject_unknown_token(&session_token, &token) { return rejection; }
     let filepath = match backend::choose_file(exe_dir()).await {
         Some(pth) => wslify_windows_path(&pth.to_string_lossy().to_string()),
         None => "".to_string()
     };
-    format!("{filepath}")
+    actix_web::HttpResponse::Ok().body(format!("{filepath}"))
 }
 
-#[get("/choosefiles/{request_id}/{filename}")]
-async fn pick_local_files(path: web::Path<(String,String)>) -> impl Responder {
-    let (_request_id, _filename) = path.into_inner();
+#[get("/choosefiles/{token}/{filename}")]
+async fn pick_local_files(path: web::Path<(String,String)>, session_token: Data<SessionToken>) -> impl Responder {
+    let (token, _filename) = path.into_inner();
+    if let Some(rejection) = reject_unknown_token(&session_token, &token) { return rejection; }
     let filepaths = match backend::choose_files(exe_dir()).await {
         Some(pths) => pths.into_iter().map(|pth| wslify_windows_path(&pth.to_string_lossy().to_string())).collect::<Vec<_>>(),
         None => vec!{"".to_string()}
     };
-    format!("{:?}", filepaths)
+    actix_web::HttpResponse::Ok().body(format!("{:?}", filepaths))
 }
 
-#[get("/choosedirectory/{request_id}/{filename}")]
-async fn pick_local_directory(path: web::Path<(String,String)>) -> impl Responder {
-    let (_request_id, _filename) = path.into_inner();
+#[get("/choosedirectory/{token}/{filename}")]
+async fn pick_local_directory(path: web::Path<(String,String)>, session_token: Data<SessionToken>) -> impl Responder {
+    let (token, _filename) = path.into_inner();
+    if let Some(rejection) = reject_unknown_token(&session_token, &token) { return rejection; }
     let dirpath = match backend::choose_directory(exe_dir()).await {
         Some(pth) => wslify_windows_path(&pth.to_string_lossy().to_string()),
         None => "".to_string()
@@ -457,36 +586,84 @@ async fn pick_local_directory(path: web::Path<(String,String)>) -> impl Responde
     actix_web::HttpResponse::Ok().body(resp_body)
 }
 
-#[get("/choosedirectories/{request_id}/{filename}")]
-async fn pick_local_directories(path: web::Path<(String,String)>) -> impl Responder {
-    let (_request_id, _filename) = path.into_inner();
+#[get("/choosedirectories/{token}/{filename}")]
+async fn pick_local_directories(path: web::Path<(String,String)>, session_token: Data<SessionToken>) -> impl Responder {
+    let (token, _filename) = path.into_inner();
+    if let Some(rejection) = reject_unknown_token(&session_token, &token) { return rejection; }
     let filepaths = match backend::choose_directories(exe_dir()).await {
         Some(pths) => pths.into_iter().map(|pth| wslify_windows_path(&pth.to_string_lossy().to_string())).collect::<Vec<_>>(),
         None => vec!["".to_string()]
     };
-    format!("{:?}", filepaths)
+    actix_web::HttpResponse::Ok().body(format!("{:?}", filepaths))
+}
+
+#[post("/frontend/task/{token}")]
+async fn frontend_task(path: web::Path<String>,
+                       request: web::Json<elements_v1::FrontendTaskRequest>,
+                       chat_store: Data<SharedChatStore>,
+                       configuration_store: Data<SharedConfigurationStore>,
+                       comm_with_backend: Data<Mutex<Sender<BackendRequest>>>,
+                       session_token: Data<SessionToken>
+) -> impl Responder {
+    if let Some(rejection) = reject_unknown_token(&session_token, &path.into_inner()) { return rejection; }
+    if let Some(response) = backend::handle_configuration_request(&configuration_store, &request) {
+        return actix_web::HttpResponse::Ok().json(response);
+    }
+
+    let response = match backend::handle_chat_request(&chat_store, &request) {
+        Some((response, changed_channel)) => {
+            if let (Some(channel_id), Ok(sender)) = (changed_channel, comm_with_backend.lock()) {
+                sender.send(BackendRequest::InformChatChannelUpdated(channel_id)).ok();
+            }
+            response
+        },
+        None => {
+            return actix_web::HttpResponse::NotImplemented().finish();
+        }
+    };
+    actix_web::HttpResponse::Ok().json(response)
 }
 
-#[post("/config/json")]
-async fn download_json_config(body: String, // alternatively, web::Json<serde_json::Value>
+// where the user wants a configuration to be kept, from a container GUI or the native configuration form
+pub fn choose_configuration_target() -> Option<PathBuf> {
+    return rfd::FileDialog::new()
+            .set_directory(exe_dir().clone().unwrap_or_else(PathBuf::new))
+            .add_filter("JSON files", &["json"])
+            .add_filter("All files", &["*"])
+            .save_file();
+}
+
+#[post("/config/json/{token}")]
+async fn download_json_config(path: web::Path<String>,
+                              body: String, // alternatively, web::Json<serde_json::Value>
                               followup_page: Data<Mutex<AppState>>,
                               payload_sender: Data<Mutex<PathBuf>>,
                               this_servers_job_id: Data<Mutex<JobId>>,
-                              payload_receiver: Data<Mutex<Sender<BackendRequest>>>
+                              payload_receiver: Data<Mutex<Sender<BackendRequest>>>,
+                              gui_port: Data<Mutex<u16>>,
+                              session_token: Data<SessionToken>,
+                              configuration_schema: Data<Option<ConfigurationSchema>>
 ) -> impl Responder {
+    if let Some(rejection) = reject_unknown_token(&session_token, &path.into_inner()) { return rejection; }
     println!("Payload received: {}", &body.to_string());
     let followup_page = followup_page.lock().unwrap();
     let payload_sender = payload_sender.lock().unwrap();
     let jid = this_servers_job_id.lock().unwrap();
     let payload_receiver = payload_receiver.lock().unwrap();
 
-    let file_dest = rfd::FileDialog::new()
-    .set_directory(exe_dir().clone().unwrap_or_else(PathBuf::new))
-    .add_filter("JSON files", &["json"])
-    .add_filter("All files", &["*"])
-    .save_file();
+    // checked before the user is asked where to save it; containers without a schema only have to send JSON
+    let violations = match (parse_configuration(&body), configuration_schema.as_ref()) {
+        (Ok(configuration), Some(schema)) => schema.validate(&configuration),
+        (Ok(_), None) => Vec::new(),
+        (Err(violation), _) => vec![violation],
+    };
+    if !violations.is_empty() {
+        println!("Configuration rejected: {}", violations.iter().join("; "));
+        payload_receiver.send(BackendRequest::InformConfigurationRejected((*payload_sender).clone(), violations.clone())).ok();
+        return actix_web::HttpResponse::UnprocessableEntity().json(serde_json::json!({ "violations": violations }));
+    }
 
-    match file_dest {
+    match choose_configuration_target() {
         Some(pth) => {
             println!("Config accepted by user");
             payload_receiver.send(BackendRequest::SetAppState((*followup_page).clone())).ok();
@@ -500,20 +677,42 @@ async fn download_json_config(body: String, // alternatively, web::Json<serde_js
 
             payload_receiver.send(BackendRequest::AcceptConfiguration((*payload_sender).clone(), pth.to_string_lossy().to_string())).ok();
             payload_receiver.send(BackendRequest::StopProcess(jid.clone())).ok();
+
+            // the self-configurator GUI is done once the configuration has been stored
+            let terminate_address = format!("http://127.0.0.1:{}/terminate", *gui_port.lock().unwrap());
+            actix_web::rt::spawn(async move { reqwest::get(terminate_address).await.ok(); });
         },
         None => {println!("Config reception cancelled by user");}
     }
 
-    "response"
+    actix_web::HttpResponse::Ok().body("response")
 }
 
 
 
+//################################################################################
+//## Tests
+//################################################################################
 
-
-
-
-
-
-
-
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_session_token_is_accepted() {
+        let token = SessionToken::new();
+        let other = SessionToken::new();
+        assert_eq!(token.as_str().len(), 64);
+
+        assert!(token.matches(token.as_str()));
+        assert!(reject_unknown_token(&token, token.as_str()).is_none());
+
+        let mut last_byte_changed = token.as_str()[..63].to_string();
+        last_byte_changed.push(if token.as_str().ends_with('0') { '1' } else { '0' });
+        for candidate in [other.as_str(), last_byte_changed.as_str(), &token.as_str()[..63], &format!("{}0", token.as_str()), ""] {
+            assert!(!token.matches(candidate));
+            let rejection = reject_unknown_token(&token, candidate).unwrap();
+            assert_eq!(rejection.status(), actix_web::http::StatusCode::FORBIDDEN);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+
+
+
+use dioxus::prelude::*;
+use plugin_interface_elements::elements_v1::{ChannelId, ChatAuthor};
+
+use crate::backend::{BackendRequest, ChatChannel, FrontendCommChannel};
+
+
+// Lets the user answer questions that plugins or running containers ask through a chat channel
+#[component]
+pub fn ChatPanel(class: String, channels: Signal<Vec<ChatChannel>>, comm_with_backend: Signal<FrontendCommChannel>) -> Element {
+
+    let mut selected_channel = use_signal(|| None as Option<ChannelId>);
+    let mut draft = use_signal(String::new);
+
+    let open_channels = use_memo(move || {
+        channels().into_iter().filter(|channel| channel.open).collect::<Vec<_>>()
+    });
+
+    // fall back to the first channel waiting for an answer, if the selected one disappeared
+    let displayed_channel = use_memo(move || {
+        let channels = open_channels();
+        selected_channel()
+            .and_then(|id| channels.iter().find(|channel| channel.id == id).cloned())
+            .or_else(|| channels.iter().find(|channel| channel.awaits_answer()).cloned())
+            .or_else(|| channels.first().cloned())
+    });
+
+    let hidden = if open_channels().is_empty() { "hidden" } else { "" };
+
+    let mut send_answer = move || {
+        if let Some(channel) = displayed_channel() {
+            let text = draft();
+            if !text.trim().is_empty() {
+                comm_with_backend.read().send(BackendRequest::AnswerChatChannel(channel.id, text)).ok();
+                draft.set(String::new());
+            }
+        }
+    };
+
+    rsx! {
+        div {
+            class: format!("{class} chat-panel chat-panel-container {hidden}"),
+            div {
+                class: format!("{class} chat-panel channel-tabs"),
+                for channel in open_channels() {
+                    {
+                        let channel_id = channel.id;
+                        let selected = if displayed_channel().is_some_and(|displayed| displayed.id == channel_id) { "selected" } else { "" };
+                        let waiting = if channel.awaits_answer() { "awaits-answer" } else { "" };
+                        rsx! {
+                            div {
+                                class: format!("{class} chat-panel channel-tab {selected} {waiting}"),
+                                onclick: move |_| { selected_channel.set(Some(channel_id)); },
+                                {channel.title.clone()}
+                            }
+                        }
+                    }
+                }
+            }
+            {
+                match displayed_channel() {
+                    Some(channel) => {
+                        let channel_id = channel.id;
+                        rsx! {
+                            div {
+                                class: format!("{class} chat-panel history"),
+                                for message in channel.history.iter() {
+                                    {
+                                        let author = match message.author {
+                                            ChatAuthor::User => "user-message",
+                                            ChatAuthor::Plugin => "plugin-message",
+                                        };
+                                        let timestamp = message.creation_time.with_timezone(&chrono::Local).format("%H:%M:%S").to_string();
+                                        rsx! {
+                                            div {
+                                                class: format!("{class} chat-panel message {author}"),
+                                                span { class: format!("{class} chat-panel message-time"), "{timestamp}" }
+                                                span { class: format!("{class} chat-panel message-text"), {message.text.clone()} }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                            div {
+                                class: format!("{class} chat-panel answer-row"),
+                                input {
+                                    class: format!("{class} chat-panel answer-input"),
+                                    value: draft,
+                                    placeholder: "Answer...",
+                                    oninput: move |evt| draft.set(evt.value()),
+                                    onkeydown: move |evt| {
+                                        if evt.key() == Key::Enter { send_answer(); }
+                                    },
+                                }
+                                button {
+                                    class: format!("{class} chat-panel send-button primary-button"),
+                                    onclick: move |_| send_answer(),
+                                    {"Send"}
+                                }
+                                button {
+                                    class: format!("{class} chat-panel close-channel-button primary-button"),
+                                    onclick: move |_| {
+                                        comm_with_backend.read().send(BackendRequest::CloseChatChannel(channel_id)).ok();
+                                    },
+                                    {"Close"}
+                                }
+                            }
+                        }
+                    },
+                    None => rsx! { }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::path::PathBuf;
+
+use dioxus::prelude::*;
+
+use crate::backend::{BackendRequest, ConfigurationField, ConfigurationForm, FieldKind, FrontendCommChannel, SchemaViolation};
+use crate::backend::persistent_state::exe_dir;
+use crate::components::{RequestDirectoryPathButton2, RequestFilePathButton2};
+
+
+// Configures a container that has no GUI of its own, with a form built from its app-configurations schema.
+// Saving writes the configuration a container GUI would post; give the card a key per container, so the inputs are reset.
+#[component]
+pub fn ConfigurationFormCard(class: String, container: PathBuf, form: ConfigurationForm,
+                             configuration_path: Option<PathBuf>,
+                             violations: Vec<SchemaViolation>,       // as reported by the backend for the last save
+                             comm_with_backend: Signal<FrontendCommChannel>,
+                             on_use_configuration: EventHandler<PathBuf>) -> Element {
+
+    let initial_values = form.initial_values();
+    let mut values = use_signal(move || initial_values);
+    let mut form_violations = use_signal(|| Vec::new() as Vec<SchemaViolation>);
+
+    // the form's own findings are newer than what the backend reported for an earlier save
+    let shown_violations = if form_violations().is_empty() { violations } else { form_violations() };
+    let general_violations = shown_violations.iter()
+                                             .filter(|violation| !form.fields.iter().any(|field| field.pointer == violation.path))
+                                             .cloned()
+                                             .collect::<Vec<_>>();
+    let saved_form = form.clone();
+
+    rsx! {
+        div {
+            class: format!("{class} configuration-form-card cfc-container"),
+            h3 { {"Configuration"} }
+            if !general_violations.is_empty() {
+                ul {
+                    class: format!("{class} configuration-form-card violations"),
+                    for violation in general_violations {
+                        li { {violation.to_string()} }
+                    }
+                }
+            }
+            {form.fields.iter().map(|field| {
+                let pointer = field.pointer.clone();
+                let field_violations = shown_violations.iter()
+                                                       .filter(|violation| violation.path == field.pointer)
+                                                       .map(|violation| violation.message.clone())
+                                                       .collect::<Vec<_>>();
+                rsx! {
+                    ConfigurationFieldRow {
+                        key: "{field.pointer}",
+                        class: class.clone(),
+                        field: field.clone(),
+                        value: values().get(&field.pointer).cloned().unwrap_or_default(),
+                        violations: field_violations,
+                        on_change: move |text: String| values.with_mut(|values| { values.insert(pointer.clone(), text); }),
+                    }
+                }
+            })}
+            div {
+                class: format!("{class} configuration-form-card button-row"),
+                button {
+                    class: format!("{class} configuration-form-card primary-button"),
+                    onclick: move |_| {
+                        match saved_form.configuration(&values()) {
+                            Ok(configuration) => {
+                                form_violations.set(Vec::new());
+                                let text = serde_json::to_string_pretty(&configuration).unwrap_or_default();
+                                comm_with_backend.read().send(BackendRequest::StoreConfiguration(container.clone(), text)).ok();
+                            },
+                            Err(violations) => form_violations.set(violations),
+                        }
+                    },
+                    {"Save configuration"}
+                }
+                if let Some(path) = configuration_path.clone() {
+                    button {
+                        class: format!("{class} configuration-form-card secondary-button"),
+                        onclick: move |_| on_use_configuration.call(path.clone()),
+                        {"Pass as argument"}
+                    }
+                }
+            }
+            if let Some(path) = configuration_path {
+                p {
+                    class: format!("{class} configuration-form-card saved-path"),
+                    {format!("Configuration: {}", path.to_string_lossy())}
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ConfigurationFieldRow(class: String, field: ConfigurationField, value: String, violations: Vec<String>,
+                         on_change: EventHandler<String>) -> Element {
+
+    let starting_dir = use_signal(|| exe_dir().clone());
+    let title = if field.required { format!("{} *", field.title) } else { field.title.clone() };
+    let indent = format!("margin-left: {}rem;", field.depth);
+
+    rsx! {
+        div {
+            class: format!("{class} configuration-form-card field"),
+            style: indent,
+            if field.kind == FieldKind::Group {
+                h4 { class: format!("{class} configuration-form-card group-title"), {title} }
+            } else {
+                label { class: format!("{class} configuration-form-card field-title"), {title} }
+            }
+            if let Some(description) = field.description.clone() {
+                p { class: format!("{class} configuration-form-card description"), {description} }
+            }
+            {match field.kind.clone() {
+                FieldKind::Group => rsx! { },
+                FieldKind::Text | FieldKind::Integer | FieldKind::Number => rsx! {
+                    input {
+                        class: format!("{class} configuration-form-card input-field"),
+                        r#type: if field.kind == FieldKind::Text { "text" } else { "number" },
+                        step: if field.kind == FieldKind::Integer { "1" } else { "any" },
+                        value,
+                        oninput: move |event| on_change.call(event.value()),
+                    }
+                },
+                FieldKind::Boolean => rsx! {
+                    input {
+                        r#type: "checkbox",
+                        checked: value == "true",
+                        onchange: move |event| on_change.call(event.checked().to_string()),
+                    }
+                },
+                FieldKind::Choice(options) => rsx! {
+                    select {
+                        class: format!("{class} configuration-form-card input-field"),
+                        value: value.clone(),
+                        onchange: move |event| on_change.call(event.value()),
+                        option { value: "", selected: value.is_empty(), {""} }
+                        for choice in options {
+                            option {
+                                value: choice.to_string(),
+                                selected: choice.to_string() == value,
+                                {choice.as_str().map(str::to_string).unwrap_or_else(|| choice.to_string())}
+                            }
+                        }
+                    }
+                },
+                FieldKind::FilePath | FieldKind::DirectoryPath => rsx! {
+                    div {
+                        class: format!("{class} configuration-form-card row"),
+                        input {
+                            class: format!("{class} configuration-form-card input-field"),
+                            r#type: "text",
+                            value,
+                            oninput: move |event| on_change.call(event.value()),
+                        }
+                        if field.kind == FieldKind::FilePath {
+                            RequestFilePathButton2 {
+                                class: format!("{class} file-input"),
+                                starting_dir,
+                                result_handler: move |ptb: PathBuf| on_change.call(ptb.to_string_lossy().to_string()),
+                            }
+                        } else {
+                            RequestDirectoryPathButton2 {
+                                class: format!("{class} file-input"),
+                                starting_dir,
+                                result_handler: move |ptb: PathBuf| on_change.call(ptb.to_string_lossy().to_string()),
+                            }
+                        }
+                    }
+                },
+                FieldKind::TextList | FieldKind::Json => rsx! {
+                    textarea {
+                        class: format!("{class} configuration-form-card text-area"),
+                        placeholder: if field.kind == FieldKind::TextList { "One item per line" } else { "JSON" },
+                        value,
+                        oninput: move |event| on_change.call(event.value()),
+                    }
+                },
+            }}
+            for message in violations {
+                p { class: format!("{class} configuration-form-card error-message"), {message} }
+            }
+        }
+    }
+}
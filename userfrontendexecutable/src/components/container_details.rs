@@ -0,0 +1,102 @@
+
+
+
+
+use dioxus::prelude::*;
+
+use crate::backend::{ContainerDigest, ContainerInspection};
+
+
+// Structured view of what `inspect --all` reported, for the container or its selected app
+#[component]
+pub fn ContainerDetailsCard(class: String, inspection: Option<ContainerInspection>, digest: Option<ContainerDigest>, selected_app: Option<String>) -> Element {
+
+    let Some(inspection) = inspection else { return rsx! { }; };
+
+    let app = selected_app.as_ref().and_then(|name| inspection.apps.get(name)).cloned();
+    let help = match &app {
+        Some(app) => app.help.clone(),
+        None => inspection.help.clone(),
+    };
+    let labels = match &app {
+        Some(app) => app.labels.clone().into_iter().collect::<Vec<_>>(),
+        None => inspection.user_labels().map(|(k, v)| (k.clone(), v.clone())).collect::<Vec<_>>(),
+    };
+    let environment = match &app {
+        Some(app) if !app.environment.is_empty() => app.environment.clone().into_iter().collect::<Vec<_>>(),
+        _ => inspection.environment.clone().into_iter().collect::<Vec<_>>(),
+    };
+    let runscript = app.as_ref().and_then(|app| app.runscript.clone()).or_else(|| inspection.runscript.clone());
+
+    let build_info = match (&inspection.build_date, &inspection.built_with) {
+        (Some(date), Some(version)) => format!("Built {} with {}", date, version),
+        (Some(date), None) => format!("Built {}", date),
+        (None, Some(version)) => format!("Built with {}", version),
+        (None, None) => String::new(),
+    };
+
+    rsx! {
+        div {
+            class: format!("{class} container-details container-details-card"),
+            h3 {
+                class: format!("{class} container-details title"),
+                {selected_app.clone().unwrap_or_else(|| "Container details".to_string())}
+            }
+            if !build_info.is_empty() {
+                p { class: format!("{class} container-details build-info"), "{build_info}" }
+            }
+            {digest.map(|digest| rsx! {
+                p {
+                    class: format!("{class} container-details digest"),
+                    title: "{digest}",
+                    {format!("Image sha256:{}…", digest.short())}
+                }
+            })}
+            {help.map(|help| rsx! {
+                p { class: format!("{class} container-details help-text"), "{help}" }
+            })}
+            if !labels.is_empty() {
+                div {
+                    class: format!("{class} container-details key-value-list labels"),
+                    for (key, value) in labels {
+                        div {
+                            class: format!("{class} container-details key-value-row"),
+                            span { class: format!("{class} container-details key"), "{key}" }
+                            span { class: format!("{class} container-details value"), "{value}" }
+                        }
+                    }
+                }
+            }
+            if !environment.is_empty() {
+                details {
+                    class: format!("{class} container-details section"),
+                    summary { {"Environment"} }
+                    div {
+                        class: format!("{class} container-details key-value-list environment"),
+                        for (key, value) in environment {
+                            div {
+                                class: format!("{class} container-details key-value-row"),
+                                span { class: format!("{class} container-details key"), "{key}" }
+                                span { class: format!("{class} container-details value"), "{value}" }
+                            }
+                        }
+                    }
+                }
+            }
+            {runscript.map(|runscript| rsx! {
+                details {
+                    class: format!("{class} container-details section"),
+                    summary { {"Runscript"} }
+                    pre { class: format!("{class} container-details script"), "{runscript}" }
+                }
+            })}
+            {inspection.deffile.clone().map(|deffile| rsx! {
+                details {
+                    class: format!("{class} container-details section"),
+                    summary { {"Definition file"} }
+                    pre { class: format!("{class} container-details script"), "{deffile}" }
+                }
+            })}
+        }
+    }
+}
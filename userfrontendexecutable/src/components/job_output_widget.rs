@@ -1,75 +1,87 @@
 
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use dioxus::prelude::*;
-use crate::backend::{JobId, FrontendCommChannel, vec_to_html_multiline};
+use crate::backend::{first_open_line, JobId, JobOutputLine, OutputStream};
 
 
 #[component]
-pub fn JobOutputWidget(class: String, job_id: JobId, working_directory: Memo<Option<PathBuf>>, displayed_text: Signal<Vec<String>>,comm_with_backend: Signal<FrontendCommChannel>) -> Element {
+pub fn JobOutputWidget(class: String, job_id: JobId, working_directory: Option<PathBuf>, job_outputs: Signal<HashMap<JobId, Vec<JobOutputLine>>>) -> Element {
 
     // displayed text needs to be injected since the viewed job may change,
     // or a job may stop to exist
     // and then this a render pass might interact with a signal that has been dropped => causes crashes
     // "Do not use 'use_signal' and the like conditionally"
-    let backend_output = displayed_text;
-
-    let log_directory_message = use_memo(move || {
-
-        if working_directory().is_some_and(|dir| dir.is_dir()) {
-            let dir = working_directory().unwrap();
-            format!("Logs will be written to {dir:?}")
-        } else {
-            format!("Warning: no valid direcotry to write logs to. Please adjust your selected working directory.")
-        }
-    });
-
-    let _update_lines = use_future(move || async move {
-
-        // initially, send all lines
-        comm_with_backend.read().send(crate::BackendRequest::SendJobOutput(job_id, 0)).ok();
-
-        loop {
-            let now = tokio::time::Instant::now();
-            let dt = std::time::Duration::from_millis(200);
-            tokio::time::sleep_until(now + dt).await;
-
-            //println!("Number of Lines of displayed output: {}", backend_output().len());
-
-            //match comm_with_backend.read().try_receive() {
-//                Ok(msg) => {
-//                    match msg {
-//                        BackendResponse::JobOutput(backend_job_id, output) if backend_job_id == job_id => {
-//                            backend_output.with_mut(move |lines| {
-//                                for new_line in output.into_iter() {
-//                                    lines.push(new_line);
-//                                }
-//                            });
-//                            comm_with_backend.read().send(crate::BackendRequest::SendJobOutput(job_id, backend_output().len())).ok();
-//                        },
-//                        other_msg => {
-//                            // here single consumer abstraction fails
-//                            println!("Received other message! {:?}", &other_msg);
-//                            comm_with_backend.read().reinsert_message(other_msg).ok();
-//                        }
-//                    }
-//                },
-//                _ => {}
-//            }
-        }
-    });
+    // the output of all jobs is kept by the page, which also requests it from the backend
+    let backend_output = use_memo(move || job_outputs().get(&job_id).cloned().unwrap_or_default());
+    // jobs attached to a pseudo-terminal only have terminal rows, the open ones are its current screen
+    let is_terminal = use_memo(move || backend_output().iter().any(|line| line.stream == OutputStream::Terminal));
+    let screen_start = use_memo(move || first_open_line(&backend_output()));
+    let mut stream_filter = use_signal(|| None as Option<OutputStream>);
+
+    let log_directory_message = if working_directory.as_ref().is_some_and(|dir| dir.is_dir()) {
+        format!("Logs will be written to {:?}", working_directory.unwrap())
+    } else {
+        format!("Warning: no valid direcotry to write logs to. Please adjust your selected working directory.")
+    };
 
     rsx! {
         div {
             class: format!("{class} job-output-widget job-output-container"),
-            p {
-                class: format!("{class} job-output-widget job-output"),
-                display: "block",
-                "{log_directory_message:?}" br {}
-                br{}
-                for elem in vec_to_html_multiline(backend_output()) {
-                    {elem}
+            if is_terminal() {
+                p {
+                    class: format!("{class} job-output-widget job-output"),
+                    display: "block",
+                    "{log_directory_message:?}"
+                }
+                pre {
+                    class: format!("{class} job-output-widget terminal-output"),
+                    div {
+                        class: format!("{class} job-output-widget terminal-scrollback"),
+                        for line in backend_output().into_iter().take(screen_start()) {
+                            span {
+                                class: format!("{class} job-output-widget terminal-line {}", line.stream.tag()),
+                                {format!("{}\n", line.text)}
+                            }
+                        }
+                    }
+                    div {
+                        class: format!("{class} job-output-widget terminal-screen"),
+                        for line in backend_output().into_iter().skip(screen_start()) {
+                            span {
+                                class: format!("{class} job-output-widget terminal-line {}", line.stream.tag()),
+                                {format!("{}\n", line.text)}
+                            }
+                        }
+                    }
+                }
+            } else {
+                div {
+                    class: format!("{class} job-output-widget stream-filter-row"),
+                    for (label, filter) in [("All", None), ("stdout", Some(OutputStream::Stdout)), ("stderr", Some(OutputStream::Stderr))] {
+                        button {
+                            class: if stream_filter() == filter { format!("{class} job-output-widget stream-filter-button selected") }
+                                   else { format!("{class} job-output-widget stream-filter-button") },
+                            onclick: move |_| stream_filter.set(filter),
+                            {label}
+                        }
+                    }
+                }
+                p {
+                    class: format!("{class} job-output-widget job-output"),
+                    display: "block",
+                    "{log_directory_message:?}" br {}
+                    br{}
+                    for line in backend_output().into_iter().filter(|line| stream_filter().is_none_or(|stream| stream == line.stream)) {
+                        span {
+                            class: format!("{class} job-output-widget job-output-line {}", line.stream.tag()),
+                            title: line.time.with_timezone(&chrono::Local).format("%H:%M:%S%.3f").to_string(),
+                            {line.text}
+                        }
+                        br {display: "block"}
+                    }
                 }
             }
         }
@@ -8,6 +8,11 @@ mod container_widgets;
 mod remote_directory_tree;
 mod frontend_util_code;
 mod job_output_widget;
+mod chat_panel;
+mod container_details;
+mod run_profile_editor;
+mod configuration_form;
+mod terminal_keys;
 
 pub use buttons::*;
 pub use icons::*;
@@ -15,3 +20,8 @@ pub use logos::*;
 pub use remote_directory_tree::*;
 pub use frontend_util_code::* ;
 pub use  job_output_widget::*;
+pub use chat_panel::*;
+pub use container_details::*;
+pub use run_profile_editor::*;
+pub use configuration_form::*;
+pub use terminal_keys::*;
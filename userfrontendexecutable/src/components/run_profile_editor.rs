@@ -0,0 +1,207 @@
+
+
+
+
+use std::path::PathBuf;
+
+use dioxus::prelude::*;
+
+use crate::backend::{BindMount, RunProfile};
+use crate::backend::persistent_state::exe_dir;
+use crate::components::RequestDirectoryPathButton2;
+
+
+// Edits the run profile of a container, or of one of its apps (which may also just use the container's).
+// Changes are kept as a draft until saved; give the card a key per container and app, so the draft is reset.
+#[component]
+pub fn RunProfileCard(class: String, app: Option<String>, profile: RunProfile, inherited: bool,
+                      on_save: EventHandler<Option<RunProfile>>) -> Element {
+
+    let initial_profile = profile.clone();
+    let mut draft = use_signal(move || initial_profile);
+    let mut use_container_profile = use_signal(move || inherited);
+    let starting_dir = use_signal(|| exe_dir().clone());
+
+    let is_app = app.is_some();
+    let editable = !(is_app && use_container_profile());
+    let changed = draft() != profile || use_container_profile() != inherited;
+    // the container's profile was checked when it was saved
+    let problem = if editable { draft().check().err() } else { None };
+    let savable = changed && problem.is_none();
+
+    rsx! {
+        div {
+            class: format!("{class} run-profile-card"),
+            h3 {
+                class: format!("{class} run-profile-card title"),
+                {match &app {
+                    Some(app) => format!("Run profile of {}", app),
+                    None => "Run profile".to_string(),
+                }}
+            }
+            if is_app {
+                label {
+                    class: format!("{class} run-profile-card checkbox-row"),
+                    input {
+                        r#type: "checkbox",
+                        checked: use_container_profile(),
+                        onchange: move |event| use_container_profile.set(event.checked()),
+                    }
+                    {"Use the container's profile"}
+                }
+            }
+            if editable {
+                div {
+                    class: format!("{class} run-profile-card section"),
+                    h4 { {"Bind mounts"} }
+                    for (index, bind) in draft().binds.into_iter().enumerate() {
+                        div {
+                            class: format!("{class} run-profile-card row"),
+                            input {
+                                class: format!("{class} run-profile-card input-field"),
+                                r#type: "text",
+                                placeholder: "Host path",
+                                value: bind.source.to_string_lossy().to_string(),
+                                oninput: move |event| draft.with_mut(|profile| profile.binds[index].source = PathBuf::from(event.value())),
+                            }
+                            RequestDirectoryPathButton2 {
+                                class: format!("{class} file-input"),
+                                starting_dir,
+                                result_handler: move |ptb| draft.with_mut(|profile| profile.binds[index].source = ptb),
+                            }
+                            input {
+                                class: format!("{class} run-profile-card input-field"),
+                                r#type: "text",
+                                placeholder: "Container path (same if empty)",
+                                value: bind.target.clone(),
+                                oninput: move |event| draft.with_mut(|profile| profile.binds[index].target = event.value()),
+                            }
+                            label {
+                                class: format!("{class} run-profile-card checkbox-row"),
+                                input {
+                                    r#type: "checkbox",
+                                    checked: bind.read_only,
+                                    onchange: move |event| draft.with_mut(|profile| profile.binds[index].read_only = event.checked()),
+                                }
+                                {"read-only"}
+                            }
+                            button {
+                                class: format!("{class} run-profile-card secondary-button"),
+                                onclick: move |_| draft.with_mut(|profile| { profile.binds.remove(index); }),
+                                {"Remove"}
+                            }
+                        }
+                    }
+                    button {
+                        class: format!("{class} run-profile-card secondary-button"),
+                        onclick: move |_| draft.with_mut(|profile| profile.binds.push(BindMount::default())),
+                        {"Add mount"}
+                    }
+                }
+                div {
+                    class: format!("{class} run-profile-card section"),
+                    h4 { {"Environment"} }
+                    for (index, (name, value)) in draft().environment.into_iter().enumerate() {
+                        div {
+                            class: format!("{class} run-profile-card row"),
+                            input {
+                                class: format!("{class} run-profile-card input-field"),
+                                r#type: "text",
+                                placeholder: "Name",
+                                value: name,
+                                oninput: move |event| draft.with_mut(|profile| profile.environment[index].0 = event.value()),
+                            }
+                            input {
+                                class: format!("{class} run-profile-card input-field"),
+                                r#type: "text",
+                                placeholder: "Value",
+                                value: value,
+                                oninput: move |event| draft.with_mut(|profile| profile.environment[index].1 = event.value()),
+                            }
+                            button {
+                                class: format!("{class} run-profile-card secondary-button"),
+                                onclick: move |_| draft.with_mut(|profile| { profile.environment.remove(index); }),
+                                {"Remove"}
+                            }
+                        }
+                    }
+                    button {
+                        class: format!("{class} run-profile-card secondary-button"),
+                        onclick: move |_| draft.with_mut(|profile| profile.environment.push((String::new(), String::new()))),
+                        {"Add variable"}
+                    }
+                }
+                div {
+                    class: format!("{class} run-profile-card section"),
+                    h4 { {"Isolation"} }
+                    label {
+                        class: format!("{class} run-profile-card checkbox-row"),
+                        input {
+                            r#type: "checkbox",
+                            checked: draft().clean_env,
+                            onchange: move |event| draft.with_mut(|profile| profile.clean_env = event.checked()),
+                        }
+                        {"Clean environment (--cleanenv)"}
+                    }
+                    label {
+                        class: format!("{class} run-profile-card checkbox-row"),
+                        input {
+                            r#type: "checkbox",
+                            checked: draft().contain_all,
+                            onchange: move |event| draft.with_mut(|profile| profile.contain_all = event.checked()),
+                        }
+                        {"Contain everything (--containall)"}
+                    }
+                    div {
+                        class: format!("{class} run-profile-card row"),
+                        input {
+                            class: format!("{class} run-profile-card input-field"),
+                            r#type: "text",
+                            placeholder: "Overlay (temporary if empty)",
+                            value: draft().overlay.map(|overlay| overlay.to_string_lossy().to_string()).unwrap_or_default(),
+                            oninput: move |event| {
+                                let overlay = event.value();
+                                draft.with_mut(|profile| profile.overlay = if overlay.is_empty() { None } else { Some(PathBuf::from(overlay)) });
+                            },
+                        }
+                    }
+                }
+                div {
+                    class: format!("{class} run-profile-card section"),
+                    h4 { {"Output"} }
+                    label {
+                        class: format!("{class} run-profile-card checkbox-row"),
+                        input {
+                            r#type: "checkbox",
+                            checked: draft().terminal,
+                            onchange: move |event| draft.with_mut(|profile| profile.terminal = event.checked()),
+                        }
+                        {"Run in a terminal (progress bars are shown as on a console, stdout and stderr are mixed)"}
+                    }
+                }
+            }
+            if let Some(problem) = problem {
+                p { class: format!("{class} run-profile-card error-message"), {problem} }
+            }
+            div {
+                class: format!("{class} run-profile-card row"),
+                button {
+                    class: if savable { format!("{class} run-profile-card primary-button") } else { format!("{class} run-profile-card primary-button disabled-button") },
+                    onclick: move |_| {
+                        if !savable { return; }
+                        if is_app && use_container_profile() { on_save.call(None); } else { on_save.call(Some(draft())); }
+                    },
+                    {"Save profile"}
+                }
+                button {
+                    class: format!("{class} run-profile-card secondary-button"),
+                    onclick: move |_| {
+                        draft.set(RunProfile::default());
+                        use_container_profile.set(is_app);
+                    },
+                    {"Reset to defaults"}
+                }
+            }
+        }
+    }
+}
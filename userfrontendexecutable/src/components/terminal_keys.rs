@@ -0,0 +1,54 @@
+use dioxus::prelude::Key;
+
+
+
+//################################################################################
+//## Terminal keys
+//## (key presses on a page showing a terminal are passed on to the job behind it)
+//################################################################################
+
+// what a terminal sends for a key press; None for keys the page should handle itself
+pub fn terminal_keys(key: &Key, ctrl: bool) -> Option<String> {
+    return match key {
+        Key::Character(text) if ctrl => {
+            let letter = text.chars().next()?.to_ascii_uppercase();
+            if letter.is_ascii_uppercase() { Some(((letter as u8 - b'A' + 1) as char).to_string()) } else { None }
+        },
+        Key::Character(text) => Some(text.clone()),
+        Key::Enter => Some("\r".to_string()),
+        Key::Backspace => Some("\u{7f}".to_string()),
+        Key::Tab => Some("\t".to_string()),
+        Key::Escape => Some("\u{1b}".to_string()),
+        Key::ArrowUp => Some("\u{1b}[A".to_string()),
+        Key::ArrowDown => Some("\u{1b}[B".to_string()),
+        Key::ArrowRight => Some("\u{1b}[C".to_string()),
+        Key::ArrowLeft => Some("\u{1b}[D".to_string()),
+        Key::Home => Some("\u{1b}[H".to_string()),
+        Key::End => Some("\u{1b}[F".to_string()),
+        Key::Delete => Some("\u{1b}[3~".to_string()),
+        _ => None,
+    };
+}
+
+
+
+//################################################################################
+//## Tests
+//################################################################################
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_sent_as_a_terminal_would() {
+        assert_eq!(terminal_keys(&Key::Character("a".to_string()), false).as_deref(), Some("a"));
+        assert_eq!(terminal_keys(&Key::Character("c".to_string()), true).as_deref(), Some("\u{3}"));
+        assert_eq!(terminal_keys(&Key::Character("D".to_string()), true).as_deref(), Some("\u{4}"));
+        assert_eq!(terminal_keys(&Key::Character("1".to_string()), true), None);
+        assert_eq!(terminal_keys(&Key::Enter, false).as_deref(), Some("\r"));
+        assert_eq!(terminal_keys(&Key::ArrowUp, false).as_deref(), Some("\u{1b}[A"));
+        assert_eq!(terminal_keys(&Key::Delete, false).as_deref(), Some("\u{1b}[3~"));
+        assert_eq!(terminal_keys(&Key::Shift, false), None);
+    }
+}
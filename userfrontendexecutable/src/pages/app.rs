@@ -7,7 +7,7 @@ use crate::backend;
 use crate::pages::*;
 use crate::pages::colony_container_page::ColonyContainerPage;
 use crate::pages::local_container_page::LocalContainerPage;
-use crate::pages::general_container_page::GeneralContainerPage;
+use crate::pages::general_container_page::{GeneralContainerPage, use_container_page_jobs};
 use crate::JobId;
 
 
@@ -27,6 +27,8 @@ pub enum AppState {
     GenericObserverPage(JobId),
     ProjectOverviewPage,
     LabBookPage,
+    JobHistoryPage,
+    ContainerShellPage(PathBuf, PathBuf), // working directory, container path
     //HTMLPage(String),
     PluginPage(Plugin),
     IFrame(String),
@@ -46,6 +48,7 @@ pub fn CompleteApp() -> Element {
     // single thread communication (on frontend)
     let app_state = use_signal(|| AppState::InstallationPage as AppState);
     let senderhandle = use_signal(move || frontcomm);
+    let container_page_jobs = use_container_page_jobs();
 
 //    let mut yrn_font_file = std::fs::File::open(YARNDINGS_FONT).expect("Unable to open font file asset");
 //    let mut yrn_font = String::new();
@@ -71,6 +74,8 @@ pub fn CompleteApp() -> Element {
         style { {include_str!("./../../public/styles/remote_dir_test.css")}}
         style { {include_str!("./../../public/styles/project_overview_page.css")}}
         style { {include_str!("./../../public/styles/project_page.css")}}
+        style { {include_str!("./../../public/styles/job_history_page.css")}}
+        style { {include_str!("./../../public/styles/container_shell_page.css")}}
 
         style { {include_str!("./../../public/styles/utilities.css")}}
 
@@ -132,7 +137,7 @@ pub fn CompleteApp() -> Element {
                 AppState::InstallationPage => rsx! { InstallPage {app_state, comm_with_backend: senderhandle} },
                 //AppState::EntryPage => rsx! { EntryPage {app_state, comm_with_backend: senderhandle} },
                 //redirected for now
-                AppState::EntryPage => rsx! { GeneralContainerPage {app_state, comm_with_backend: senderhandle} },
+                AppState::EntryPage => rsx! { GeneralContainerPage {app_state, comm_with_backend: senderhandle, page_jobs: container_page_jobs} },
                 AppState::LocalStartPage(_container_path) => rsx! { LocalContainerPage {app_state, comm_with_backend: senderhandle} },
                 AppState::ColonyStartPage(_container_path) => rsx! { ColonyContainerPage {app_state, comm_with_backend: senderhandle}  },
                 //AppState::LocalRunningPage(_container_path) => rsx! { ColonyContainerPage {app_state, comm_with_backend: senderhandle} },
@@ -144,6 +149,8 @@ pub fn CompleteApp() -> Element {
                 AppState::TestPage => rsx! { RemoteDirTestPage { app_state, comm_with_backend: senderhandle } },
                 AppState::ProjectOverviewPage => rsx! { ProjectOverviewPage { app_state, comm_with_backend: senderhandle } },
                 AppState::LabBookPage => rsx! { LabBookPage { app_state, comm_with_backend: senderhandle } },
+                AppState::JobHistoryPage => rsx! { JobHistoryPage { app_state, comm_with_backend: senderhandle } },
+                AppState::ContainerShellPage(workdir, container) => rsx! { ContainerShellPage { app_state, comm_with_backend: senderhandle, working_directory: workdir, container } },
             }
         }
     }
@@ -44,18 +44,15 @@ pub fn ColonyContainerPage(app_state: Signal<AppState>, comm_with_backend: Signa
                 let dt = std::time::Duration::from_millis(200);
                 tokio::time::sleep_until(now + dt).await;
                 match comm_with_backend.read().try_receive() {
-                    Ok(BackendResponse::LocalWebServerStarted(None)) => {
+                    Ok(BackendResponse::LocalWebServerStarted(Err(e))) => {
                         //TODO: webserver failed to start, give error message
+                        println!("Frontend-local webserver failed to start: {}", &e);
                         hide_config_self_button.set(false);
                     },
-                    Ok(BackendResponse::LocalWebServerStarted(Some(_port))) => {
-                        // portnumber (and later magic link suffix) need to be
-                        // transmitted to container at some point
+                    Ok(BackendResponse::LocalWebServerStarted(Ok(ports))) => {
                         // Configuration messages should not be possible to be received before this section is matched
-                        // send BackendRequest::GetWebServerAddress(JobId)
-                        // match on BackendResponse::WebServerAddress
-                        // set webserver_port to the contained value
-                        comm_with_backend.read().send(BackendRequest::RunSingularityApp(PathBuf::from("~"), container_send.clone(), "self-configurator".to_string(), vec!["--colony-interop".to_string()])).ok();
+                        webserver_port.set(Some(ports.gui));
+                        comm_with_backend.read().send(BackendRequest::RunSingularityApp(PathBuf::from("~"), container_send.clone(), "self-configurator".to_string(), ports.container_args(), None)).ok();
                     },
                     Ok(BackendResponse::ContainerWebServerStarted(None)) => {
                         //TODO: webserver failed to start, give error message
@@ -132,7 +129,7 @@ pub fn ColonyContainerPage(app_state: Signal<AppState>, comm_with_backend: Signa
                                 ];
                                 println!("Running with args: {:?}", args);
                                 comm_with_backend.read().sender.send(
-                                    BackendRequest::RunSingularity(workdir_path().clone(), selected_container().clone(), args)
+                                    BackendRequest::RunSingularity(workdir_path().clone(), selected_container().clone(), args, None)
                                 ).ok();
                             });
                         },
@@ -188,8 +185,8 @@ pub fn ConfigureSelfCard(class: String, app_state: Signal<AppState>, container:
                             },
                             None => {
                                 println!("No webserver running!");
+                                // the self-configurator is run once the server knows its ports
                                 comm_with_backend.read().send(BackendRequest::StartLocalWebServer(app_state().clone(), container().clone())).ok();
-                                comm_with_backend.read().send(BackendRequest::RunSingularityApp(PathBuf::from("~"), container().clone(), "self-configurator".to_string(), vec!["--colony-interop".to_string()])).ok();
                                 hide_config_self_button.set(true);
 
                             }
@@ -0,0 +1,156 @@
+
+
+
+use std::path::PathBuf;
+
+use dioxus::prelude::*;
+
+use crate::pages::*;
+use crate::components::*;
+use crate::backend::*;
+
+
+// a shell inside a container, with the mounts and working directory of its runs, for debugging interactively
+#[component]
+pub fn ContainerShellPage(app_state: Signal<AppState>, comm_with_backend: Signal<FrontendCommChannel>,
+                          working_directory: PathBuf, container: PathBuf) -> Element {
+
+    let shell_job = use_hook(JobId::new);
+    let mut shell_opened = use_signal(|| false);
+    let mut shell_state = use_signal(|| JobState::Queued);
+    let mut shell_error = use_signal(|| None as Option<String>);
+    let mut lines = use_signal(Vec::<JobOutputLine>::new);
+    let screen_start = use_memo(move || first_open_line(&lines()));
+
+    let container_name = container.file_name()
+                                  .map(|name| name.to_string_lossy().to_string())
+                                  .unwrap_or_else(|| "container".to_string());
+
+    // sent while the page is built, so it always reaches the backend before the page closes the shell again
+    let shell_workdir = working_directory.clone();
+    let shell_container = container.clone();
+    use_hook(move || {
+        comm_with_backend.read().send(BackendRequest::OpenContainerShell(shell_job, shell_workdir, shell_container)).ok();
+    });
+
+    let _poll_backend_loop = use_future(move || async move {
+        loop {
+            let now = tokio::time::Instant::now();
+            let dt = std::time::Duration::from_millis(200);
+            tokio::time::sleep_until(now + dt).await;
+
+            if shell_opened() {
+                comm_with_backend.read().send(BackendRequest::SendJobOutput(shell_job, screen_start())).ok();
+                if !shell_state().is_finished() {
+                    comm_with_backend.read().send(BackendRequest::SendJobInfo(shell_job)).ok();
+                }
+            }
+
+            // output and state are asked for together, so take all answers at once;
+            // the rest is left for the container page, which keeps polling the state and output of its jobs
+            let mut unrelated = Vec::new();
+            while let Ok(response) = comm_with_backend.read().try_receive() {
+                match response {
+                    BackendResponse::ContainerShellOpened(job_id, Ok(())) if job_id == shell_job => {
+                        shell_opened.set(true);
+                        shell_state.set(JobState::Running);
+                    },
+                    BackendResponse::ContainerShellOpened(job_id, Err(e)) if job_id == shell_job => {
+                        shell_error.set(Some(e));
+                    },
+                    BackendResponse::JobOutput(job_id, start, new_lines) if job_id == shell_job => {
+                        lines.with_mut(|lines| {
+                            lines.truncate(start);
+                            lines.extend(new_lines);
+                        });
+                    },
+                    BackendResponse::JobInfo(job_id, state) if job_id == shell_job => {
+                        shell_state.set(state);
+                    },
+                    BackendResponse::JobNotListed(job_id) if job_id == shell_job => {
+                        shell_state.set(JobState::Orphaned);
+                    },
+                    response => unrelated.push(response),
+                }
+            }
+            for response in unrelated {
+                comm_with_backend.read().reinsert_message(response).ok();
+            }
+        }
+    });
+
+    // the shell does not outlive the page, however it is left, also while it is still opening
+    use_drop(move || {
+        comm_with_backend.read().send(BackendRequest::CloseContainerShell(shell_job)).ok();
+    });
+
+    let type_keys = move |keys: String| {
+        if shell_opened() {
+            comm_with_backend.read().send(BackendRequest::SendJobInput(shell_job, JobInput::Keys(keys))).ok();
+        }
+    };
+
+    rsx! {
+        div {
+            class: "container-shell-page background",
+            div {
+                class: "container-shell-page logo-navbar-row",
+                div {
+                    class: "container-shell-page logo-row",
+                    IMILogo { class: "navbar-logo" }
+                }
+                div {
+                    class: "container-shell-page large-navbar",
+                    HomeButton {app_state, class: "container-shell-page"}
+                }
+            }
+            div {
+                class: "container-shell-page content-area",
+                div {
+                    class: "container-shell-page header-row",
+                    h3 {
+                        class: "container-shell-page title",
+                        {format!("Shell in {} at {}", &container_name, working_directory.to_string_lossy())}
+                    }
+                    button {
+                        class: "container-shell-page primary-button",
+                        onclick: move |_| app_state.set(AppState::EntryPage),
+                        {if shell_state().is_finished() { "Back" } else { "Close shell" }}
+                    }
+                }
+                match (shell_error(), shell_opened()) {
+                    (Some(e), _) => rsx! { p { class: "container-shell-page placeholder", {format!("The shell could not be opened: {}", e)} } },
+                    (None, false) => rsx! { p { class: "container-shell-page placeholder", "Opening shell..." } },
+                    (None, true) => rsx! {
+                        pre {
+                            class: if shell_state().is_finished() { "container-shell-page terminal finished" } else { "container-shell-page terminal" },
+                            tabindex: 0,
+                            autofocus: true,
+                            onkeydown: move |evt: KeyboardEvent| {
+                                if let Some(keys) = terminal_keys(&evt.key(), evt.modifiers().ctrl()) {
+                                    evt.prevent_default();
+                                    type_keys(keys);
+                                }
+                            },
+                            div {
+                                class: "container-shell-page terminal-scrollback",
+                                for line in lines().into_iter().take(screen_start()) {
+                                    {format!("{}\n", line.text)}
+                                }
+                            }
+                            div {
+                                class: "container-shell-page terminal-screen",
+                                for line in lines().into_iter().skip(screen_start()) {
+                                    {format!("{}\n", line.text)}
+                                }
+                            }
+                        }
+                        if shell_state().is_finished() {
+                            p { class: "container-shell-page placeholder", "The shell has exited." }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
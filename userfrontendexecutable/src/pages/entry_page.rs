@@ -13,6 +13,7 @@ use crate::backend::{FrontendCommChannel, BackendRequest, BackendResponse, Singu
 use crate::backend;
 use crate::SingularityResponse;
 use crate::backend::persistent_state::PersistentStateUpdate;
+use crate::backend::{ContainerRuntime, RuntimePreference};
 
 
 #[derive(Clone, Copy, PartialEq, Debug)]
@@ -30,6 +31,7 @@ pub fn EntryPage(app_state: Signal<AppState>, comm_with_backend: Signal<Frontend
 
     let mut container_paths = use_signal(|| None as Option<Vec<PathBuf>>);
     let mut last_selected_container_dir = use_signal(|| None as Option<PathBuf>);
+    let mut container_runtime = use_signal(RuntimePreference::default);
 
     let _init_paths = use_future(move || async move {
         println!("Initializing entry page state!");
@@ -46,6 +48,7 @@ pub fn EntryPage(app_state: Signal<AppState>, comm_with_backend: Signal<Frontend
                         BackendResponse::PersistentState(state) => {
                             println!("Received message with persistent state");
                             last_selected_container_dir.set(state.last_selected_container_dir);
+                            container_runtime.set(state.container_runtime);
                             let backend_container_pathss = state.containers.into_iter()
                                     .map(|bcd| bcd.path)
                                     .collect_vec();
@@ -333,6 +336,33 @@ pub fn EntryPage(app_state: Signal<AppState>, comm_with_backend: Signal<Frontend
                         },
                         {"Remove selected container"}
                     }
+                    div {
+                        class: "entry-page runtime-selection",
+                        label { class: "entry-page runtime-label", {"Container runtime"} }
+                        select {
+                            class: "entry-page runtime-select",
+                            onchange: move |evt| {
+                                let preference = ContainerRuntime::ALL.into_iter()
+                                                    .find(|runtime| runtime.binary() == evt.value())
+                                                    .map(RuntimePreference::Fixed)
+                                                    .unwrap_or(RuntimePreference::Automatic);
+                                container_runtime.set(preference);
+                                comm_with_backend.read().send(BackendRequest::UpdatePersistentState(PersistentStateUpdate::SetContainerRuntime(preference))).ok();
+                            },
+                            option {
+                                value: "automatic",
+                                selected: container_runtime() == RuntimePreference::Automatic,
+                                {"Automatic"}
+                            }
+                            for runtime in ContainerRuntime::ALL {
+                                option {
+                                    value: runtime.binary(),
+                                    selected: container_runtime() == RuntimePreference::Fixed(runtime),
+                                    {runtime.display_name()}
+                                }
+                            }
+                        }
+                    }
                 }
             }
         }
@@ -3,7 +3,7 @@
 
 
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use dioxus::prelude::*;
@@ -11,9 +11,7 @@ use itertools::Itertools;
 use tokio;
 use uuid::Uuid;
 extern crate chrono;
-use chrono::prelude::DateTime;
 use chrono::Utc;
-use std::time::{UNIX_EPOCH, Duration};
 
 use crate::pages::*;
 use crate::backend::*;
@@ -29,14 +27,24 @@ pub struct ContainerDescription {
     pub id: Uuid,
     pub path: PathBuf,
     pub title: Option<String>,
-    pub labels: Option<String>,
-    pub help_section: Option<String>,
-    pub apps: Option<Vec<String>>
+    pub digest: Option<ContainerDigest>,
+    pub inspection: Option<ContainerInspection>,
+    pub apps: Option<Vec<String>>,
+    pub run_help: Option<Option<String>>,       // requested when Main is first selected, Some(None) if there is no help text
+    pub app_help: HashMap<String, Option<String>>,          // app name, help text; requested when an app is first selected
+    pub run_labels: Option<BTreeMap<String, String>>,       // requested together with the help text
+    pub app_labels: HashMap<String, BTreeMap<String, String>>,
+    pub run_profile: RunProfile,
+    pub app_run_profiles: BTreeMap<String, RunProfile>,
+    pub configuration_form: Option<Option<ConfigurationForm>>,     // built from what the app-configurations app publishes, requested when the form is first opened; Some(None) if there is no usable schema
 }
 
 impl ContainerDescription {
     pub fn from_backend(bcd: crate::backend::persistent_state::BackendContainerDescription) -> Self {
-        return Self { id: bcd.id, path: bcd.path, title: None, labels: None, help_section: None, apps: None }
+        let digest = bcd.digest().cloned();
+        return Self { id: bcd.id, path: bcd.path, title: None, digest, inspection: None, apps: None, run_help: None, app_help: HashMap::new(),
+                      run_labels: None, app_labels: HashMap::new(),
+                      run_profile: bcd.run_profile, app_run_profiles: bcd.app_run_profiles, configuration_form: None }
     }
 }
 
@@ -54,15 +62,35 @@ pub enum ContainerPageUpdate {
 
 
 #[derive(Clone, PartialEq, Debug)]
-struct ContainerPageJobState {
+pub struct ContainerPageJobState {
     pub container_id: Uuid,
     pub container_path: PathBuf,
+    pub label: String,                          // what was run: "Main", the app's name, or another task
+    pub working_directory: Option<PathBuf>,     // the job's output log is written there
     pub job_id: JobId,
     pub state: crate::backend::JobState
 }
 
+// kept by the app, so the jobs are still listed after the job history or a container shell has been visited
+#[derive(Clone, Copy, PartialEq)]
+pub struct ContainerPageJobs {
+    pub jobs: Signal<Vec<ContainerPageJobState>>,           // in the order they were started
+    pub observed_job: Signal<Option<JobId>>,
+    pub job_outputs: Signal<HashMap<JobId, Vec<JobOutputLine>>>,
+    pub copy_files_job: Signal<Option<JobId>>,              // copies the configuration into the working directory
+}
+
+pub fn use_container_page_jobs() -> ContainerPageJobs {
+    return ContainerPageJobs {
+        jobs: use_signal(Vec::new),
+        observed_job: use_signal(|| None),
+        job_outputs: use_signal(HashMap::new),
+        copy_files_job: use_signal(|| None),
+    };
+}
+
 #[component]
-pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Signal<FrontendCommChannel>) -> Element {
+pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Signal<FrontendCommChannel>, page_jobs: ContainerPageJobs) -> Element {
 
     let mut containers = use_signal(|| None as Option<HashMap<Uuid, ContainerDescription>>);
     let container_path_to_id = use_memo(move|| {
@@ -81,10 +109,12 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
     let mut workdir_paths = use_signal(|| HashMap::new() as HashMap<Uuid, Option<PathBuf>>);
     let mut config_paths = use_signal(|| HashMap::new() as HashMap<Uuid, Option<PathBuf>>);
     //TODO: args must be chosen both per container and per app
-    let container_args = use_signal(|| HashMap::new() as HashMap<Uuid, Vec<(i32, String)>>);
+    let mut container_args = use_signal(|| HashMap::new() as HashMap<Uuid, Vec<(i32, String)>>);
+    let mut configuration_violations = use_signal(|| HashMap::new() as HashMap<Uuid, Vec<SchemaViolation>>);
+    let mut opened_configuration_forms = use_signal(|| HashSet::new() as HashSet<Uuid>);
 
-    let mut running_job = use_signal(|| None as Option<ContainerPageJobState>);
-    let mut copy_files_job = use_signal(|| None as Option<crate::backend::JobId>);
+    let ContainerPageJobs { mut jobs, mut observed_job, mut job_outputs, mut copy_files_job } = page_jobs;
+    let mut chat_channels = use_signal(|| Vec::new() as Vec<ChatChannel>);
 
     let container_paths = use_memo(move || {
         let result = containers().map(|store| store.values().map(|descr| descr.path.clone()).collect_vec() );
@@ -113,49 +143,36 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
     });
 
     let mut last_selected_container_dir = use_signal(|| None as Option<PathBuf>);
-    let mut webserver_port = use_signal(|| None as Option<u16>);
-    let job_observer_visible = use_signal(|| false);
-    let mut backend_output = use_signal(|| Vec::new() as Vec<String>);
-
-    let _clear_backend_output = use_effect(move || {
-        if running_job().is_none() {
-            backend_output.with_mut(|v| {
-                // writing existing output_to file
-                let outputfile_dir = selected_cont_workdir().or_else(|| exe_dir().clone()).expect("Could not determine File to write logs to.");
-
-                // Try with timestamp first
-                let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
-                let mut filename = format!("raw_output_{}.log", timestamp);
-
-                let mut counter = 1;
-                let mut path = PathBuf::from(&outputfile_dir);
-                path.push(filename);
-
-                // If somehow file exists already, bump counter
-                while path.exists() {
-                    filename = format!("raw_output_{}_{}.log", timestamp, counter);
-                    path = PathBuf::from(&filename);
-                    counter += 1;
-                }
+    let mut self_configurator_ports = use_signal(|| None as Option<SelfConfiguratorPorts>);
+    let mut webserver_error = use_signal(|| None as Option<String>);
+    let mut job_observer_visible = use_signal(|| false);
+    let mut job_stop_failure = use_signal(|| None as Option<String>);
 
-                // Create file (fails if someone else just created it in between)
-                let mut file = OpenOptions::new()
-                    .write(true)
-                    .create_new(true) // fail if exists
-                    .open(&path);
-
-                if file.is_ok() {
-                    let mut writer = BufWriter::new(file.unwrap());
-                    for line in v.iter() {
-                        writer.write_all(line.as_bytes()).ok();
-                        writer.write_all(b"\n").ok();
-                    }
-                }
+    let observed_job_state = use_memo(move || {
+        observed_job().and_then(|job_id| jobs().into_iter().find(|job| job.job_id == job_id))
+    });
+    let running_job_count = use_memo(move || jobs().iter().filter(|job| !job.state.is_finished()).count());
+    let selected_container_busy = use_memo(move || {
+        selected_container().is_some_and(|id| jobs().iter().any(|job| job.container_id == id && !job.state.is_finished()))
+    });
 
-                v.clear();
-            })
+    // a dismissed job is forgotten, after its output has been written to a log file
+    let mut dismiss_job = move |job_id: JobId| {
+        let dismissed = jobs().into_iter().find(|job| job.job_id == job_id);
+        jobs.with_mut(|jobs| jobs.retain(|job| job.job_id != job_id));
+        let output = job_outputs.with_mut(|outputs| outputs.remove(&job_id)).unwrap_or_default();
+
+        if let Some(job) = dismissed {
+            let log_dir = job.working_directory.filter(|dir| dir.is_dir()).or_else(|| exe_dir().clone());
+            match log_dir {
+                Some(dir) => write_job_log(&dir, &output),
+                None => println!("Could not determine directory to write the log of job {:?} to", &job_id),
+            }
         }
-    });
+        if observed_job().is_some_and(|observed| observed == job_id) {
+            observed_job.set(jobs().last().map(|job| job.job_id));
+        }
+    };
 
     let _poll_backend_loop = use_future(move || {  async move {
             println!("Initializing entry page state!");
@@ -163,6 +180,7 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                 println!("Sending Backend Request for Initialization");
                 comm_with_backend.read().send(BackendRequest::ReadPersistentState).ok();
             }
+            comm_with_backend.read().send(BackendRequest::ReadChatChannels).ok();
 
             loop {
                 let now = tokio::time::Instant::now();
@@ -181,7 +199,8 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                                 .for_each(|(id, mut descr)| {
                                     let container_path = descr.path.clone();
                                     spawn(async move {
-                                        comm_with_backend.read().send(BackendRequest::QuerySingularity(container_path, SingularityQuery::AppList)).ok();
+                                        comm_with_backend.read().send(BackendRequest::QuerySingularity(container_path.clone(), SingularityQuery::AppList)).ok();
+                                        comm_with_backend.read().send(BackendRequest::QuerySingularity(container_path, SingularityQuery::Inspection)).ok();
                                     });
                                     if descr.title.is_none() {
                                         let title = descr.path.components().last()
@@ -194,16 +213,13 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                         last_selected_container_dir.set(state.last_selected_container_dir);
                         println!("Processed message with persistent state");
                     },
-                    Ok(BackendResponse::LocalWebServerStarted(None)) => {
-                        //TODO: webserver failed to start, give error message
-                        //todo!();
-                        webserver_port.set(None);
+                    Ok(BackendResponse::LocalWebServerStarted(Err(e))) => {
+                        self_configurator_ports.set(None);
+                        webserver_error.set(Some(e));
                     },
-                    Ok(BackendResponse::LocalWebServerStarted(Some(port))) => {
-                        //TODO: webserver successfully started, ignore for now
+                    Ok(BackendResponse::LocalWebServerStarted(Ok(ports))) => {
                         //TODO: start-self-configurator button should turn into a spinning icon now, at latest
-                        //todo!();
-                        webserver_port.set(Some(port));
+                        self_configurator_ports.set(Some(ports));
                     },
                     Ok(BackendResponse::ContainerWebServerStarted(None)) => {
                         //TODO: webserver failed to start, give error message
@@ -223,6 +239,16 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                             None => {},
                             Some(id) => {
                                 config_paths.write().insert(id, Some(PathBuf::from(configuration)));
+                                configuration_violations.write().remove(&id);
+                            }
+                        }
+                    },
+                    Ok(BackendResponse::ConfigurationRejected(target_container, violations)) => {
+                        let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
+                        match cont_id {
+                            None => {},
+                            Some(id) => {
+                                configuration_violations.write().insert(id, violations);
                             }
                         }
                     },
@@ -243,13 +269,38 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                                 store_maybeinit.as_mut().unwrap().insert(container_id, new_container);
                                 println!("Added new container at: {:?}", &container_path);
                                 spawn(async move {
-                                    comm_with_backend.read().send(BackendRequest::QuerySingularity(container_path, SingularityQuery::AppList)).ok();
+                                    comm_with_backend.read().send(BackendRequest::QuerySingularity(container_path.clone(), SingularityQuery::AppList)).ok();
+                                    comm_with_backend.read().send(BackendRequest::QuerySingularity(container_path, SingularityQuery::Inspection)).ok();
                                 });
                             } else {
                                 println!("Could not add container; store had not been initialized");
                             }
                         });
                     },
+                    Ok(BackendResponse::ContainerIdentified(bcd)) => {
+                        containers.with_mut(|store_option| {
+                            if let Some(ref mut store) = store_option {
+                                store.entry(bcd.id).and_modify(|descr| descr.digest = bcd.digest().cloned());
+                            }
+                        });
+                    },
+                    Ok(BackendResponse::ContainerMoved(removed, kept)) => {
+                        println!("Container {:?} was already registered as {:?}", &removed, &kept.id);
+                        containers.with_mut(|store_option| {
+                            if let Some(ref mut store) = store_option {
+                                let duplicate = store.remove(&removed);
+                                store.entry(kept.id).and_modify(|descr| {
+                                    // moved or renamed: the details read from the new file are just as valid
+                                    descr.path = kept.path.clone();
+                                    descr.title = duplicate.and_then(|dup| dup.title).or(descr.title.take());
+                                    descr.digest = kept.digest().cloned();
+                                });
+                            }
+                        });
+                        if selected_container().is_some_and(|selected| selected == removed) {
+                            selected_container.set(Some(kept.id));
+                        }
+                    },
                     Ok(BackendResponse::SingularityInfo(target_container, Some(SingularityResponse::AppList(app_list)))) => {
                         let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
 
@@ -264,77 +315,185 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                             }
                         }
                     },
-                    Ok(BackendResponse::JobInfo(job_id, job_state)) => {
-                        //TODO: abstraction is wrong, incorrect information may be displayed
-                        //TODO: what if the user attempts to run multiple jobs simultaneously?
-
-                        //secondly, request new update
-                        match job_state {
-                            //do nothing
-                            JobState::Completed(_) => {
-                                if copy_files_job().is_some() && running_job().is_some() {
-                                    if copy_files_job().unwrap() == running_job().unwrap().job_id &&
-                                        selected_container().is_some_and(|cont_id| cont_id == running_job().unwrap().container_id)
-                                    {
-                                        copy_files_job.set(None);
-                                        match (selected_cont_workdir(), selected_cont_config()) {
-                                            (Some(workdir), Some(config_file)) => {
-                                                if config_file.components().last().is_some() {
-                                                    let config_file_name = config_file.components().last().unwrap().clone();
-                                                    let mut new_config_path = workdir.clone();
-                                                    new_config_path.push(config_file_name.clone());
-
-                                                    config_paths.with_mut(|store| {
-                                                        store.insert(selected_container().unwrap(), Some(new_config_path));
-                                                    });
-                                                }
-                                            },
-                                            _ => {}
-                                        };
+                    Ok(BackendResponse::SingularityInfo(target_container, Some(SingularityResponse::AppConfigurationOptions(options)))) => {
+                        let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
+                        // failures are remembered as well, so the container is not run again for them
+                        let form = match options.ok_or_else(|| "The app-configurations app did not answer".to_string())
+                                                .and_then(|options| ConfigurationSchema::parse(&options))
+                                                .and_then(ConfigurationForm::from_schema) {
+                            Ok(form) => Some(form),
+                            Err(e) => { println!("No configuration form for {:?}: {}", &target_container, e); None }
+                        };
+
+                        match cont_id {
+                            None => {},
+                            Some(id) => {
+                                containers.with_mut(|store_option| {
+                                    if let Some(ref mut store) = store_option {
+                                        store.entry(id).and_modify(|descr| descr.configuration_form = Some(form));
                                     }
-                                    // else do not note anything has changed
-                                }
-                            },
-                            JobState::JobNotListed => {
-                                if running_job().is_some_and(|job| job.job_id == job_id ) { running_job.set(None); }
+                                });
+                            }
+                        }
+                    },
+                    Ok(BackendResponse::SingularityInfo(target_container, Some(SingularityResponse::Inspection(inspection)))) => {
+                        let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
+
+                        match cont_id {
+                            None => {},
+                            Some(id) => {
+                                containers.with_mut(|store_option| {
+                                    if let Some(ref mut store) = store_option {
+                                        store.entry(id).and_modify(|descr| descr.inspection = Some(inspection));
+                                    }
+                                });
+                            }
+                        }
+                    },
+                    Ok(BackendResponse::SingularityInfo(target_container, Some(SingularityResponse::RunHelp(help)))) => {
+                        let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
+
+                        match cont_id {
+                            None => {},
+                            Some(id) => {
+                                containers.with_mut(|store_option| {
+                                    if let Some(ref mut store) = store_option {
+                                        store.entry(id).and_modify(|descr| descr.run_help = Some(help));
+                                    }
+                                });
+                            }
+                        }
+                    },
+                    Ok(BackendResponse::SingularityInfo(target_container, Some(SingularityResponse::AppHelp(app, help)))) => {
+                        let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
+
+                        match cont_id {
+                            None => {},
+                            Some(id) => {
+                                containers.with_mut(|store_option| {
+                                    if let Some(ref mut store) = store_option {
+                                        store.entry(id).and_modify(|descr| { descr.app_help.insert(app, help); });
+                                    }
+                                });
+                            }
+                        }
+                    },
+                    Ok(BackendResponse::SingularityInfo(target_container, Some(SingularityResponse::RunLabels(labels)))) => {
+                        let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
+
+                        match cont_id {
+                            None => {},
+                            Some(id) => {
+                                containers.with_mut(|store_option| {
+                                    if let Some(ref mut store) = store_option {
+                                        store.entry(id).and_modify(|descr| descr.run_labels = Some(labels));
+                                    }
+                                });
+                            }
+                        }
+                    },
+                    Ok(BackendResponse::SingularityInfo(target_container, Some(SingularityResponse::AppLabels(app, labels)))) => {
+                        let cont_id = container_path_to_id().map(|store| store.get(&target_container).cloned()).flatten();
+
+                        match cont_id {
+                            None => {},
+                            Some(id) => {
+                                containers.with_mut(|store_option| {
+                                    if let Some(ref mut store) = store_option {
+                                        store.entry(id).and_modify(|descr| { descr.app_labels.insert(app, labels); });
+                                    }
+                                });
+                            }
+                        }
+                    },
+                    Ok(BackendResponse::JobStarted(job_id, container_path, app, workdir)) if containers().is_none() => {
+                        // e.g. re-run from the job history, before the containers have been read
+                        comm_with_backend.read().reinsert_message(BackendResponse::JobStarted(job_id, container_path, app, workdir)).ok();
+                    },
+                    Ok(BackendResponse::JobStarted(job_id, container_path, app, workdir)) => {
+                        match container_path_to_id().and_then(|store| store.get(&container_path).cloned()) {
+                            Some(container_id) => {
+                                let new_job_state = ContainerPageJobState {
+                                    container_id,
+                                    container_path,
+                                    label: app.unwrap_or_else(|| "Main".to_string()),
+                                    working_directory: Some(workdir),
+                                    job_id,
+                                    state: JobState::Queued,
+                                };
+                                jobs.with_mut(|jobs| jobs.push(new_job_state));
+                                observed_job.set(Some(job_id));
+                                comm_with_backend.read().send(BackendRequest::SendJobOutput(job_id, 0)).ok();
                             },
-                            //continue polling
-                            _ =>  { comm_with_backend.read().send(BackendRequest::SendJobInfo(job_id)).ok(); }//TODO: reliably handle failures without spamming the queue
+                            None => {
+                                println!("Job {:?} was started for unknown container {:?}", &job_id, &container_path);
+                            }
                         }
+                    },
+                    Ok(BackendResponse::JobInfo(job_id, job_state)) if containers().is_none() => {
+                        // keeps its order behind the reinserted JobStarted
+                        comm_with_backend.read().reinsert_message(BackendResponse::JobInfo(job_id, job_state)).ok();
+                    },
+                    Ok(BackendResponse::JobInfo(job_id, job_state)) => {
+                        let listed_job = jobs().into_iter().find(|job| job.job_id == job_id);
+                        match listed_job {
+                            Some(job) => {
+                                jobs.with_mut(|jobs| {
+                                    jobs.iter_mut().filter(|job| job.job_id == job_id).for_each(|job| job.state = job_state);
+                                });
 
-                        // firstly, accept and process message
-                        if running_job().is_some_and(|prev_job_state| prev_job_state.job_id == job_id) {
-                            running_job.with_mut(|prev_job_state| {
-                                if let Some(prev_job_state) = prev_job_state {
-                                    //println!("Setting Job {:?} to: {:?}", &prev_job_state.job_id, &job_state);
-                                    prev_job_state.state = job_state;
-                                }
-                            });
-                        } else { // Job may be the first one, or a different one: INCORRECT BEHAVIOUR
-                            match (selected_container(), selected_container_path()) {
-                                (Some(id), Some(path)) => {
-                                    let new_job_state = ContainerPageJobState {
-                                        container_id: id, //maybeuninit
-                                        container_path: path.clone(), //maybeuninit
-                                        job_id,
-                                        state: job_state,
-                                    };
-                                    //println!("Setting Job {:?} to: {:?}", &job_id, &new_job_state);
-                                    running_job.set(Some(new_job_state));
-                                },
-                                _ => {
-                                    println!("Retrieveing Job state errored: No container selected!")
+                                match job_state {
+                                    // a finished export means the configuration now lives in the working directory
+                                    state if state.is_finished() => {
+                                        if copy_files_job().is_some_and(|copy_job| copy_job == job_id) {
+                                            copy_files_job.set(None);
+                                            let workdir = workdir_paths().get(&job.container_id).cloned().flatten().filter(|p| p.is_dir());
+                                            let config_file = config_paths().get(&job.container_id).cloned().flatten().filter(|p| p.is_file());
+                                            match (workdir, config_file) {
+                                                (Some(workdir), Some(config_file)) => {
+                                                    if let Some(config_file_name) = config_file.components().last() {
+                                                        let new_config_path = workdir.join(config_file_name);
+                                                        config_paths.with_mut(|store| {
+                                                            store.insert(job.container_id, Some(new_config_path));
+                                                        });
+                                                    }
+                                                },
+                                                _ => {}
+                                            };
+                                        }
+                                    },
+                                    //continue polling
+                                    _ =>  { comm_with_backend.read().send(BackendRequest::SendJobInfo(job_id)).ok(); }//TODO: reliably handle failures without spamming the queue
                                 }
+                            },
+                            None => {
+                                println!("Received state of unlisted job {:?}: {:?}", &job_id, &job_state);
                             }
                         }
                     },
-                    Ok(BackendResponse::JobOutput(backend_job_id, output)) if running_job().is_some_and(|job| job.job_id == backend_job_id) => {
-                        backend_output.with_mut(move |lines| {
-                            for new_line in output.into_iter() {
-                                lines.push(new_line);
-                            }
+                    Ok(BackendResponse::ChatChannels(channels)) => {
+                        chat_channels.set(channels);
+                    },
+                    Ok(BackendResponse::JobStopFailed(_job_id, reason)) => {
+                        job_stop_failure.set(Some(reason));
+                    },
+                    Ok(BackendResponse::JobNotListed(job_id)) => {
+                        if jobs().iter().any(|job| job.job_id == job_id) { dismiss_job(job_id); }
+                    },
+                    Ok(BackendResponse::JobOutput(backend_job_id, start, output)) if jobs().iter().any(|job| job.job_id == backend_job_id) => {
+                        let finished = jobs().iter().any(|job| job.job_id == backend_job_id && job.state.is_finished());
+                        let received_lines = !output.is_empty();
+                        job_outputs.with_mut(move |outputs| {
+                            let lines = outputs.entry(backend_job_id).or_default();
+                            // lines from start on may have changed since they were last sent
+                            lines.truncate(start);
+                            lines.extend(output);
                         });
-                        comm_with_backend.read().send(crate::BackendRequest::SendJobOutput(backend_job_id, backend_output().len())).ok();
+                        // once a job has ended, its output is complete as soon as nothing new arrives
+                        if received_lines || !finished {
+                            let next_line = job_outputs().get(&backend_job_id).map(|lines| first_open_line(lines)).unwrap_or(0);
+                            comm_with_backend.read().send(crate::BackendRequest::SendJobOutput(backend_job_id, next_line)).ok();
+                        }
                     },
                     Ok(response) => {println!("GeneralContainerPage received: {response:?}");}, //TODO: send unrelated messages back or error out
                     Err(_) => {}
@@ -344,8 +503,6 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
         }}
     );
 
-    let mut stop_entire_program_overlay_visible = use_signal(|| false);
-
     let mut page_updates = use_signal(|| Vec::new() as Vec<ContainerPageUpdate>);
 
     let _update_event = use_future(move || async move {
@@ -354,8 +511,6 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
             let dt = std::time::Duration::from_millis(500);
             tokio::time::sleep_until(now + dt).await;
 
-            if running_job().is_some_and(|job| job.state == JobState::JobNotListed) { running_job.set(None); }
-
             if !page_updates.read().is_empty() {
                 for update in page_updates().iter() {
                     match update {
@@ -392,23 +547,25 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                             })
                         },
                         ContainerPageUpdate::StartSelfConfigurator(container_pth) => {
-                            if webserver_port().is_none() {
+                            if self_configurator_ports().is_none() {
                                 println!("No webserver running!");
+                                webserver_error.set(None);
                                 comm_with_backend.read().send(BackendRequest::StartLocalWebServer(app_state().clone(), container_pth.clone())).ok();
                             }
-                            while webserver_port().is_none() {
+                            while self_configurator_ports().is_none() && webserver_error().is_none() {
                                 let now = tokio::time::Instant::now();
                                 let dt = std::time::Duration::from_millis(200);
                                 tokio::time::sleep_until(now + dt).await;
                             }
 
-                            comm_with_backend.read().send(BackendRequest::RunSingularityApp(PathBuf::from("~"), container_pth.clone(), "self-configurator".to_string(), vec!["--colony-interop".to_string()])).ok();
-                            let ip_address = format!("http://localhost:{:?}", &webserver_port());
-                            println!("Webserver supposedly at: {}", &ip_address);
-                            let ip_address = format!("http://localhost:9283/");
+                            // the container is told where to find the launcher and where to serve its GUI
+                            if let Some(ports) = self_configurator_ports() {
+                                comm_with_backend.read().send(BackendRequest::RunSingularityApp(PathBuf::from("~"), container_pth.clone(), "self-configurator".to_string(), ports.container_args(), None)).ok();
+                                let ip_address = ports.gui_address();
 
-                            println!("Connecting to webserver at: {}", &ip_address);
-                            app_state.set(AppState::ContainerSelfConfiguratorPage(container_pth.clone(), ip_address));
+                                println!("Connecting to webserver at: {}", &ip_address);
+                                app_state.set(AppState::ContainerSelfConfiguratorPage(container_pth.clone(), ip_address));
+                            }
                         },
                     }
                 }
@@ -432,12 +589,29 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                 div {
                     class: "general-container-page large-navbar",
                     HomeButton {app_state, class: "general-container-page"}
+                    JobHistoryButton {app_state, class: "general-container-page"}
+                    if running_job_count() > 0 {
+                        div {
+                            class: "general-container-page running-jobs-indicator",
+                            onclick: move |_| job_observer_visible.set(true),
+                            LoadingIcon { class: "general-container-page running-jobs-indicator small-icon unclickable".to_owned() }
+                            {match running_job_count() {
+                                1 => "1 job running".to_string(),
+                                count => format!("{count} jobs running"),
+                            }}
+                        }
+                    }
                 }
             }
             div {
                 class: "general-container-page content-area",
-                StopEntireProgramOverlay { comm_with_backend, running_job, is_visible: stop_entire_program_overlay_visible }
-                JobObserverOverlay { is_visible: job_observer_visible, running_job, working_directory: selected_cont_workdir, backend_output, stop_entire_program_overlay_visible, comm_with_backend }
+                StopEntireProgramOverlay {
+                    comm_with_backend,
+                    stop_failure: job_stop_failure,
+                    // the output of every job is kept in a log file, as if they had been dismissed
+                    on_exit: move |_| jobs().into_iter().for_each(|job| dismiss_job(job.job_id)),
+                }
+                JobObserverOverlay { is_visible: job_observer_visible, running_job: observed_job_state, job_outputs, comm_with_backend }
                 div {
                     class: "general-container-page overview-column",
                     {
@@ -502,6 +676,12 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                 }
                 div {
                     class: "general-container-page argument-column",
+                    ContainerDetailsCard {
+                        class: "general-container-page".to_string(),
+                        inspection: selected_container().and_then(|id| containers().and_then(|store| store.get(&id).and_then(|descr| descr.inspection.clone()))),
+                        digest: selected_container().and_then(|id| containers().and_then(|store| store.get(&id).and_then(|descr| descr.digest.clone()))),
+                        selected_app: selected_app(),
+                    }
                     {
                         if selected_container().is_some() && selected_app().is_some_and(|app| app == "self-configurator") {
                             rsx! {
@@ -512,8 +692,8 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                                     container_path: selected_container_path,
                                     workdir_path: selected_cont_workdir,
                                     configuration_path: selected_cont_config,
-                                    running_job,
-                                    webserver_port,
+                                    container_busy: selected_container_busy,
+                                    webserver_error,
                                     comm_with_backend,
                                     page_updates,
                                 }
@@ -527,37 +707,130 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                                     container_args,
                                     page_updates
                                 }
+                                {
+                                    let container_id = selected_container().unwrap();
+                                    let descr = containers().and_then(|store| store.get(&container_id).cloned());
+                                    let has_form = descr.as_ref().and_then(|descr| descr.apps.as_ref()).is_some_and(|apps| apps.iter().any(|app| app == "app-configurations"));
+                                    let form = descr.and_then(|descr| descr.configuration_form);
+                                    let opened = opened_configuration_forms().contains(&container_id);
+                                    match (form, selected_container_path()) {
+                                        // asking the container for its schema runs it, so this waits until the form is wanted
+                                        (None, Some(container_path)) if has_form && !opened => rsx! {
+                                            button {
+                                                class: "general-container-page configuration-form-card secondary-button",
+                                                onclick: move |_| {
+                                                    opened_configuration_forms.with_mut(|opened| { opened.insert(container_id); });
+                                                    comm_with_backend.read().send(BackendRequest::QuerySingularity(container_path.clone(), SingularityQuery::AppConfigurationOptions)).ok();
+                                                },
+                                                {"Open configuration form"}
+                                            }
+                                        },
+                                        (None, Some(_)) if opened => rsx! {
+                                            p { class: "general-container-page configuration-form-card description", {"Reading the configuration schema..."} }
+                                        },
+                                        (Some(None), Some(_)) => rsx! {
+                                            p { class: "general-container-page configuration-form-card description", {"The container publishes no usable configuration schema"} }
+                                        },
+                                        (Some(Some(form)), Some(container_path)) => rsx! {
+                                            ConfigurationFormCard {
+                                                key: "{container_id}",
+                                                class: "general-container-page".to_string(),
+                                                container: container_path,
+                                                form,
+                                                configuration_path: selected_cont_config(),
+                                                violations: configuration_violations().get(&container_id).cloned().unwrap_or_default(),
+                                                comm_with_backend,
+                                                on_use_configuration: move |configuration: PathBuf| {
+                                                    container_args.with_mut(|store| {
+                                                        let args = store.entry(container_id).or_insert_with(Vec::new);
+                                                        let next_id = args.iter().map(|(id, _)| *id).max().unwrap_or(0) + 1;
+                                                        args.push((next_id, wslify_windows_path(&configuration.to_string_lossy())));
+                                                    });
+                                                }
+                                            }
+                                        },
+                                        _ => rsx! { }
+                                    }
+                                }
                             }
                         } else { rsx! { } }
                     }
+                    {
+                        let selected_descr = selected_container().and_then(|id| containers().and_then(|store| store.get(&id).cloned()));
+                        match selected_descr {
+                            Some(descr) => {
+                                let app = selected_app();
+                                let app_profile = app.as_ref().and_then(|app| descr.app_run_profiles.get(app).cloned());
+                                let inherited = app.is_some() && app_profile.is_none();
+                                let profile = app_profile.unwrap_or_else(|| descr.run_profile.clone());
+                                let container_id = descr.id;
+                                let saved_app = app.clone();
+                                let card_key = format!("{}-{}", container_id, app.clone().unwrap_or_default());
+                                rsx! {
+                                    RunProfileCard {
+                                        key: "{card_key}",
+                                        class: "general-container-page".to_string(),
+                                        app,
+                                        profile,
+                                        inherited,
+                                        on_save: move |profile: Option<RunProfile>| {
+                                            containers.with_mut(|store_option| {
+                                                if let Some(descr) = store_option.as_mut().and_then(|store| store.get_mut(&container_id)) {
+                                                    set_run_profile(&mut descr.run_profile, &mut descr.app_run_profiles, saved_app.clone(), profile.clone());
+                                                }
+                                            });
+                                            let update = persistent_state::PersistentStateUpdate::SetRunProfile(container_id, saved_app.clone(), profile);
+                                            comm_with_backend.read().send(BackendRequest::UpdatePersistentState(update)).ok();
+                                        }
+                                    }
+                                }
+                            },
+                            None => rsx! { }
+                        }
+                    }
 
                 }
                 div {
                     class: "general-container-page button-column",
                     p {""}
 
-                    RunningContainerCard { job: running_job, working_directory: selected_cont_workdir, backend_output, comm_with_backend, job_observer_visible  }
+                    JobListCard { jobs, observed_job, on_dismiss: move |job_id| dismiss_job(job_id), comm_with_backend }
+
+                    RunningContainerCard { job: observed_job_state, job_outputs, on_dismiss: move |job_id| dismiss_job(job_id), job_observer_visible  }
+
+                    ChatPanel { class: "general-container-page".to_string(), channels: chat_channels, comm_with_backend }
 
                     button {
-                        class: match (running_job(), selected_app(), selected_cont_workdir(), selected_cont_config()) {
-                            // The button is hidden while a job is running
-                            (Some(_), _, _, _) => "general-container-page export-analysis-button primary-button disabled-button hidden",
+                        class: match (selected_container_busy(), selected_app(), selected_cont_workdir(), selected_cont_config()) {
+                            // The button is hidden while a job of the selected container is running
+                            (true, _, _, _) => "general-container-page export-analysis-button primary-button disabled-button hidden",
                             // The button may be shown, but only if the selected app is named "self-configurator"
-                            (None, Some(app), None, _) if app == "self-configurator" => "general-container-page export-analysis-button primary-button disabled-button",
-                            (None, Some(app), _, None) if app == "self-configurator" => "general-container-page export-analysis-button primary-button disabled-button",
+                            (false, Some(app), None, _) if app == "self-configurator" => "general-container-page export-analysis-button primary-button disabled-button",
+                            (false, Some(app), _, None) if app == "self-configurator" => "general-container-page export-analysis-button primary-button disabled-button",
                             // The button is only enabled if all information is set correctly
-                            (None, Some(app), Some(_workdir), Some(_config_path)) if app == "self-configurator" => "general-container-page export-analysis-button primary-button",
+                            (false, Some(app), Some(_workdir), Some(_config_path)) if app == "self-configurator" => "general-container-page export-analysis-button primary-button",
                             // If the selected app is anything other than "self.configurator", the button is hidden
                             // also, rust needs a fallback case here
                             _ => "general-container-page export-analysis-button primary-button disabled-button hidden"
                         },
                         onclick: move |_| {
                             spawn(async move {
-                                match (running_job(), selected_container_path(), selected_cont_workdir(), selected_cont_config()) {
-                                    (None, Some(container), Some(workdir), Some(config_path)) => {
+                                match (selected_container_busy(), selected_container(), selected_container_path(), selected_cont_workdir(), selected_cont_config()) {
+                                    (false, Some(container_id), Some(container), Some(workdir), Some(config_path)) => {
                                         let jid = crate::backend::JobId::new();
                                         match comm_with_backend.read().send(BackendRequest::ExportAnalysisIntoRepository(jid, workdir.clone(), container.clone(), config_path.clone())) {
-                                            Ok(_) => { copy_files_job.set(Some(jid)); },
+                                            Ok(_) => {
+                                                copy_files_job.set(Some(jid));
+                                                jobs.with_mut(|jobs| jobs.push(ContainerPageJobState {
+                                                    container_id,
+                                                    container_path: container,
+                                                    label: "Export into repository".to_string(),
+                                                    working_directory: Some(workdir),
+                                                    job_id: jid,
+                                                    state: JobState::Queued,
+                                                }));
+                                                observed_job.set(Some(jid));
+                                            },
                                             Err(_) => { } //TODO
                                         }
                                     },
@@ -568,71 +841,67 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                         {"Export Analysis"} br {} {"Into Repository"}
                     }
                     button {
-                        class: match (running_job(), selected_app(), selected_cont_workdir(), selected_cont_config()) {
-                            // This button is visible at all times
-                            // While a job is running, the button is shown, but disabled
-                            (Some(_), _, _, _) => "general-container-page start-container-button primary-button disabled-button",
+                        class: match (selected_app(), selected_cont_workdir(), selected_cont_config()) {
+                            // This button is visible at all times, other jobs may keep running meanwhile
                             // If an app is selected, and not "self-configurator", only the working directory is necessary to enable the button
-                            (None, Some(app), Some(_workdir), _) if app != "self-configurator" => "general-container-page start-container-button primary-button",
+                            (Some(app), Some(_workdir), _) if app != "self-configurator" => "general-container-page start-container-button primary-button",
                             // If an app is selected, and it is "self-configurator", both the working directory and the config file need to be present
-                            (None, Some(app), Some(_workdir), Some(_config_path)) if app == "self-configurator" => "general-container-page start-container-button primary-button",
+                            (Some(app), Some(_workdir), Some(_config_path)) if app == "self-configurator" => "general-container-page start-container-button primary-button",
                             _ => "general-container-page start-container-button primary-button disabled-button"
                         },
                         onclick: move |_| {
                             spawn(async move {
-                                if running_job().is_none() {
-                                    match selected_app() {
-                                        None => {
-                                            match (selected_container(), selected_container_path(), selected_cont_workdir()) {
-                                                (Some(cont_id), Some(cont_pth), Some(workdir))  => {
-                                                    let args_maybe = container_args().get(&cont_id)
-                                                                        .map(|vals| {
-                                                                            vals.iter()
-                                                                                .map(|(_, arg)| arg.clone())
-                                                                                .collect_vec()
-                                                                });
-                                                    if let Some(args) = args_maybe {
-                                                        println!("Running with args: {:?}", args);
-                                                        comm_with_backend.read().sender.send(
-                                                            BackendRequest::RunSingularity(workdir.clone(), cont_pth.clone(), args)
-                                                        ).ok();
-                                                    }
-                                                },
-                                                _ => { println!("Main app: Container: {:?}, Path: {:?}, Workdir: {:?}", &selected_container(), &selected_container_path(), &selected_cont_workdir()) }
-                                            }
-                                        },
-                                        Some(app) if app == "self-configurator" => {
-                                            match (selected_container_path(), selected_cont_workdir(), selected_cont_config()) {
-                                                (Some(cont_pth), Some(workdir), Some(config))  => {
-                                                    let args = vec![
-                                                        wslify_windows_path(&config.to_string_lossy().to_string())
-                                                    ];
+                                match selected_app() {
+                                    None => {
+                                        match (selected_container(), selected_container_path(), selected_cont_workdir()) {
+                                            (Some(cont_id), Some(cont_pth), Some(workdir))  => {
+                                                let args_maybe = container_args().get(&cont_id)
+                                                                    .map(|vals| {
+                                                                        vals.iter()
+                                                                            .map(|(_, arg)| arg.clone())
+                                                                            .collect_vec()
+                                                            });
+                                                if let Some(args) = args_maybe {
                                                     println!("Running with args: {:?}", args);
                                                     comm_with_backend.read().sender.send(
-                                                        BackendRequest::RunSingularity(workdir.clone(), cont_pth.clone(), args)
+                                                        BackendRequest::RunSingularity(workdir.clone(), cont_pth.clone(), args, None)
                                                     ).ok();
-                                                },
-                                                _ => { println!("Self-Configurator: Path: {:?}, Workdir: {:?}, Config: {:?}", &selected_container_path(), &selected_container_path(), &selected_cont_workdir()) }
-                                            }
-                                        },
-                                        Some(app) => {
-                                            match (selected_container(), selected_container_path(), selected_cont_workdir()) {
-                                                (Some(cont_id), Some(cont_pth), Some(workdir))  => {
-                                                    let args_maybe = container_args().get(&cont_id)
-                                                                        .map(|vals| {
-                                                                            vals.iter()
-                                                                                .map(|(_, arg)| arg.clone())
-                                                                                .collect_vec()
-                                                                });
-                                                    if let Some(args) = args_maybe {
-                                                        println!("Running with args: {:?}", args);
-                                                        comm_with_backend.read().sender.send(
-                                                            BackendRequest::RunSingularityApp(workdir.clone(), cont_pth.clone(), app, args)
-                                                        ).ok();
-                                                    }
-                                                },
-                                                _ => { println!("App: {}, Container: {:?}, Path: {:?}, Workdir: {:?}", &app, &selected_container(), &selected_container_path(), &selected_cont_workdir()) }
-                                            }
+                                                }
+                                            },
+                                            _ => { println!("Main app: Container: {:?}, Path: {:?}, Workdir: {:?}", &selected_container(), &selected_container_path(), &selected_cont_workdir()) }
+                                        }
+                                    },
+                                    Some(app) if app == "self-configurator" => {
+                                        match (selected_container_path(), selected_cont_workdir(), selected_cont_config()) {
+                                            (Some(cont_pth), Some(workdir), Some(config))  => {
+                                                let args = vec![
+                                                    wslify_windows_path(&config.to_string_lossy().to_string())
+                                                ];
+                                                println!("Running with args: {:?}", args);
+                                                comm_with_backend.read().sender.send(
+                                                    BackendRequest::RunSingularity(workdir.clone(), cont_pth.clone(), args, None)
+                                                ).ok();
+                                            },
+                                            _ => { println!("Self-Configurator: Path: {:?}, Workdir: {:?}, Config: {:?}", &selected_container_path(), &selected_container_path(), &selected_cont_workdir()) }
+                                        }
+                                    },
+                                    Some(app) => {
+                                        match (selected_container(), selected_container_path(), selected_cont_workdir()) {
+                                            (Some(cont_id), Some(cont_pth), Some(workdir))  => {
+                                                let args_maybe = container_args().get(&cont_id)
+                                                                    .map(|vals| {
+                                                                        vals.iter()
+                                                                            .map(|(_, arg)| arg.clone())
+                                                                            .collect_vec()
+                                                            });
+                                                if let Some(args) = args_maybe {
+                                                    println!("Running with args: {:?}", args);
+                                                    comm_with_backend.read().sender.send(
+                                                        BackendRequest::RunSingularityApp(workdir.clone(), cont_pth.clone(), app, args, None)
+                                                    ).ok();
+                                                }
+                                            },
+                                            _ => { println!("App: {}, Container: {:?}, Path: {:?}, Workdir: {:?}", &app, &selected_container(), &selected_container_path(), &selected_cont_workdir()) }
                                         }
                                     }
                                 }
@@ -641,20 +910,29 @@ pub fn GeneralContainerPage(app_state: Signal<AppState>, comm_with_backend: Sign
                         {"Start Container"}
                     }
                     button {
-                        class: match running_job() {
-                            Some(_) => "general-container-page stop-container-button primary-button",
-                            None => "general-container-page stop-container-button primary-button hidden"
+                        class: match (selected_container_path(), selected_cont_workdir()) {
+                            (Some(_container), Some(_workdir)) => "general-container-page open-shell-button primary-button",
+                            _ => "general-container-page open-shell-button primary-button disabled-button"
+                        },
+                        title: "Open a shell in the container, with the working directory and mounts of its runs",
+                        onclick: move |_| {
+                            if let (Some(container), Some(workdir)) = (selected_container_path(), selected_cont_workdir()) {
+                                app_state.set(AppState::ContainerShellPage(workdir, container));
+                            }
+                        },
+                        {"Open Shell"}
+                    }
+                    button {
+                        class: match observed_job_state() {
+                            Some(job_state) if !job_state.state.is_finished() => "general-container-page stop-container-button primary-button",
+                            _ => "general-container-page stop-container-button primary-button hidden"
                         },
                         onclick: move |_| {
-                            stop_entire_program_overlay_visible.set(true);
                             spawn(async move {
-                                match running_job() {
+                                match observed_job_state() {
                                     Some(job_state) => {
                                         match job_state.state {
-                                            crate::backend::JobState::Submitted => {
-                                                comm_with_backend.read().send(BackendRequest::StopProcess(job_state.job_id)).ok();
-                                            },
-                                            crate::backend::JobState::Scheduled => {
+                                            crate::backend::JobState::Queued => {
                                                 comm_with_backend.read().send(BackendRequest::StopProcess(job_state.job_id)).ok();
                                             },
                                             crate::backend::JobState::Running => {
@@ -686,6 +964,8 @@ pub fn ContainerAppCard(class: String, app_state: Signal<AppState>, page_updates
     let container_selected = if selected_container().is_some_and(|selected| container.id == selected) { "selected" } else { "" };
     let apps_hidden = if selected_container().is_some_and(|selected| container.id == selected) { "" } else { "" };
     let app_list_initialized = container.apps.is_some();
+    let main_path = container.path.clone();
+    let run_help_known = container.run_help.is_some();
 
     let mut pseudo_real_time = use_signal(||0 as i64);
     let icon_spin = use_memo(move || {
@@ -721,11 +1001,18 @@ pub fn ContainerAppCard(class: String, app_state: Signal<AppState>, page_updates
                     },
                     onclick: move |_| {
                         selected_app.set(None); // Main will always be chosen, indirectly
+                        if !run_help_known {
+                            comm_with_backend.read().send(BackendRequest::QuerySingularity(main_path.clone(), SingularityQuery::RunHelp)).ok();
+                            comm_with_backend.read().send(BackendRequest::QuerySingularity(main_path.clone(), SingularityQuery::RunLabels)).ok();
+                        }
                     },
                     h3 {
                         class: format!("{class} container-app-card app-title"),
                         {"Main"}
                     }
+                    if !container_selected.is_empty() && selected_app().is_none() {
+                        AppDetails { class: class.clone(), help: container.run_help.clone().flatten(), labels: container.run_labels.clone().unwrap_or_default() }
+                    }
                 }
                 div {
                     class: match app_list_initialized {
@@ -743,17 +1030,31 @@ pub fn ContainerAppCard(class: String, app_state: Signal<AppState>, page_updates
                                 {
                                     app_list.into_iter().map(|name| {
                                         let selected = if selected_app().is_some_and(|app| app == name) { container_selected } else { "" };
+                                        let help = container.app_help.get(&name).cloned();
+                                        let help_known = help.is_some();
+                                        let labels = container.app_labels.get(&name).cloned().unwrap_or_default();
+                                        let app_path = container.path.clone();
+                                        let app_name = name.clone();
                                         rsx! {
                                             div {
                                                 class: format!("{class} container-app-card app-card {selected}"),
                                                 onclick: move |_| {
                                                     if selected_app().is_some_and(|app| app == name) {
                                                         selected_app.set(None);
-                                                    } else { selected_app.set(Some(name.to_string())); }
+                                                    } else {
+                                                        selected_app.set(Some(name.to_string()));
+                                                        if !help_known {
+                                                            comm_with_backend.read().send(BackendRequest::QuerySingularity(app_path.clone(), SingularityQuery::AppHelp(name.clone()))).ok();
+                                                            comm_with_backend.read().send(BackendRequest::QuerySingularity(app_path.clone(), SingularityQuery::AppLabels(name.clone()))).ok();
+                                                        }
+                                                    }
                                                 },
                                                 h3 {
                                                     class: "{class} container-app-card app-title",
-                                                    {name.clone()}
+                                                    {app_name}
+                                                }
+                                                if !selected.is_empty() {
+                                                    AppDetails { class: class.clone(), help: help.flatten(), labels }
                                                 }
                                             }
                                         }
@@ -768,14 +1069,32 @@ pub fn ContainerAppCard(class: String, app_state: Signal<AppState>, page_updates
     }
 }
 
+// the usage of an app as its author describes it
+#[component]
+fn AppDetails(class: String, help: Option<String>, labels: BTreeMap<String, String>) -> Element {
+    rsx! {
+        if let Some(help) = help {
+            p { class: format!("{class} container-app-card app-help"), "{help}" }
+        }
+        if !labels.is_empty() {
+            ul {
+                class: format!("{class} container-app-card app-labels"),
+                for (key, value) in labels {
+                    li { {format!("{key}: {value}")} }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 fn ConfigureSelfCard(class: String, app_state: Signal<AppState>,
                          selected_container: Signal<Option<Uuid>>,
                          container_path: Memo<Option<PathBuf>>,
                          workdir_path: Memo<Option<PathBuf>>,
                          configuration_path: Memo<Option<PathBuf>>,
-                         running_job: Signal<Option<ContainerPageJobState>>,
-                         webserver_port: Signal<Option<u16>>,
+                         container_busy: Memo<bool>,
+                         webserver_error: Signal<Option<String>>,
                          page_updates: Signal<Vec<ContainerPageUpdate>>,
                          comm_with_backend: Signal<FrontendCommChannel>) -> Element {
 
@@ -785,11 +1104,15 @@ fn ConfigureSelfCard(class: String, app_state: Signal<AppState>,
     let hidden = if container_path().is_none() { "hidden" } else { "" };
     let mut self_configurator_is_starting = use_signal(|| false);
     let self_config_button_disabled = use_memo(move || {
-        if self_configurator_is_starting() || running_job().is_some() { "disabled-button" } else { "" }
+        if self_configurator_is_starting() || container_busy() { "disabled-button" } else { "" }
     });
     let waiting_for_self_configurator_hidden = use_memo(move || {
         if self_configurator_is_starting() { "" } else { "hidden" }
     });
+    // the button can be used again once the server has failed to start
+    use_effect(move || {
+        if webserver_error().is_some() { self_configurator_is_starting.set(false); }
+    });
 
     rsx! {
         div {
@@ -835,7 +1158,12 @@ fn ConfigureSelfCard(class: String, app_state: Signal<AppState>,
                     true => rsx! { SelfConfigSpinningIcon { class: class.clone(), hidden: waiting_for_self_configurator_hidden() } }
                 }
             }
-
+            if let Some(e) = webserver_error() {
+                p {
+                    class: format!("configure-self-card error-message {class}"),
+                    {format!("The self-configurator could not be started: {}", e)}
+                }
+            }
         }
     }
 }
@@ -1088,21 +1416,122 @@ pub fn InputField(container_id: Uuid, arg_id: i32, container_args: Signal<HashMa
 
 
 
+fn job_state_heading(state: JobState) -> String {
+    return match state {
+        JobState::Queued => "Job is queued".to_string(),
+        JobState::Running => "Job is running...".to_string(),
+        JobState::Succeeded => "Job has completed".to_string(),
+        JobState::Failed { exit_code: Some(code), .. } => format!("Job has failed with exit code {code}"),
+        JobState::Failed { signal: Some(signal), .. } => format!("Job was terminated by signal {signal}"),
+        JobState::Cancelled => "Job was cancelled".to_string(),
+        JobState::TimedOut => "Job has timed out".to_string(),
+        _ => "Job has ended".to_string(),
+    };
+}
+
+// the output of a dismissed job, written to a new file in the given directory
+fn write_job_log(directory: &Path, lines: &[JobOutputLine]) {
+    // Try with timestamp first
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
+    let mut path = directory.join(format!("raw_output_{}.log", timestamp));
+
+    // If somehow file exists already, bump counter
+    let mut counter = 1;
+    while path.exists() {
+        path = directory.join(format!("raw_output_{}_{}.log", timestamp, counter));
+        counter += 1;
+    }
+
+    // Create file (fails if someone else just created it in between)
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true) // fail if exists
+        .open(&path);
+
+    match file {
+        Ok(file) => {
+            let mut writer = BufWriter::new(file);
+            for line in lines.iter() {
+                writer.write_all(line.log_line().as_bytes()).ok();
+                writer.write_all(b"\n").ok();
+            }
+        },
+        Err(e) => println!("Could not write job log to {:?}: {:?}", &path, &e),
+    }
+}
+
+// all jobs started from this page; the observed one is shown below and in the observer overlay
 #[component]
-fn RunningContainerCard(job: Signal<Option<ContainerPageJobState>>,
-                        working_directory: Memo<Option<PathBuf>>,
-                        backend_output: Signal<Vec<String>>,
-                        comm_with_backend: Signal<FrontendCommChannel>,
+fn JobListCard(jobs: Signal<Vec<ContainerPageJobState>>,
+               observed_job: Signal<Option<JobId>>,
+               on_dismiss: EventHandler<JobId>,
+               comm_with_backend: Signal<FrontendCommChannel>) -> Element {
+
+    let hidden = if jobs().is_empty() { "hidden" } else { "" };
+
+    rsx! {
+        div {
+            class: format!("general-container-page job-list-card card-container {hidden}"),
+            h3 { class: "general-container-page job-list-card title", "Jobs" }
+            {jobs().into_iter().map(|job_state| {
+                let job_id = job_state.job_id;
+                let observed = if observed_job().is_some_and(|observed| observed == job_id) { "selected" } else { "" };
+                let container_name = job_state.container_path.file_name()
+                                              .map(|name| name.to_string_lossy().to_string())
+                                              .unwrap_or_else(|| "Unnamed".to_string());
+                let finished = job_state.state.is_finished();
+                rsx! {
+                    div {
+                        key: "{job_id.id}",
+                        class: format!("general-container-page job-list-card job-entry {observed}"),
+                        onclick: move |_| observed_job.set(Some(job_id)),
+                        div {
+                            class: "general-container-page job-list-card job-description",
+                            h4 { class: "general-container-page job-list-card job-title", "{container_name}: {job_state.label}" }
+                            p {
+                                class: "general-container-page job-list-card job-state",
+                                {format!("{} ({})", job_state_heading(job_state.state), job_id.generation_time.format("%H:%M:%S"))}
+                            }
+                        }
+                        if finished {
+                            button {
+                                class: "general-container-page job-list-card secondary-button",
+                                onclick: move |event| {
+                                    event.stop_propagation();
+                                    on_dismiss.call(job_id);
+                                },
+                                "Dismiss"
+                            }
+                        } else {
+                            button {
+                                class: "general-container-page job-list-card secondary-button",
+                                onclick: move |event| {
+                                    event.stop_propagation();
+                                    comm_with_backend.read().send(BackendRequest::StopProcess(job_id)).ok();
+                                },
+                                "Stop"
+                            }
+                        }
+                    }
+                }
+            })}
+        }
+    }
+}
+
+#[component]
+fn RunningContainerCard(job: Memo<Option<ContainerPageJobState>>,
+                        job_outputs: Signal<HashMap<JobId, Vec<JobOutputLine>>>,
+                        on_dismiss: EventHandler<JobId>,
                         job_observer_visible: Signal<bool>) -> Element {
 
     let hidden = if job().is_none() { "hidden" } else { "" };
+    let working_directory = job().and_then(|job_state| job_state.working_directory);
 
 
 
     let job_order_time = job().map(|job_state| {
-        let order_time = job_state.job_id.order_time;
-        let order_system_time = UNIX_EPOCH + Duration::from_secs(order_time.seconds);
-        let datetime = DateTime::<Utc>::from(order_system_time);
+        let datetime = job_state.job_id.generation_time;
         let timestamp_str = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
         timestamp_str
     });
@@ -1116,9 +1545,8 @@ fn RunningContainerCard(job: Signal<Option<ContainerPageJobState>>,
     let runtime_duration = use_memo(move || {
         pseudo_real_time(); // unused read hopefully never gets compiled away
         job().map(|job_state| {
-            let order_time = job_state.job_id.order_time;
-            let order_system_time = UNIX_EPOCH + Duration::from_secs(order_time.seconds);
-            let duration_string = order_system_time.elapsed().ok().map(|duration| {
+            let order_time = job_state.job_id.generation_time;
+            let duration_string = (Utc::now() - order_time).to_std().ok().map(|duration| {
                 let seconds = duration.as_secs() % 60;
                 let minutes = (duration.as_secs() / 60) % 60;
                 let hours = (duration.as_secs() / 60) / 60;
@@ -1142,15 +1570,16 @@ fn RunningContainerCard(job: Signal<Option<ContainerPageJobState>>,
         div {
             class: format!("general-container-page running-container-card card-container {hidden}"),
             match job().map(|job_state| job_state.state) {
-                Some(JobState::Completed(_exit_status)) => {
+                Some(state) if state.is_finished() => {
+                    let heading = job_state_heading(state);
                     rsx! {
-                        h2 { text_align: "center",  "Job has completed" }
+                        h2 { text_align: "center",  "{heading}" }
                         div {
                             display: "flex",
                             align_items: "center",
                             justify_content: "center",
                             onclick: move |_| {
-                                job.set(None);
+                                if let Some(job_state) = job() { on_dismiss.call(job_state.job_id); }
                             },
                             StoppedIcon { class: "general-container-page spinning-icon large-icon unclickable".to_owned() }
                         }
@@ -1162,11 +1591,11 @@ fn RunningContainerCard(job: Signal<Option<ContainerPageJobState>>,
                                             job_observer_visible.set(true);
                                         },
                                         JobOutputWidget {
+                                            key: "{job().unwrap().job_id.id}",
                                             class: "general-container-page running-container-card",
                                             job_id: job().map(|job_state| job_state.job_id).unwrap(),
-                                            working_directory,
-                                            displayed_text: backend_output,
-                                            comm_with_backend
+                                            working_directory: working_directory.clone(),
+                                            job_outputs,
                                         }
                                     }
                                 }
@@ -1176,9 +1605,6 @@ fn RunningContainerCard(job: Signal<Option<ContainerPageJobState>>,
                         }
                     }
                 },
-                Some(JobState::JobNotListed) => {
-                    rsx!{ }
-                }
                 None => {
                     rsx! { }
                 }
@@ -1219,11 +1645,11 @@ fn RunningContainerCard(job: Signal<Option<ContainerPageJobState>>,
                                             job_observer_visible.set(true);
                                         },
                                         JobOutputWidget {
+                                            key: "{job().unwrap().job_id.id}",
                                             class: "general-container-page running-container-card",
                                             job_id: job().map(|job_state| job_state.job_id).unwrap(),
-                                            working_directory,
-                                            displayed_text: backend_output,
-                                            comm_with_backend
+                                            working_directory: working_directory.clone(),
+                                            job_outputs,
                                         }
                                     }
                                 }
@@ -1242,10 +1668,8 @@ fn RunningContainerCard(job: Signal<Option<ContainerPageJobState>>,
 
 #[component]
 fn JobObserverOverlay(is_visible: Signal<bool>,
-                      running_job: Signal<Option<ContainerPageJobState>>,
-                      working_directory: Memo<Option<PathBuf>>,
-                      backend_output: Signal<Vec<String>>,
-                      stop_entire_program_overlay_visible: Signal<bool>,
+                      running_job: Memo<Option<ContainerPageJobState>>,
+                      job_outputs: Signal<HashMap<JobId, Vec<JobOutputLine>>>,
                       comm_with_backend: Signal<FrontendCommChannel>) -> Element {
 
     let running_job_id = use_memo(move || running_job().map(|job_state| job_state.job_id));
@@ -1260,11 +1684,14 @@ fn JobObserverOverlay(is_visible: Signal<bool>,
                     },
 
                     JobOutputWidget {
+                        key: "{jid.id}",
                         class: "general-container-page job-observer-overlay",
                         job_id: jid,
-                        working_directory,
-                        displayed_text: backend_output,
-                        comm_with_backend
+                        working_directory: running_job().and_then(|job_state| job_state.working_directory),
+                        job_outputs,
+                    }
+                    if running_job().is_some_and(|job_state| !job_state.state.is_finished()) {
+                        JobInputRow { key: "{jid.id}", job_id: jid, comm_with_backend }
                     }
                     div {
                         class: "general-container-page job-observer-button-row",
@@ -1277,19 +1704,15 @@ fn JobObserverOverlay(is_visible: Signal<bool>,
                         }
                         button {
                             class: match running_job() {
-                                Some(_) => "general-container-page job-observer-stop-container-button primary-button",
-                                None => "general-container-page job-observer-stop-container-button primary-button hidden"
+                                Some(job_state) if !job_state.state.is_finished() => "general-container-page job-observer-stop-container-button primary-button",
+                                _ => "general-container-page job-observer-stop-container-button primary-button hidden"
                             },
                             onclick: move |_| {
-                                stop_entire_program_overlay_visible.set(true);
                                 spawn(async move {
                                     match running_job() {
                                         Some(job_state) => {
                                             match job_state.state {
-                                                crate::backend::JobState::Submitted => {
-                                                    comm_with_backend.read().send(BackendRequest::StopProcess(job_state.job_id)).ok();
-                                                },
-                                                crate::backend::JobState::Scheduled => {
+                                                crate::backend::JobState::Queued => {
                                                     comm_with_backend.read().send(BackendRequest::StopProcess(job_state.job_id)).ok();
                                                 },
                                                 crate::backend::JobState::Running => {
@@ -1314,13 +1737,54 @@ fn JobObserverOverlay(is_visible: Signal<bool>,
     }
 }
 
-// A hacky solution since I am not confident tunneling processes through windows c-api will work flawlessly
-// Subprocesses on windows are tied to the console of the parent process, ergo one needs the process id of the console to be closed
-// Sending a stop process signal (SIGINT, SIGTERM, SIGKILL) on windows is NOT possible
-// In the future Colony will start some kind of server inside wsl once and all process manipulations shall be done inside linux
-// Closing the entire application should (hopefully) eliminate all jobs inside wsl linux
+// answers for tools that ask interactively; empty lines are sent, too, e.g. to accept a default
+#[component]
+fn JobInputRow(job_id: JobId, comm_with_backend: Signal<FrontendCommChannel>) -> Element {
+
+    let mut draft = use_signal(String::new);
+
+    let mut send_line = move || {
+        comm_with_backend.read().send(BackendRequest::SendJobInput(job_id, JobInput::Line(draft()))).ok();
+        draft.set(String::new());
+    };
+
+    rsx! {
+        div {
+            class: "general-container-page job-input-row",
+            input {
+                class: "general-container-page job-input-field",
+                value: draft,
+                placeholder: "Input for the job...",
+                oninput: move |evt| draft.set(evt.value()),
+                onkeydown: move |evt| {
+                    if evt.key() == Key::Enter { send_line(); }
+                },
+            }
+            button {
+                class: "general-container-page job-input-button primary-button",
+                onclick: move |_| send_line(),
+                {"Send"}
+            }
+            button {
+                class: "general-container-page job-input-button primary-button",
+                title: "Interrupt the job (Ctrl-C)",
+                onclick: move |_| { comm_with_backend.read().send(BackendRequest::SendJobInput(job_id, JobInput::Interrupt)).ok(); },
+                {"Ctrl-C"}
+            }
+            button {
+                class: "general-container-page job-input-button primary-button",
+                title: "End the input of the job (Ctrl-D)",
+                onclick: move |_| { comm_with_backend.read().send(BackendRequest::SendJobInput(job_id, JobInput::EndOfInput)).ok(); },
+                {"EOF"}
+            }
+        }
+    }
+}
+
+// Stopped jobs have their whole process tree terminated by the backend, this is only shown if processes survived that
+// Exiting the program shuts down ColonyWSL on windows, which is the last resort to end them
 #[component]
-fn StopEntireProgramOverlay(comm_with_backend: Signal<FrontendCommChannel>, running_job: Signal<Option<ContainerPageJobState>> ,is_visible: Signal<bool>) -> Element {
+fn StopEntireProgramOverlay(comm_with_backend: Signal<FrontendCommChannel>, stop_failure: Signal<Option<String>>, on_exit: EventHandler<()>) -> Element {
 
 
     let no_overlay = rsx! { };
@@ -1332,9 +1796,11 @@ fn StopEntireProgramOverlay(comm_with_backend: Signal<FrontendCommChannel>, runn
                 div {
                     h3 {
                         class: "general-container-page stop-program-info",
-                        "Stopping all spawned processes currently requires terminating the whole program."
+                        "Not all processes of the job could be stopped:"
+                        br { }
+                        {stop_failure().unwrap_or_default()}
                         br { }
-                        "Do you want to continue?"
+                        "Do you want to exit the program?"
                     }
                 }
                 div {
@@ -1342,7 +1808,7 @@ fn StopEntireProgramOverlay(comm_with_backend: Signal<FrontendCommChannel>, runn
                     button {
                         class: "general-container-page stop-program-button primary-button",
                         onclick: move |_| {
-                            running_job.set(None);
+                            on_exit.call(());
                             comm_with_backend.read().send(BackendRequest::StopProgram).ok();
                         },
                         "Exit Program"
@@ -1350,7 +1816,7 @@ fn StopEntireProgramOverlay(comm_with_backend: Signal<FrontendCommChannel>, runn
                     button {
                         class: "general-container-page cancel-stop-program-button primary-button",
                         onclick: move |_| {
-                            is_visible.set(false)
+                            stop_failure.set(None)
                         },
                         "Cancel"
                     }
@@ -1359,7 +1825,7 @@ fn StopEntireProgramOverlay(comm_with_backend: Signal<FrontendCommChannel>, runn
         }
     };
 
-    match is_visible() {
+    match stop_failure().is_some() {
         true => actual_overlay,
         false => no_overlay
     }
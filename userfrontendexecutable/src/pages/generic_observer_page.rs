@@ -24,7 +24,7 @@ pub enum GenericJobState {
 pub fn GenericObserverPage(app_state: Signal<AppState>, comm_with_backend: Signal<FrontendCommChannel>, jobid: JobId) -> Element {
     // Signals with Signal dependencies usually need use_memo
 
-    let mut backend_output = use_signal_sync(|| Vec::new() as Vec<String>);
+    let mut backend_output = use_signal_sync(|| Vec::new() as Vec<JobOutputLine>);
 
     // have the backend install wsl in the background
     use_future(move || { let jobid = jobid.clone(); async move {
@@ -33,7 +33,7 @@ pub fn GenericObserverPage(app_state: Signal<AppState>, comm_with_backend: Signa
             let dt = std::time::Duration::from_millis(200);
             tokio::time::sleep_until(now + dt).await;
 
-            match comm_with_backend.read().send(BackendRequest::SendJobOutput(jobid.clone(), backend_output().len())) {
+            match comm_with_backend.read().send(BackendRequest::SendJobOutput(jobid.clone(), first_open_line(&backend_output()))) {
                 Ok(_response) => {},
                 Err(_) => {continue;}
             }
@@ -46,8 +46,10 @@ pub fn GenericObserverPage(app_state: Signal<AppState>, comm_with_backend: Signa
                 match comm_with_backend.read().try_receive() {
                     Ok(msg) => {
                         match msg {
-                            BackendResponse::JobOutput(received_job_id, mut lines) if received_job_id == jobid => {
-                                backend_output.write().append(&mut lines);
+                            BackendResponse::JobOutput(received_job_id, start, lines) if received_job_id == jobid => {
+                                let mut output = backend_output.write();
+                                output.truncate(start);
+                                output.extend(lines);
                             },
                             _ => {panic!("Need to echo unused messages back")}
                         }
@@ -77,7 +79,7 @@ pub fn GenericObserverPage(app_state: Signal<AppState>, comm_with_backend: Signa
                     p {
                         class: "installation-page backend-output",
                         display: "block",
-                        for elem in vec_to_html_multiline(backend_output()) {
+                        for elem in vec_to_html_multiline(backend_output().into_iter().map(|line| line.text).collect()) {
                             {elem}
                         }
                     }
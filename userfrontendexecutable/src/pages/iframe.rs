@@ -49,6 +49,9 @@ pub fn IFramePage(app_state: Signal<AppState>, title: String, source: String) ->
 #[component]
 pub fn ContainerSelfConfiguratorPage(app_state: Signal<AppState>, comm_with_backend: Signal<FrontendCommChannel>, title: String, container: PathBuf, address: String) -> Element {
 
+    // of the last configuration the container sent, which was not saved
+    let mut violations = use_signal(Vec::<SchemaViolation>::new);
+
     use_future(move || {
         let container_send = container.clone();
         async move {
@@ -67,11 +70,15 @@ pub fn ContainerSelfConfiguratorPage(app_state: Signal<AppState>, comm_with_back
                                     BackendResponse::Configuration(target_container,config) => {
                                         println!("Iframe received Configuration");
                                         if target_container == container_send {
+                                            violations.set(Vec::new());
                                             comm_with_backend.read().send(BackendRequest::StartJob(container_send.clone(), config)).ok();
                                         } else {
                                             println!("Received configuration from a different container");
                                         }
                                     },
+                                    BackendResponse::ConfigurationRejected(target_container, rejected) if target_container == container_send => {
+                                        violations.set(rejected);
+                                    },
                                     BackendResponse::JobInfo(_job_id, _) => {
                                         // 2. redirect to GenericOberverPage, pass JobId
                                         println!("Iframe received JobInfo");
@@ -94,6 +101,17 @@ pub fn ContainerSelfConfiguratorPage(app_state: Signal<AppState>, comm_with_back
 
     rsx! {
         h1 { "This is the Self-Configurator" }
+        if !violations().is_empty() {
+            div {
+                class: "iframe configuration-violations",
+                p { "The configuration was not saved, it does not match what the container expects:" }
+                ul {
+                    for violation in violations() {
+                        li { {violation.to_string()} }
+                    }
+                }
+            }
+        }
         IFramePage { app_state: app_state, title: title, source: address }
     }
 }
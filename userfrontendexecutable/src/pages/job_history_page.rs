@@ -0,0 +1,162 @@
+
+
+use dioxus::prelude::*;
+
+use crate::pages::*;
+use crate::components::*;
+use crate::backend::*;
+
+
+// earlier runs of containers, also from before the launcher was restarted
+#[component]
+pub fn JobHistoryPage(app_state: Signal<AppState>, comm_with_backend: Signal<FrontendCommChannel>) -> Element {
+
+    let mut records = use_signal(|| None as Option<Vec<JobRecord>>);
+    let mut search_query = use_signal(String::new);
+
+    let filtered_records = use_memo(move || {
+        records().unwrap_or_default().into_iter()
+                 .filter(|record| record.matches(&search_query()))
+                 .collect::<Vec<_>>()
+    });
+
+    let _poll_backend_loop = use_future(move || async move {
+        comm_with_backend.read().send(BackendRequest::ReadJobHistory).ok();
+
+        loop {
+            let now = tokio::time::Instant::now();
+            let dt = std::time::Duration::from_millis(200);
+            tokio::time::sleep_until(now + dt).await;
+
+            match comm_with_backend.read().try_receive() {
+                Ok(BackendResponse::JobHistory(history)) => {
+                    records.set(Some(history));
+                },
+                // left for the container page, which keeps polling the state and output of its jobs
+                Ok(response) => { comm_with_backend.read().reinsert_message(response).ok(); },
+                Err(_) => {}
+            }
+        }
+    });
+
+    rsx! {
+        div {
+            class: "job-history-page background",
+            div {
+                class: "job-history-page logo-navbar-row",
+                div {
+                    class: "job-history-page logo-row",
+                    IMILogo { class: "navbar-logo" }
+                }
+                div {
+                    class: "job-history-page large-navbar",
+                    HomeButton {app_state, class: "job-history-page"}
+                }
+            }
+            div {
+                class: "job-history-page content-area",
+                div {
+                    class: "job-history-page search-row",
+                    input {
+                        class: "job-history-page search-field",
+                        r#type: "search",
+                        placeholder: "Search by container, app, arguments, directory or state",
+                        value: search_query(),
+                        oninput: move |event| search_query.set(event.value()),
+                    }
+                    button {
+                        class: "job-history-page primary-button",
+                        onclick: move |_| { comm_with_backend.read().send(BackendRequest::ReadJobHistory).ok(); },
+                        {"Refresh"}
+                    }
+                }
+                match records() {
+                    None => rsx! { p { class: "job-history-page placeholder", "Reading job history..." } },
+                    Some(all) if all.is_empty() => rsx! { p { class: "job-history-page placeholder", "No container has been run yet." } },
+                    Some(_) => rsx! {
+                        div {
+                            class: "job-history-page record-list",
+                            for record in filtered_records() {
+                                JobRecordCard { key: "{record.job_id.id}", record, app_state, comm_with_backend }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn JobRecordCard(record: JobRecord, app_state: Signal<AppState>, comm_with_backend: Signal<FrontendCommChannel>) -> Element {
+
+    let container_name = record.container_path.file_name()
+                               .map(|name| name.to_string_lossy().to_string())
+                               .unwrap_or_else(|| "Unnamed".to_string());
+    let app_name = record.app.clone().unwrap_or_else(|| "Main".to_string());
+    let local_time = |time: chrono::DateTime<chrono::Utc>| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string();
+    let started = local_time(record.start_time);
+    let ended = record.end_time.map(local_time).unwrap_or_else(|| "-".to_string());
+    let outcome = match record.state {
+        JobState::Queued | JobState::Running => "Running".to_string(),
+        JobState::Succeeded => "Completed".to_string(),
+        JobState::Failed { exit_code: Some(code), .. } => format!("Failed with exit code {code}"),
+        JobState::Failed { signal: Some(signal), .. } => format!("Terminated by signal {signal}"),
+        JobState::Cancelled => "Cancelled".to_string(),
+        JobState::TimedOut => "Timed out".to_string(),
+        JobState::Orphaned => "Lost (launcher was closed)".to_string(),
+        _ => "Ended".to_string(),
+    };
+    let outcome_class = if record.state == JobState::Succeeded { "succeeded" } else if record.state.is_finished() { "failed" } else { "" };
+
+    // the same container file and working directory are needed to run it the same way again
+    let can_rerun = record.container_path.is_file() && record.working_directory.is_dir() && record.can_be_repeated();
+    let rerun_hint = if !record.can_be_repeated() { "Self-configurator runs belong to the launcher session that started them" }
+                     else if !can_rerun { "The container or the working directory no longer exists" }
+                     else { "" };
+    let rerun_record = record.clone();
+
+    rsx! {
+        div {
+            class: "job-history-page record-card",
+            div {
+                class: "job-history-page record-header",
+                h3 { class: "job-history-page record-title", "{container_name}: {app_name}" }
+                span { class: format!("job-history-page record-state {outcome_class}"), "{outcome}" }
+            }
+            table {
+                class: "job-history-page record-details",
+                tr { th { "Started" } td { "{started}" } }
+                tr { th { "Ended" } td { "{ended}" } }
+                tr { th { "Container" } td { {record.container_path.to_string_lossy().to_string()} } }
+                if let Some(digest) = &record.container_digest {
+                    tr { th { "Digest" } td { class: "job-history-page digest", {digest.short().to_string()} } }
+                }
+                tr { th { "Arguments" } td { {if record.arguments.is_empty() { "-".to_string() } else { record.arguments.join(" ") }} } }
+                tr { th { "Working directory" } td { {record.working_directory.to_string_lossy().to_string()} } }
+                if let Some(configuration) = &record.configuration_path {
+                    tr { th { "Configuration" } td { {configuration.to_string_lossy().to_string()} } }
+                }
+                if let Some(log_path) = &record.log_path {
+                    tr { th { "Log" } td { {log_path.to_string_lossy().to_string()} } }
+                }
+            }
+            button {
+                class: if can_rerun { "job-history-page primary-button" } else { "job-history-page primary-button disabled-button" },
+                title: rerun_hint,
+                onclick: move |_| {
+                    if !can_rerun { return; }
+                    let record = rerun_record.clone();
+                    let request = match record.app {
+                        Some(app) => BackendRequest::RunSingularityApp(record.working_directory, record.container_path, app, record.arguments, Some(record.run_profile)),
+                        None => BackendRequest::RunSingularity(record.working_directory, record.container_path, record.arguments, Some(record.run_profile)),
+                    };
+                    comm_with_backend.read().send(request).ok();
+                    // the new job shows up on the container page
+                    app_state.set(AppState::EntryPage);
+                },
+                {"Re-run with same settings"}
+            }
+        }
+    }
+}
@@ -111,7 +111,7 @@ pub fn LocalContainerPage(app_state: Signal<AppState>, comm_with_backend: Signal
                                 let args = container_args().into_iter().map(|(_,s)| s).collect();
                                 //backend::start_container(&pth, args);
                                 comm_with_backend.read().sender.send(
-                                    BackendRequest::RunSingularity(workdir_path().clone(), selected_container().clone(), args)
+                                    BackendRequest::RunSingularity(workdir_path().clone(), selected_container().clone(), args, None)
                                 ).ok();
                             });
 
@@ -165,18 +165,13 @@ pub fn DescriptionCard(selected_container: Signal<PathBuf>, container_descriptio
                 //TODO: make labels an array, then show each key-value-pair in the description card
                 match comm_with_backend.read().try_receive() {
                     Ok(msg) => match msg {
-                        BackendResponse::SingularityInfo(_container, Some(SingularityResponse::Inspection(json))) => {
-                            //container_description.write().labels = format!("{}",json["data"]["attributes"]["labels"]);
-                            let labels = json["data"]["attributes"]["labels"]
-                                .as_object().unwrap()
-                                .iter()
-                                .filter(|(k,_)| !k.contains("org."))
-                                .map(|(k,v)| {
-                                    format!("{}: {}", k.clone(), v.as_str().unwrap())
-                                })
+                        BackendResponse::SingularityInfo(_container, Some(SingularityResponse::Inspection(inspection))) => {
+                            let labels = inspection.user_labels()
+                                .map(|(k,v)| format!("{}: {}", k, v))
                                 .collect::<Vec<_>>()
                                 .join("\n");
                             container_description.write().labels = labels;
+                            container_description.write().help_section = inspection.help.unwrap_or_default();
                         },
                         BackendResponse::SingularityInfo(_container, None) => {println!("Singularity Inspection failed");},
                         msg => {
@@ -11,6 +11,8 @@ mod generic_observer_page;
 mod remote_dir_test;
 mod project_overview_page;
 mod labbook_page;
+mod job_history_page;
+mod container_shell_page;
 //mod project_page2;
 
 
@@ -25,6 +27,8 @@ pub use generic_observer_page::*;
 pub use remote_dir_test::*;
 pub use project_overview_page::*;
 pub use labbook_page::*;
+pub use job_history_page::*;
+pub use container_shell_page::*;
 //pub use project_page2::*;
 
 
@@ -36,7 +36,7 @@ new_key_type! { struct ContainerKey; }
 
 #[derive(Eq, PartialEq, Hash, Debug, Clone)]
 pub struct ContainerId {
-    pub id: String, // could be cryptographic hash of Container instead, as soo as implemented
+    pub id: String, // the content digest (backend::ContainerDigest) once containers come from the persistent state
 }
 
 #[derive(Eq, PartialEq, Hash, Debug, Clone)]